@@ -0,0 +1,56 @@
+#![doc = r"
+    Aspen Halls pack validator, checks a pack folder for broken definitions,
+    missing registry keys/files and invalid dungeon rooms without starting the game.
+
+    usage: `cargo run --bin aspen-validate assets/packs/asha`
+
+    `--write-schemas assets/schemas` writes json schemas for actor definition files
+"]
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+
+/// checks Aspen Halls pack folders
+#[derive(Debug, Parser)]
+#[command(name = "aspen-validate")]
+struct ValidateArgs {
+    /// pack folders too check, usually `assets/packs/$PACK`
+    #[arg(required_unless_present = "write_schemas")]
+    pack_dirs: Vec<PathBuf>,
+    /// write actor definition json schemas too this folder
+    #[arg(long, value_name = "DIR")]
+    write_schemas: Option<PathBuf>,
+}
+
+/// writes requested schemas, validates each pack folder and fails if anything errored
+fn main() -> ExitCode {
+    let args = ValidateArgs::parse();
+
+    let mut failed = false;
+    if let Some(schema_dir) = &args.write_schemas {
+        match aspenlib::write_definition_schemas(schema_dir) {
+            Ok(written) => {
+                for path in written {
+                    println!("wrote {}", path.display());
+                }
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                failed = true;
+            }
+        }
+    }
+
+    for pack_dir in &args.pack_dirs {
+        let report = aspenlib::validate_pack(pack_dir);
+        println!("{report}\n");
+        failed |= !report.is_ok();
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -10,7 +10,10 @@ use crate::{
             components::{CharacterMoveState, CharacterType},
         },
         components::{ActorColliderType, TimeToLive},
-        items::weapons::components::{AttackDamage, WeaponDescriptor, WeaponHolder},
+        items::{
+            components::ItemFunction,
+            weapons::components::{AttackDamage, WeaponDescriptor, WeaponHolder},
+        },
     },
     loading::registry::RegistryIdentifier,
 };
@@ -61,6 +64,25 @@ pub struct WeaponBundle {
     pub rigidbody_bundle: RigidBodyBundle,
 }
 
+/// bundle for spawning armor, trinkets and food
+#[derive(Bundle, Reflect, Clone)]
+pub struct ItemBundle {
+    /// item name
+    pub name: Name,
+    /// accesor for item definition
+    pub identifier: RegistryIdentifier,
+    /// what this item does when picked up
+    pub function: ItemFunction,
+    /// stats applied too carrier
+    pub stats: EquipmentStats,
+    /// sprite for item
+    #[reflect(ignore)]
+    pub sprite: AnimatedSpriteBundle,
+    /// item physics
+    #[reflect(ignore)]
+    pub rigidbody_bundle: RigidBodyBundle,
+}
+
 /// bundle too spawn projectiles
 #[derive(Bundle)]
 pub struct ProjectileBundle {
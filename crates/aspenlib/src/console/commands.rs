@@ -2,7 +2,10 @@ use bevy::math::Vec2;
 use bevy_console::ConsoleCommand;
 use clap::{Error, Parser};
 
-use crate::loading::registry::RegistryIdentifier;
+use crate::loading::{
+    custom_assets::actor_definitions::{DefinitionFormat, ScaffoldKind},
+    registry::RegistryIdentifier,
+};
 
 ///  spawns requested actor amount of times
 #[derive(Debug, ConsoleCommand, Parser)]
@@ -33,6 +36,30 @@ pub struct TeleportCharacterCommand {
     pub who: Option<CommandTarget>,
 }
 
+/// writes json schemas for actor definition files
+#[derive(ConsoleCommand, Parser)]
+#[command(name = "schema")]
+pub struct ExportSchemaCommand {
+    /// folder too write schemas too, defaults too `assets/schemas`
+    pub dir: Option<String>,
+}
+
+/// writes a new actor definition into a pack
+#[derive(ConsoleCommand, Parser)]
+#[command(name = "scaffold")]
+pub struct ScaffoldActorCommand {
+    /// kind of actor: hero, creep, boss, critter, weapon, armor, trinket or food
+    pub kind: ScaffoldKind,
+    /// actor name, identifier is created from this
+    pub name: String,
+    /// pack folder name in `assets/packs`
+    #[arg(long, default_value = "asha")]
+    pub pack: String,
+    /// definition file format, toml or ron
+    #[arg(long, default_value = "toml")]
+    pub format: DefinitionFormat,
+}
+
 //######## COMMAND ARGS ########//
 /// x,y location for comand
 #[derive(Debug, Clone, Copy)]
@@ -24,9 +24,9 @@ impl Plugin for QuakeConPlugin {
                 ..default()
             })
             .add_console_command::<commands::SpawnActorCommand, _>(systems::spawn_command)
-            .add_console_command::<commands::TeleportCharacterCommand, _>(
-                systems::teleport_command,
-            );
+            .add_console_command::<commands::TeleportCharacterCommand, _>(systems::teleport_command)
+            .add_console_command::<commands::ExportSchemaCommand, _>(systems::schema_command)
+            .add_console_command::<commands::ScaffoldActorCommand, _>(systems::scaffold_command);
     }
 }
 
@@ -4,16 +4,24 @@ use bevy_console::ConsoleCommand;
 use crate::{
     console::{
         commands::{CommandSpawnType, CommandTarget},
-        commands::{SpawnActorCommand, TeleportCharacterCommand},
+        commands::{
+            ExportSchemaCommand, ScaffoldActorCommand, SpawnActorCommand, TeleportCharacterCommand,
+        },
     },
     game::{
         characters::{
-            components::CharacterMoveState, player::PlayerSelectedHero, EventSpawnCharacter,
+            components::{CharacterMoveState, CharacterType},
+            player::PlayerSelectedHero,
+            EventSpawnCharacter,
         },
         game_world::components::{ActorTeleportEvent, TpTriggerEffect},
-        items::EventSpawnItem,
+        items::{components::ItemType, EventSpawnItem},
+    },
+    loading::{
+        custom_assets::actor_definitions::scaffold_actor_definition,
+        registry::ActorRegistry,
+        schema::{write_definition_schemas, DEFAULT_SCHEMA_DIR},
     },
-    loading::registry::ActorRegistry,
 };
 
 /// interprets `SpawnCommand` from console and sends `SpawnActorEvent`
@@ -54,12 +62,13 @@ pub fn spawn_command(
 
     match spawn_type {
         CommandSpawnType::Item => {
-            let item_reg = &registry.items;
-            if item_reg.weapons.contains_key(&identifier) {
-                spawn.reply("got weapon item");
-            } else {
-                spawn.reply_failed("item did not exist in registry");
-                return;
+            match registry.get_item_type(&identifier) {
+                Some(ItemType::Weapon) => spawn.reply("got weapon item"),
+                Some(item_type) => spawn.reply(format!("got {item_type:?} item")),
+                None => {
+                    spawn.reply_failed("item did not exist in registry");
+                    return;
+                }
             }
 
             spawn.reply_ok("Spawning item");
@@ -70,16 +79,15 @@ pub fn spawn_command(
             });
         }
         CommandSpawnType::Npc => {
-            let char_reg = &registry.characters;
-            if char_reg.creeps.contains_key(&identifier) {
-                spawn.reply("got creep");
-            } else if char_reg.bosses.contains_key(&identifier) {
-                spawn.reply("got boss");
-            } else if char_reg.heroes.contains_key(&identifier) {
-                spawn.reply("got hero");
-            } else {
-                spawn.reply_failed("character did not exist in registry");
-                return;
+            match registry.get_character_type(&identifier) {
+                Some(CharacterType::Creep) => spawn.reply("got creep"),
+                Some(CharacterType::Boss) => spawn.reply("got boss"),
+                Some(CharacterType::Hero) => spawn.reply("got hero"),
+                Some(character_type) => spawn.reply(format!("got {character_type:?}")),
+                None => {
+                    spawn.reply_failed("character did not exist in registry");
+                    return;
+                }
             };
 
             spawn.reply_ok("Spawning character");
@@ -174,3 +182,45 @@ pub fn teleport_command(
         }
     }
 }
+
+/// writes definition file json schemas too requested folder
+pub fn schema_command(mut schema: ConsoleCommand<ExportSchemaCommand>) {
+    let Some(Ok(ExportSchemaCommand { dir })) = schema.take() else {
+        return;
+    };
+
+    let dir = dir.unwrap_or_else(|| DEFAULT_SCHEMA_DIR.to_string());
+    match write_definition_schemas(std::path::Path::new(&dir)) {
+        Ok(written) => {
+            for path in &written {
+                schema.reply(format!("wrote {}", path.display()));
+            }
+            schema.reply_ok(format!("wrote {} schemas too {dir}", written.len()));
+        }
+        Err(e) => schema.reply_failed(e),
+    }
+}
+
+/// writes a new actor definition folder into requested pack
+pub fn scaffold_command(mut scaffold: ConsoleCommand<ScaffoldActorCommand>) {
+    let Some(Ok(ScaffoldActorCommand {
+        kind,
+        name,
+        pack,
+        format,
+    })) = scaffold.take()
+    else {
+        return;
+    };
+
+    let pack_dir = std::path::Path::new("assets/packs").join(&pack);
+    match scaffold_actor_definition(&pack_dir, kind, &name, format) {
+        Ok(path) => {
+            scaffold.reply(format!("wrote {}", path.display()));
+            scaffold.reply_ok(format!(
+                "add it too `actor_definitions` in {pack}/pack.registry too load it"
+            ));
+        }
+        Err(e) => scaffold.reply_failed(e),
+    }
+}
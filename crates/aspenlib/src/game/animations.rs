@@ -6,7 +6,10 @@ use bevy_asepritesheet::{
 use bevy_rapier2d::prelude::Velocity;
 
 use crate::{
-    game::characters::components::{CharacterMoveState, CurrentMovement, MoveDirection},
+    game::{
+        characters::components::{CharacterMoveState, CurrentMovement, MoveDirection},
+        combat::Dying,
+    },
     utilities::vector_to_cardinal_direction,
 };
 
@@ -47,8 +50,13 @@ impl Plugin for AnimationsPlugin {
         app.add_event::<EventAnimationChange>();
         app.add_systems(
             Update,
-            (handle_animation_changes, change_character_animations),
+            (
+                handle_animation_changes,
+                change_character_animations,
+                animate_dying_characters,
+            ),
         );
+        app.add_systems(PostUpdate, reset_revived_characters);
     }
 }
 
@@ -107,6 +115,29 @@ fn change_character_animations(
     }
 }
 
+/// tips over and fades out characters that are `Dying`
+fn animate_dying_characters(mut dying: Query<(&Dying, &mut Sprite, &mut Transform)>) {
+    for (dying, mut sprite, mut transform) in &mut dying {
+        let progress = dying.fraction();
+        sprite.color.set_alpha(1.0 - progress);
+        transform.rotation = Quat::from_rotation_z(progress * std::f32::consts::FRAC_PI_2);
+    }
+}
+
+/// undoes the death animation for characters that stopped `Dying`
+fn reset_revived_characters(
+    mut revived: RemovedComponents<Dying>,
+    mut characters: Query<(&mut Sprite, &mut Transform)>,
+) {
+    for character in revived.read() {
+        let Ok((mut sprite, mut transform)) = characters.get_mut(character) else {
+            continue;
+        };
+        sprite.color.set_alpha(1.0);
+        transform.rotation = Quat::IDENTITY;
+    }
+}
+
 /// updates actors animations
 fn handle_animation_changes(
     mut change_events: EventReader<EventAnimationChange>,
@@ -7,13 +7,33 @@ use std::{
 };
 
 use bevy::{
+    app::{App, Plugin, Update},
     ecs::{bundle::Bundle, component::Component, entity::Entity, reflect::ReflectComponent},
     log::warn,
-    prelude::{Added, Changed, Children, HierarchyQueryExt, Parent, Query, With},
+    prelude::{
+        in_state, Added, Changed, Children, HierarchyQueryExt, IntoSystemConfigs, Parent, Query,
+        Res, With,
+    },
     reflect::Reflect,
+    time::Time,
 };
 
-use crate::consts::TILE_SIZE;
+use crate::{consts::TILE_SIZE, register_types, AppState};
+
+/// syncs character stats with equipment and effects
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        register_types!(app, [EffectQueue, DamageQueue]);
+        app.add_systems(
+            Update,
+            (sync_character_stats, update_effect_queues)
+                .chain()
+                .run_if(in_state(AppState::PlayingGame)),
+        );
+    }
+}
 
 /// how many points of health per stamina point
 pub const HP_PER_STAMINA: i32 = 4;
@@ -51,7 +71,7 @@ pub fn sync_character_stats(
             let equipment_attrs_total: Attributes = equipment_total_attrs.into_iter().sum();
 
             stats.equipment = equipment_attrs_total;
-            stats.current = stats.equipment + stats.base;
+            stats.recalculate();
         }
     }
 
@@ -62,10 +82,76 @@ pub fn sync_character_stats(
             actor_stat.base = Attributes::CREEP_DEFAULT;
             actor_stat.added = Attributes::ZERO;
             actor_stat.equipment = Attributes::ZERO;
+            actor_stat.effects = Attributes::ZERO;
         }
     }
 }
 
+/// applies instant and over time effects and removes finished ones.
+///
+/// attribute effects are summed into `EffectQueue.current` and added too character stats
+pub fn update_effect_queues(
+    time: Res<Time>,
+    mut characters: Query<(&mut EffectQueue, &mut CharacterStats)>,
+) {
+    let delta = time.delta_seconds();
+    for (mut queue, mut stats) in &mut characters {
+        if queue.amount == 0 && queue.current.is_all_zero() {
+            continue;
+        }
+        let attrs = stats.base + stats.equipment + stats.added;
+        let mut effect_attrs = Attributes::ZERO;
+
+        let queue = &mut *queue;
+        for effects in [&mut queue.buffs, &mut queue.debuffs] {
+            effects.retain_mut(|effect| {
+                let amount = effect.amount(effect.target.base_value(&attrs));
+                match effect.duration {
+                    EffectDuration::Instant => {
+                        // instant attribute effects are permanent rewards
+                        let mut added = stats.added;
+                        stats.apply_effect_amount(effect.target, amount, &mut added);
+                        stats.added = added;
+                        false
+                    }
+                    EffectDuration::OverTime(seconds) => {
+                        let step = delta.min(seconds - effect.elapsed).max(0.0);
+                        if effect.target.is_attribute() {
+                            effect.target.add_to(&mut effect_attrs, amount);
+                        } else if seconds > 0.0 {
+                            stats.apply_effect_amount(
+                                effect.target,
+                                amount * (step / seconds),
+                                &mut effect_attrs,
+                            );
+                        }
+                        effect.elapsed += delta;
+                        effect.elapsed < seconds
+                    }
+                    EffectDuration::Infinite => {
+                        if effect.target.is_attribute() {
+                            effect.target.add_to(&mut effect_attrs, amount);
+                        } else {
+                            // infinite health/mana effects are applied per second
+                            stats.apply_effect_amount(
+                                effect.target,
+                                amount * delta,
+                                &mut effect_attrs,
+                            );
+                        }
+                        true
+                    }
+                }
+            });
+        }
+
+        queue.amount = (queue.buffs.len() + queue.debuffs.len()) as u32;
+        queue.current = effect_attrs;
+        stats.effects = effect_attrs;
+        stats.recalculate();
+    }
+}
+
 /// stats related components for characters
 #[derive(Debug, Bundle, Clone, Reflect)]
 pub struct CharacterStatBundle {
@@ -77,7 +163,6 @@ pub struct CharacterStatBundle {
     damage: DamageQueue,
 }
 
-//TODO: track id of "effect"
 /// modifiers too player stats
 /// can be spell modifiers, curses, pretty much anything
 #[derive(Debug, Component, Clone, Reflect)]
@@ -107,7 +192,7 @@ pub struct DamageQueue {
 
 //TODO: make a "BuffQueue" of Buff
 /// stats for npcs or players
-#[derive(Debug, Component, Reflect, Clone, Copy)]
+#[derive(Debug, Component, Reflect, Clone, Copy, serde::Deserialize, serde::Serialize)]
 pub struct CharacterStats {
     /// current actor health
     pub health: f32,
@@ -123,8 +208,14 @@ pub struct CharacterStats {
     equipment: Attributes,
     /// attributes assigned at spawn
     base: Attributes,
-    /// attributes added from levels/perks/rewards/buffs
+    /// attributes added from levels/perks/rewards
     added: Attributes,
+    /// attributes added from `EffectQueue`
+    effects: Attributes,
+    /// difficulty scale applied too max hp
+    health_scale: f32,
+    /// difficulty scale applied too speed
+    speed_scale: f32,
 }
 
 //TODO: reuse "BuffQueue" of Buff for weapon upgrade system
@@ -152,7 +243,17 @@ pub struct ProjectileStats {
 }
 
 /// damage amounts
-#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub struct Damage {
     /// damage too apply
     pub physical: PhysicalDamage,
@@ -161,7 +262,17 @@ pub struct Damage {
 }
 
 /// damage applied directly too characters health
-#[derive(Debug, Clone, Copy, PartialEq, Default, Reflect, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Default,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub struct PhysicalDamage(pub f32);
 
 //TODO:
@@ -172,7 +283,17 @@ pub struct PhysicalDamage(pub f32);
 // water: idek
 // air: character should randomly get a burst of uncontrollable speed
 /// different elemental buffer damage amounts that attacks/actions can apply
-#[derive(Debug, Clone, Copy, PartialEq, Reflect, Default, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Reflect,
+    Default,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub enum ElementalEffect {
     /// no elemental effect
     #[default]
@@ -203,20 +324,46 @@ pub struct ElementalBuffers {
 }
 
 /// modifier applied too player stats
-#[derive(Debug, Clone, Reflect, PartialEq)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Reflect,
+    PartialEq,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub struct Effect {
     /// duration of this effect
-    duration: EffectDuration,
+    pub duration: EffectDuration,
     /// what this effect does
-    apply_type: EffectType,
+    pub apply_type: EffectType,
+    /// which stat this effect changes
+    pub target: EffectTarget,
+    /// entity that applied this effect, effects from carried items are removed when dropped
+    #[serde(skip)]
+    pub source: Option<Entity>,
+    /// seconds this effect has been applied
+    #[serde(skip)]
+    elapsed: f32,
 }
 
 /// effects value and how it is applied
-#[derive(Debug, Clone, PartialEq, Reflect)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub enum EffectType {
-    // TODO: impl buff modifiers.
-    // this is multiplication
     /// SIGNED percentage modifiers. +5% -10% +11.5% etc
+    ///
+    /// percentage of max hp/mana for health and mana effects
     PercentModifier(f32),
     /// SIGNED values added too stat.
     ///
@@ -225,18 +372,156 @@ pub enum EffectType {
 }
 
 /// how long should this effect last
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Reflect)]
-enum EffectDuration {
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
+pub enum EffectDuration {
     /// effect never wears off unless removed or entitity dies
+    ///
+    /// health and mana effects are applied every second
     Infinite,
-    /// effects value is applied over total duration
-    OverTime(Duration),
+    /// effects value is applied over total duration in seconds
+    ///
+    /// attribute effects last for the duration
+    OverTime(f32),
     /// all of this effect is applied instantly
     Instant,
 }
 
+/// stat an effect changes
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
+pub enum EffectTarget {
+    /// current health, heals or hurts
+    Health,
+    /// current mana
+    Mana,
+    /// maximum health
+    MaxHp,
+    /// maximum mana
+    MaxMana,
+    /// health regen
+    HpRegen,
+    /// mana regen
+    ManaRegen,
+    /// movement speed
+    BaseSpeed,
+    /// damage reduction
+    Armor,
+    /// damage attribute
+    Strength,
+    /// speed attribute
+    Agility,
+    /// mana attribute
+    Intelligence,
+}
+
+impl Effect {
+    /// creates effect from parts
+    pub const fn new(
+        duration: EffectDuration,
+        apply_type: EffectType,
+        target: EffectTarget,
+    ) -> Self {
+        Self {
+            duration,
+            apply_type,
+            target,
+            source: None,
+            elapsed: 0.0,
+        }
+    }
+
+    /// copy of this effect applied by `source`
+    #[must_use]
+    pub const fn from_source(self, source: Entity) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    /// true if this effect raises its target
+    pub fn is_buff(&self) -> bool {
+        match self.apply_type {
+            EffectType::PercentModifier(value) | EffectType::ValueModifier(value) => value >= 0.0,
+        }
+    }
+
+    /// how much this effect changes a stat with `base` value
+    fn amount(&self, base: f32) -> f32 {
+        match self.apply_type {
+            EffectType::PercentModifier(percent) => base * (percent / 100.0),
+            EffectType::ValueModifier(value) => value,
+        }
+    }
+}
+
+impl EffectTarget {
+    /// false for health and mana, they are not `Attributes`
+    const fn is_attribute(self) -> bool {
+        !matches!(self, Self::Health | Self::Mana)
+    }
+
+    /// value percentage effects are calculated from
+    const fn base_value(self, attrs: &Attributes) -> f32 {
+        match self {
+            Self::Health | Self::MaxHp => attrs.max_hp,
+            Self::Mana | Self::MaxMana => attrs.max_mana,
+            Self::HpRegen => attrs.hp_regen,
+            Self::ManaRegen => attrs.mana_regen,
+            Self::BaseSpeed => attrs.base_speed,
+            Self::Armor => attrs.armor as f32,
+            Self::Strength => attrs.strength as f32,
+            Self::Agility => attrs.agility as f32,
+            Self::Intelligence => attrs.intelligence as f32,
+        }
+    }
+
+    /// adds `amount` too the attribute this target changes
+    fn add_to(self, attrs: &mut Attributes, amount: f32) {
+        match self {
+            Self::Health | Self::Mana => {}
+            Self::MaxHp => attrs.max_hp += amount,
+            Self::MaxMana => attrs.max_mana += amount,
+            Self::HpRegen => attrs.hp_regen += amount,
+            Self::ManaRegen => attrs.mana_regen += amount,
+            Self::BaseSpeed => attrs.base_speed += amount,
+            Self::Armor => attrs.armor += amount as i32,
+            Self::Strength => attrs.strength += amount as i32,
+            Self::Agility => attrs.agility += amount as i32,
+            Self::Intelligence => attrs.intelligence += amount as i32,
+        }
+    }
+}
+
 /// stats updated from equipment and "base stats"
-#[derive(Debug, Reflect, Component, Clone, Copy, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Debug,
+    Reflect,
+    Component,
+    Clone,
+    Copy,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub struct Attributes {
     /// how much damage can this actor take
     pub max_hp: f32,
@@ -276,6 +561,50 @@ impl Default for EffectQueue {
     }
 }
 
+impl EffectQueue {
+    /// adds effect too buffs or debuffs, returns false if the queue is full
+    pub fn push_effect(&mut self, effect: Effect) -> bool {
+        if self.amount >= self.max {
+            return false;
+        }
+        if effect.is_buff() {
+            self.buffs.push_back(effect);
+        } else {
+            self.debuffs.push_back(effect);
+        }
+        self.amount += 1;
+        true
+    }
+
+    /// removes every effect applied by `source`
+    pub fn remove_source(&mut self, source: Entity) {
+        self.buffs.retain(|f| f.source != Some(source));
+        self.debuffs.retain(|f| f.source != Some(source));
+        self.amount = (self.buffs.len() + self.debuffs.len()) as u32;
+    }
+
+    /// iterates buffs then debuffs
+    pub fn iter(&self) -> impl Iterator<Item = &Effect> {
+        self.buffs.iter().chain(self.debuffs.iter())
+    }
+}
+
+impl Damage {
+    /// damage with physical and elemental amounts multiplied by `scale`
+    pub const fn scaled(self, scale: f32) -> Self {
+        Self {
+            physical: PhysicalDamage(self.physical.0 * scale),
+            elemental: match self.elemental {
+                ElementalEffect::None => ElementalEffect::None,
+                ElementalEffect::Earth(v) => ElementalEffect::Earth(v * scale),
+                ElementalEffect::Fire(v) => ElementalEffect::Fire(v * scale),
+                ElementalEffect::Water(v) => ElementalEffect::Water(v * scale),
+                ElementalEffect::Air(v) => ElementalEffect::Air(v * scale),
+            },
+        }
+    }
+}
+
 impl DamageQueue {
     /// default empty damage queue
     pub const DEFAULT: Self = Self {
@@ -407,6 +736,51 @@ impl CharacterStats {
         self.equipment_amount = amt;
     }
 
+    /// recalculates final stats from base, equipment, added and effect attributes
+    pub fn recalculate(&mut self) {
+        self.current = self.base + self.equipment + self.added + self.effects;
+        self.current.max_hp *= self.health_scale;
+        self.current.base_speed *= self.speed_scale;
+        self.health = self.health.min(self.current.max_hp);
+        self.mana = self.mana.min(self.current.max_mana);
+    }
+
+    /// adds `amount` too health, mana or `effect_attrs` depending on `target`
+    fn apply_effect_amount(
+        &mut self,
+        target: EffectTarget,
+        amount: f32,
+        effect_attrs: &mut Attributes,
+    ) {
+        match target {
+            EffectTarget::Health => {
+                self.health = (self.health + amount).clamp(0.0, self.current.max_hp);
+            }
+            EffectTarget::Mana => {
+                self.mana = (self.mana + amount).clamp(0.0, self.current.max_mana);
+            }
+            _ => target.add_to(effect_attrs, amount),
+        }
+    }
+
+    /// sets difficulty scales for max hp and speed, keeps the current health percentage
+    pub fn set_difficulty_scales(&mut self, health_scale: f32, speed_scale: f32) {
+        let health_fraction = if self.current.max_hp > 0.0 {
+            self.health / self.current.max_hp
+        } else {
+            1.0
+        };
+        self.health_scale = health_scale;
+        self.speed_scale = speed_scale;
+        self.recalculate();
+        self.health = self.current.max_hp * health_fraction;
+    }
+
+    /// difficulty scale applied too speed, used for movement not based on `base_speed`
+    pub const fn speed_scale(&self) -> f32 {
+        self.speed_scale
+    }
+
     /// returns ref too final stat values
     pub const fn attrs(&self) -> &Attributes {
         &self.current
@@ -433,7 +807,10 @@ impl CharacterStats {
             current: new,
             equipment: Attributes::ZERO,
             added: Attributes::ZERO,
+            effects: Attributes::ZERO,
             element_buffer: ElementalBuffers::default(),
+            health_scale: 1.0,
+            speed_scale: 1.0,
         }
     }
 }
@@ -449,9 +826,12 @@ impl Default for CharacterStats {
             current: attrs,
             base: attrs,
             added: Attributes::ZERO,
+            effects: Attributes::ZERO,
             equipment: Attributes::ZERO,
             equipment_amount: 0,
             element_buffer: ElementalBuffers::default(),
+            health_scale: 1.0,
+            speed_scale: 1.0,
         }
     }
 }
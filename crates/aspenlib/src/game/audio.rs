@@ -9,7 +9,16 @@ use bevy_kira_audio::{
 use bevy_rapier2d::dynamics::Velocity;
 
 use crate::{
-    consts::MIN_VELOCITY, game::characters::{components::{CharacterMoveState, CurrentMovement}, player::PlayerSelectedHero}, loading::{assets::AspenAudioHandles, config::SoundSettings, splashscreen::MainCamera}, register_types, AppState
+    consts::MIN_VELOCITY,
+    game::{
+        characters::{
+            components::{CharacterMoveState, CurrentMovement},
+            player::PlayerSelectedHero,
+        },
+        game_world::dungeonator_v2::components::Dungeon,
+    },
+    loading::{assets::AspenAudioHandles, config::SoundSettings, splashscreen::MainCamera},
+    register_types, AppState,
 };
 
 /// OST music is played on this channel.
@@ -66,6 +75,7 @@ impl Plugin for AudioPlugin {
                 prepare_actor_spatial_sound,
                 actor_footstep_sound_system.run_if(in_state(AppState::PlayingGame)),
                 play_background_audio.run_if(in_state(AppState::StartMenu).and_then(run_once())),
+                play_dungeon_music.run_if(in_state(AppState::PlayingGame)),
             )
                 .run_if(resource_exists::<AspenAudioHandles>),
         )
@@ -95,6 +105,36 @@ fn play_background_audio(
     audio.play(audio_assets.game_soundtrack.clone()).looped();
 }
 
+/// switches the music too the new dungeons `music`, or back too the game soundtrack
+/// once the dungeon is removed
+fn play_dungeon_music(
+    asset_server: Res<AssetServer>,
+    audio_assets: Res<AspenAudioHandles>,
+    audio: Res<AudioChannel<MusicSoundChannel>>,
+    dungeons: Query<Ref<Dungeon>>,
+    mut removed_dungeons: RemovedComponents<Dungeon>,
+    mut playing: Local<Option<String>>,
+) {
+    let dungeon_removed = removed_dungeons.read().count() > 0;
+    let music = match dungeons.get_single() {
+        Ok(dungeon) if dungeon.is_added() => dungeon.settings.music.clone(),
+        Err(_) if dungeon_removed && dungeons.is_empty() => None,
+        _ => return,
+    };
+    if *playing == music {
+        return;
+    }
+
+    let track = music.as_ref().map_or_else(
+        || audio_assets.game_soundtrack.clone(),
+        |path| asset_server.load(path.clone()),
+    );
+    info!("playing dungeon music {music:?}");
+    *playing = music;
+    audio.stop();
+    audio.play(track).looped();
+}
+
 use bevy_kira_audio::prelude::AudioReceiver;
 
 /// applies sound data mapps and a spacial emitter for actors that dont already have emitters
@@ -86,6 +86,7 @@ pub struct AIWanderAction;
     Eq,
     serde::Deserialize,
     serde::Serialize,
+    schemars::JsonSchema,
     Reflect,
     Component,
 )]
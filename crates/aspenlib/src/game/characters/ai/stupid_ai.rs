@@ -19,6 +19,7 @@ use rand::{thread_rng, Rng};
 use crate::{
     consts::default_actor_collider,
     game::{
+        attributes_stats::CharacterStats,
         characters::{
             ai::components::{
                 AIChaseAction, AICombatAggroConfig, AIWanderAction, AIWanderConfig, AttackScorer,
@@ -159,7 +160,12 @@ fn stupid_ai_aggro_manager(
 /// handles enemy's that can chase
 fn chase_action(
     player_query: Query<&Transform, With<PlayerSelectedHero>>,
-    mut enemy_query: Query<(&Transform, &mut Velocity, &AICombatAggroConfig)>,
+    mut enemy_query: Query<(
+        &Transform,
+        &mut Velocity,
+        &AICombatAggroConfig,
+        &CharacterStats,
+    )>,
     mut chasing_enemies: Query<(&Actor, &mut ActionState), With<AIChaseAction>>,
 ) {
     let Ok(player_transform) = player_query.get_single() else {
@@ -168,7 +174,9 @@ fn chase_action(
     };
 
     for (Actor(actor), mut state) in &mut chasing_enemies {
-        if let Ok((enemy_transform, mut velocity, combat_cfg)) = enemy_query.get_mut(*actor) {
+        if let Ok((enemy_transform, mut velocity, combat_cfg, stats)) = enemy_query.get_mut(*actor)
+        {
+            let speed = 50. * stats.speed_scale();
             let player_pos = player_transform.translation.truncate();
             let enemy_pos = enemy_transform.translation.truncate();
             let direction = (player_pos - enemy_pos).normalize_or_zero();
@@ -198,11 +206,11 @@ fn chase_action(
                     if !actor_in_personal_space {
                         // move towards the player if player is close enough
                         trace!("actor not in shoot range, moving closer");
-                        *velocity = Velocity::linear(direction * 50.);
+                        *velocity = Velocity::linear(direction * speed);
                     } else if actor_in_personal_space {
                         // move away from player if too close
                         trace!("actor in personal space, moving away");
-                        *velocity = Velocity::linear(-direction * 50.);
+                        *velocity = Velocity::linear(-direction * speed);
                     }
                 }
                 ActionState::Cancelled => {
@@ -275,14 +283,20 @@ fn attack_action(
 
 /// handles enemy's that are doing the wander action
 fn wander_action(
-    mut enemy_query: Query<(&Transform, &mut Velocity, &mut Sprite, &mut AIWanderConfig)>,
+    mut enemy_query: Query<(
+        &Transform,
+        &mut Velocity,
+        &mut Sprite,
+        &mut AIWanderConfig,
+        &CharacterStats,
+    )>,
     mut thinker_query: Query<(&Actor, &mut ActionState), With<AIWanderAction>>,
     rapier_context: Query<&RapierContext>,
 ) {
     let rapier_context = rapier_context.single();
 
     for (Actor(actor), mut state) in &mut thinker_query {
-        if let Ok((enemy_transform, mut velocity, _sprite, mut can_meander_tag)) =
+        if let Ok((enemy_transform, mut velocity, _sprite, mut can_meander_tag, stats)) =
             enemy_query.get_mut(*actor)
         {
             let spawn_pos = can_meander_tag
@@ -348,7 +362,7 @@ fn wander_action(
                         can_meander_tag.wander_target = None;
                         *state = ActionState::Requested;
                     } else {
-                        *velocity = Velocity::linear(direction * 100.);
+                        *velocity = Velocity::linear(direction * 100. * stats.speed_scale());
                     }
                 }
                 ActionState::Success | ActionState::Failure => {
@@ -37,26 +37,27 @@ pub mod utils {
             characters::boss::EventSpawnBoss, components::ActorColliderType,
             game_world::components::CharacterSpawner,
         },
-        loading::{custom_assets::actor_definitions::CharacterDefinition, registry::ActorRegistry},
+        loading::registry::ActorRegistry,
     };
 
     /// spawns creep character in world
     /// if requested by spawner, adds too spawner list
     pub fn spawn_boss(
         registry: Res<ActorRegistry>,
-        char_assets: Res<Assets<CharacterDefinition>>,
         mut commands: Commands,
         mut boss_spawns: EventReader<EventSpawnBoss>,
         mut spawners: Query<&mut CharacterSpawner>,
     ) {
         for spawn_event in boss_spawns.read() {
-            let (_, char_def) = char_assets
-                .iter()
-                .find(|(_, asset)| asset.actor.identifier == spawn_event.actor_id)
-                .expect("Spawned characters asset definition did not exist");
+            let Some(char_def) = registry.get_definition(&spawn_event.actor_id) else {
+                error!(
+                    "spawned characters definition did not exist: {:?}",
+                    spawn_event.actor_id
+                );
+                return;
+            };
 
-            let Some(mut character) = registry.characters.get_character(&spawn_event.actor_id)
-            else {
+            let Some(mut character) = registry.get_character(&spawn_event.actor_id) else {
                 error!(
                     "could not get CharacterBundle from character registry: {:?}",
                     spawn_event.actor_id
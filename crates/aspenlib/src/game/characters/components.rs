@@ -17,6 +17,7 @@ use crate::loading::registry::RegistryIdentifier;
     Clone,
     PartialEq,
     Eq,
+    Hash,
     serde::Deserialize,
     serde::Serialize,
 )]
@@ -46,6 +47,27 @@ pub enum CharacterType {
     Shopkeep,
 }
 
+impl CharacterType {
+    /// true for characters hostile too the player
+    pub const fn is_enemy(self) -> bool {
+        matches!(
+            self,
+            Self::Boss | Self::MiniBoss | Self::Creep | Self::CreepElite
+        )
+    }
+
+    /// coins dropped when this character is defeated
+    pub const fn coin_value(self) -> u64 {
+        match self {
+            Self::Boss => 25,
+            Self::MiniBoss => 10,
+            Self::CreepElite => 3,
+            Self::Creep => 1,
+            Self::Critter | Self::HeroPet | Self::Hero | Self::Shopkeep => 0,
+        }
+    }
+}
+
 /// character move state and move permissions
 /// current teleport status
 #[derive(Debug, Component, Reflect, Clone, Default)]
@@ -71,8 +93,10 @@ pub struct CharacterInventory {
 }
 
 /// character available item slot
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect, Default)]
-pub struct ItemSlot(u32);
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect, Default, serde::Deserialize, serde::Serialize,
+)]
+pub struct ItemSlot(pub u32);
 
 /// character available action slots
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect, Default)]
@@ -94,7 +118,9 @@ pub enum ActionSlot {
 }
 
 /// weapon slots for character
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect, Default)]
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, Reflect, Default, serde::Deserialize, serde::Serialize,
+)]
 pub enum WeaponSlot {
     #[default]
     /// first slot
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_asepritesheet::sprite::AnimHandle;
 use bevy_rapier2d::dynamics::Velocity;
-use rand::prelude::{thread_rng, Rng};
+use rand::prelude::{thread_rng, Rng, SliceRandom};
 
 use crate::{
     consts::{MIN_VELOCITY, WALK_MODIFIER},
@@ -12,10 +12,17 @@ use crate::{
             boss::EventSpawnBoss,
             components::{CharacterInventory, CharacterMoveState, CharacterType, CurrentMovement},
             creeps::EventSpawnCreep,
+            player::PlayerSelectedHero,
         },
-        game_world::components::CharacterSpawner,
+        game_world::{
+            components::CharacterSpawner,
+            dungeonator_v2::{components::Dungeon, GeneratorState},
+        },
+    },
+    loading::{
+        config::{update_difficulty_settings, DifficultyScales},
+        registry::{ActorRegistry, RegistryIdentifier},
     },
-    loading::registry::{ActorRegistry, RegistryIdentifier},
     register_types,
     utilities::vector_to_pi8,
     AppState,
@@ -54,8 +61,55 @@ impl Plugin for CharactersPlugin {
             (
                 (update_character_move_state,).run_if(in_state(AppState::PlayingGame)),
                 spawn_character_on_event.run_if(on_event::<EventSpawnCharacter>()),
+                scale_spawned_enemies.run_if(in_state(AppState::PlayingGame)),
+                give_enemies_coins.run_if(in_state(AppState::PlayingGame)),
             ),
         );
+        app.add_systems(
+            OnEnter(GeneratorState::LayoutDungeon),
+            scale_player_for_difficulty.after(update_difficulty_settings),
+        );
+    }
+}
+
+/// scales health and speed of newly spawned enemies with `DifficultyScales`
+fn scale_spawned_enemies(
+    difficulty: Res<DifficultyScales>,
+    mut spawned: Query<(&CharacterType, &mut CharacterStats), Added<CharacterStats>>,
+) {
+    for (character_type, mut stats) in &mut spawned {
+        if character_type.is_enemy() {
+            stats
+                .set_difficulty_scales(difficulty.enemy_health_scale, difficulty.enemy_speed_scale);
+        }
+    }
+}
+
+/// gives newly spawned enemies an inventory holding there `CharacterType::coin_value`
+fn give_enemies_coins(
+    mut cmds: Commands,
+    spawned: Query<(Entity, &CharacterType), (Added<CharacterType>, Without<CharacterInventory>)>,
+) {
+    for (enemy, character_type) in &spawned {
+        if character_type.is_enemy() {
+            cmds.entity(enemy).insert(CharacterInventory {
+                coins: character_type.coin_value(),
+                ..default()
+            });
+        }
+    }
+}
+
+/// scales player health and speed with `DifficultyScales` when a new dungeon is laid out
+fn scale_player_for_difficulty(
+    difficulty: Res<DifficultyScales>,
+    mut player: Query<&mut CharacterStats, With<PlayerSelectedHero>>,
+) {
+    for mut stats in &mut player {
+        stats.set_difficulty_scales(
+            difficulty.player_health_scale,
+            difficulty.player_speed_scale,
+        );
     }
 }
 
@@ -84,16 +138,22 @@ pub fn spawn_character_on_event(
     spawners: Query<&CharacterSpawner>,
     global_transforms: Query<&GlobalTransform>,
     registry: Res<ActorRegistry>,
+    dungeon: Query<&Dungeon>,
     mut character_requests: EventReader<EventSpawnCharacter>,
     mut creep_events: EventWriter<EventSpawnCreep>,
     mut boss_events: EventWriter<EventSpawnBoss>,
 ) {
+    let boss_pool = dungeon
+        .get_single()
+        .map_or(&[][..], |f| f.settings.boss_pool.as_slice());
+
     for event in character_requests.read() {
         handle_character_spawn(
             &global_transforms,
             event,
             &registry,
             &spawners,
+            boss_pool,
             &mut creep_events,
             &mut boss_events,
         );
@@ -101,11 +161,14 @@ pub fn spawn_character_on_event(
 }
 
 /// delegates requested charcter spawns based on character type
+///
+/// bosses not in a non empty `boss_pool` are swapped for a random boss from the pool
 fn handle_character_spawn(
     global_transforms: &Query<'_, '_, &GlobalTransform>,
     event: &EventSpawnCharacter,
     registry: &Res<'_, ActorRegistry>,
     spawners: &Query<'_, '_, &CharacterSpawner>,
+    boss_pool: &[RegistryIdentifier],
     creep_events: &mut EventWriter<'_, EventSpawnCreep>,
     boss_events: &mut EventWriter<'_, EventSpawnBoss>,
 ) {
@@ -116,7 +179,7 @@ fn handle_character_spawn(
     let mut rng = thread_rng();
     let spawn_pos = requester_transform.translation().truncate();
 
-    let Some(character_type) = registry.characters.get_character_type(&event.identifier) else {
+    let Some(character_type) = registry.get_character_type(&event.identifier) else {
         error!(
             "requested item did not exist in character registry: {:?}",
             event.identifier
@@ -150,8 +213,17 @@ fn handle_character_spawn(
                     y: spawn_pos.y + random_radius(spawner.spawn_radius),
                 });
 
+            let actor_id = if boss_pool.contains(&event.identifier) {
+                event.identifier.clone()
+            } else {
+                boss_pool
+                    .choose(&mut rng)
+                    .unwrap_or(&event.identifier)
+                    .clone()
+            };
+
             boss_events.send(EventSpawnBoss {
-                actor_id: event.identifier.clone(),
+                actor_id,
                 spawner: event.requester,
                 position: spawn_pos,
             });
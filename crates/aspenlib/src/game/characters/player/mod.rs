@@ -9,11 +9,16 @@ use crate::{
     bundles::ActorColliderBundle,
     consts::{actor_collider, AspenCollisionLayer, ACTOR_PHYSICS_Z_INDEX},
     game::{
-        characters::components::WeaponSlot, components::ActorColliderType,
-        interface::start_menu::StartMenuTag, items::weapons::components::WeaponCarrier,
+        characters::components::{CharacterInventory, CharacterType, WeaponSlot},
+        combat::Dying,
+        components::ActorColliderType,
+        game_world::{suspend::ResumingRun, RegenReason, RegenerateDungeonEvent},
+        interface::{profile_menu::ProfileMenuTag, start_menu::StartMenuTag},
+        items::weapons::components::WeaponCarrier,
     },
     loading::{
-        custom_assets::actor_definitions::CharacterDefinition, registry::RegistryIdentifier,
+        config::launch::LaunchOptions,
+        registry::{ActorRegistry, RegistryIdentifier},
         splashscreen::MainCamera,
     },
     utilities::EntityCreator,
@@ -38,27 +43,52 @@ impl Plugin for PlayerPlugin {
             .add_systems(
                 Update,
                 ((
-                    movement::update_player_velocity,
                     movement::camera_movement_system,
-                    actions::spawn_custom,
-                    actions::player_attack,
-                    actions::equip_closest_weapon,
                     actions::zoom_control,
-                    actions::change_weapon,
-                    actions::aim_weapon,
+                    (
+                        movement::update_player_velocity,
+                        actions::spawn_custom,
+                        actions::player_attack,
+                        actions::equip_closest_weapon,
+                        actions::change_weapon,
+                        actions::aim_weapon,
+                    )
+                        .run_if(player_is_alive),
                 )
                     .run_if(in_state(AppState::PlayingGame)),),
             )
             .add_systems(OnExit(AppState::StartMenu), build_player_from_selected_hero)
+            .add_systems(
+                OnEnter(AppState::StartMenu),
+                check_skip_menu_hero.run_if(skip_menu_requested),
+            )
+            .add_systems(
+                OnEnter(AppState::PlayingGame),
+                skip_menu_start_dungeon.run_if(skip_menu_requested.and_then(run_once())),
+            )
             .add_systems(
                 Update,
-                select_wanted_hero.run_if(
-                    in_state(AppState::StartMenu).and_then(on_event::<SelectThisHeroForPlayer>()),
-                ),
+                (
+                    skip_menu_select_hero
+                        .run_if(in_state(AppState::StartMenu).and_then(skip_menu_requested)),
+                    resume_select_hero.run_if(
+                        in_state(AppState::StartMenu).and_then(resource_exists::<ResumingRun>),
+                    ),
+                    select_wanted_hero.run_if(
+                        in_state(AppState::StartMenu)
+                            .and_then(on_event::<SelectThisHeroForPlayer>()),
+                    ),
+                )
+                    .chain(),
             );
     }
 }
 
+/// player input is locked while the player is `Dying`
+pub fn player_is_alive(dying: Query<(), (With<PlayerSelectedHero>, With<Dying>)>) -> bool {
+    dying.is_empty()
+}
+
 /// hero player has selected for play
 #[derive(Debug, Component)]
 pub struct PlayerSelectedHero;
@@ -76,6 +106,7 @@ impl From<ListenerInput<Pointer<Down>>> for SelectThisHeroForPlayer {
 /// Unlike callback systems, this is a normal system that can be run in parallel with other systems.
 fn select_wanted_hero(
     start_menu_query: Query<&Style, (With<Node>, With<StartMenuTag>)>,
+    profile_menu_query: Query<&Style, (With<Node>, With<ProfileMenuTag>)>,
     mut cmds: Commands,
     mut select_events: EventReader<SelectThisHeroForPlayer>,
     mut camera_query: Query<&mut OrthographicProjection, With<MainCamera>>,
@@ -84,7 +115,11 @@ fn select_wanted_hero(
     let start_menu_style = start_menu_query.single();
     let mut camera_projection = camera_query.single_mut();
 
-    if start_menu_style.display != Display::None {
+    if start_menu_style.display != Display::None
+        || profile_menu_query
+            .iter()
+            .any(|f| f.display != Display::None)
+    {
         return;
     }
 
@@ -102,17 +137,84 @@ fn select_wanted_hero(
     }
 }
 
+/// true if the launcher asked too skip the start menu
+fn skip_menu_requested(launch: Res<LaunchOptions>) -> bool {
+    launch.skip_menu_hero.is_some()
+}
+
+/// falls back too the start menu if the skip menu hero is not a hero
+fn check_skip_menu_hero(mut launch: ResMut<LaunchOptions>, registry: Res<ActorRegistry>) {
+    let Some(wanted) = &launch.skip_menu_hero else {
+        return;
+    };
+    if registry
+        .characters_of_type(CharacterType::Hero)
+        .any(|(id, _)| &id.0 == wanted)
+    {
+        info!("skipping start menu, playing as {wanted}");
+        return;
+    }
+
+    let heroes: Vec<&str> = registry
+        .characters_of_type(CharacterType::Hero)
+        .map(|(id, _)| id.0.as_str())
+        .collect();
+    error!("cant skip start menu, {wanted} is not a hero. heroes: {heroes:?}");
+    launch.skip_menu_hero = None;
+}
+
+/// selects the skip menu hero once the hideout heroes are spawned
+fn skip_menu_select_hero(
+    launch: Res<LaunchOptions>,
+    mut start_menu_query: Query<&mut Style, (With<Node>, With<StartMenuTag>)>,
+    heroes: Query<(Entity, &RegistryIdentifier, &CharacterType), Without<PlayerSelectedHero>>,
+    mut select_events: EventWriter<SelectThisHeroForPlayer>,
+) {
+    let Some((hero, _, _)) = heroes.iter().find(|(_, id, character_type)| {
+        **character_type == CharacterType::Hero && launch.skip_menu_hero.as_ref() == Some(&id.0)
+    }) else {
+        return;
+    };
+
+    start_menu_query.single_mut().display = Display::None;
+    select_events.send(SelectThisHeroForPlayer(hero, ()));
+}
+
+/// selects the hero of a continued suspended run once the hideout heroes are spawned
+fn resume_select_hero(
+    resuming: Res<ResumingRun>,
+    mut start_menu_query: Query<&mut Style, (With<Node>, With<StartMenuTag>)>,
+    heroes: Query<(Entity, &RegistryIdentifier, &CharacterType), Without<PlayerSelectedHero>>,
+    mut select_events: EventWriter<SelectThisHeroForPlayer>,
+) {
+    let Some((hero, _, _)) = heroes.iter().find(|(_, id, character_type)| {
+        **character_type == CharacterType::Hero && **id == resuming.hero
+    }) else {
+        return;
+    };
+
+    start_menu_query.single_mut().display = Display::None;
+    select_events.send(SelectThisHeroForPlayer(hero, ()));
+}
+
+/// starts the first dungeon instead of waiting in the hideout
+fn skip_menu_start_dungeon(mut regen_event: EventWriter<RegenerateDungeonEvent>) {
+    info!("skipping hideout");
+    regen_event.send(RegenerateDungeonEvent {
+        reason: RegenReason::FirstGeneration,
+    });
+}
+
 /// spawns player with no weapons
 pub fn build_player_from_selected_hero(
     mut commands: Commands,
     player_selected_hero: Query<(Entity, &RegistryIdentifier), With<PlayerSelectedHero>>,
-    char_assets: Res<Assets<CharacterDefinition>>,
+    registry: Res<ActorRegistry>,
 ) {
     let (selected_hero, player_registry_identifier) = player_selected_hero.single();
 
-    let (_, char_def) = char_assets
-        .iter()
-        .find(|(_, asset)| asset.actor.identifier == *player_registry_identifier)
+    let char_def = registry
+        .get_definition(player_registry_identifier)
         .expect("Spawned characters asset definition did not exist");
 
     commands
@@ -122,10 +224,13 @@ pub fn build_player_from_selected_hero(
     info!("Finalizing player before game start");
     commands
         .entity(selected_hero)
-        .insert((WeaponCarrier {
-            drawn_slot: None,
-            weapon_slots: hero_weapon_slots(),
-        },))
+        .insert((
+            WeaponCarrier {
+                drawn_slot: None,
+                weapon_slots: hero_weapon_slots(),
+            },
+            CharacterInventory::default(),
+        ))
         .with_children(|child| {
             child.spawn((
                 EntityCreator(selected_hero),
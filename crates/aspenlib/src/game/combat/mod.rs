@@ -1,13 +1,20 @@
-use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy::{ecs::system::SystemParam, prelude::*, time::Stopwatch, utils::HashMap};
 use bevy_rapier2d::{
+    dynamics::Velocity,
     geometry::SolverFlags,
     pipeline::{BevyPhysicsHooks, PairFilterContextView},
 };
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    colors,
+    consts::{ACTOR_Z_INDEX, TILE_SIZE},
     game::{
         attributes_stats::{CharacterStats, DamageQueue},
-        characters::player::PlayerSelectedHero,
+        characters::{
+            components::{CharacterInventory, CharacterType},
+            player::PlayerSelectedHero,
+        },
         combat::unarmed::EventAttackUnarmed,
         game_world::{RegenReason, RegenerateDungeonEvent},
         items::weapons::{
@@ -15,6 +22,7 @@ use crate::{
             EventAttackWeapon,
         },
     },
+    loading::config::DifficultyScales,
     utilities::EntityCreator,
     AppState,
 };
@@ -22,6 +30,12 @@ use crate::{
 /// handles attacks from characters without weapons
 pub mod unarmed;
 
+/// seconds the player death animation plays before the run fails
+const PLAYER_DEATH_TIME: f32 = 1.5;
+
+/// how close the player has too be too collect dropped coins
+const COIN_PICKUP_DISTANCE: f32 = TILE_SIZE;
+
 /// game combat functionality
 pub struct CombatPlugin;
 
@@ -30,8 +44,7 @@ impl Plugin for CombatPlugin {
         app.add_plugins(unarmed::UnArmedPlugin);
 
         app.add_event::<EventRequestAttack>()
-            .insert_resource(CurrentRunInformation::default())
-            .insert_resource(PlayerSaveInformation::default());
+            .insert_resource(CurrentRunInformation::default());
 
         app.add_systems(
             PreUpdate,
@@ -43,6 +56,8 @@ impl Plugin for CombatPlugin {
             (
                 delegate_attack_events.run_if(on_event::<EventRequestAttack>()),
                 handle_death_system,
+                finish_player_death,
+                collect_coin_drops,
             )
                 .run_if(in_state(AppState::PlayingGame)),
         );
@@ -50,10 +65,12 @@ impl Plugin for CombatPlugin {
 }
 
 // TODO: have damaged characters use particle effect or red tint when damaged
-/// applys
+/// applys queued damage, scaled by `DifficultyScales` for who dealt it
 #[allow(clippy::type_complexity)]
 fn apply_damage_system(
     mut game_info: ResMut<CurrentRunInformation>,
+    mut save_info: ResMut<PlayerSaveInformation>,
+    difficulty: Res<DifficultyScales>,
     mut damaged_characters: Query<
         (&mut CharacterStats, Entity, &mut DamageQueue),
         Changed<DamageQueue>,
@@ -63,56 +80,175 @@ fn apply_damage_system(
     for (mut character_stats, character, mut damage_queue) in &mut damaged_characters {
         for damage in damage_queue.iter_queue() {
             if character_stats.get_current_health() <= 0.0 {
-                return;
+                break;
             }
-            if player_controlled.get(character).is_ok() {
+            let damage = if player_controlled.get(character).is_ok() {
+                let damage = damage.scaled(difficulty.enemy_damage_scale);
                 game_info.player_physical_damage_taken += damage.physical.0;
+                damage
             } else {
+                let damage = damage.scaled(difficulty.player_damage_scale);
                 game_info.enemy_physical_damage_taken += damage.physical.0;
-            }
-            character_stats.apply_damage(*damage);
+                save_info.all_time_damage += damage.physical.0;
+                damage
+            };
+            character_stats.apply_damage(damage);
         }
         damage_queue.empty_queue();
     }
 }
 
-/// gathers entitys that have damage and despawns them if have no remaining health
+/// gathers entitys that have damage and handles them if they have no remaining health.
+///
+/// the player starts dying, defeated enemies are counted, drop there coins and are despawned
 #[allow(clippy::type_complexity)]
 fn handle_death_system(
     mut game_info: ResMut<CurrentRunInformation>,
+    mut save_info: ResMut<PlayerSaveInformation>,
     mut cmds: Commands,
-    mut damaged_query: Query<
+    damaged_query: Query<
         (
             Entity,
+            &CharacterStats,
+            &CharacterType,
+            &Transform,
+            Option<&CharacterInventory>,
+            Has<PlayerSelectedHero>,
+        ),
+        (Changed<CharacterStats>, Without<Dying>),
+    >,
+) {
+    for (ent, stats, character_type, transform, inventory, is_player) in &damaged_query {
+        if stats.get_current_health() > 0.0 {
+            continue;
+        }
+
+        if is_player {
+            info!("player died");
+            game_info.player_deaths += 1;
+            save_info.total_deaths += 1;
+            cmds.entity(ent).insert((
+                Dying(Timer::from_seconds(PLAYER_DEATH_TIME, TimerMode::Once)),
+                Velocity::zero(),
+            ));
+            continue;
+        }
+
+        if character_type.is_enemy() {
+            game_info.enemies_deaths += 1;
+            *game_info.enemies_killed.entry(*character_type).or_default() += 1;
+            save_info.total_kills += 1;
+
+            let coins = inventory.map_or(0, |f| f.coins);
+            if coins > 0 {
+                cmds.spawn((
+                    Name::new("CoinDrop"),
+                    CoinDrop(coins),
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: colors::GOLD.into(),
+                            custom_size: Some(Vec2::splat(TILE_SIZE / 4.0)),
+                            ..default()
+                        },
+                        transform: Transform::from_translation(
+                            transform.translation.truncate().extend(ACTOR_Z_INDEX),
+                        ),
+                        ..default()
+                    },
+                ));
+            }
+        }
+        debug!("despawning defeated {:?}", character_type);
+        cmds.entity(ent).despawn_recursive();
+    }
+}
+
+/// fails the run once the players death animation has finished.
+///
+/// carried coins and items are kept or lost by `DifficultyScales`
+/// and the player is healed before returning too the hideout
+fn finish_player_death(
+    mut cmds: Commands,
+    time: Res<Time>,
+    difficulty: Res<DifficultyScales>,
+    mut save_info: ResMut<PlayerSaveInformation>,
+    mut player: Query<
+        (
+            Entity,
+            &mut Dying,
             &mut CharacterStats,
-            &mut Transform,
-            Option<&PlayerSelectedHero>,
+            &mut CharacterInventory,
         ),
-        Changed<CharacterStats>,
+        With<PlayerSelectedHero>,
     >,
     mut regen_event: EventWriter<RegenerateDungeonEvent>,
 ) {
-    for (ent, mut stats, _transform, player_control) in &mut damaged_query {
-        if stats.get_current_health() <= 0.0 {
-            if player_control.is_some() {
-                info!("player died, resetting player");
-                stats.set_health(150.0);
-                game_info.player_deaths += 1;
-
-                regen_event.send(RegenerateDungeonEvent {
-                    reason: RegenReason::PlayerDeath,
-                });
-                continue;
-            }
+    let Ok((player, mut dying, mut stats, mut inventory)) = player.get_single_mut() else {
+        return;
+    };
+    if !dying.tick(time.delta()).finished() {
+        return;
+    }
 
-            // entity that died is not player
-            error!("despawning entity");
-            game_info.enemies_deaths += 1;
-            cmds.entity(ent).despawn_recursive();
+    let kept = (inventory.coins as f32 * difficulty.death_coin_keep.clamp(0.0, 1.0)) as u64;
+    let lost = inventory.coins.saturating_sub(kept);
+    inventory.coins = kept;
+    save_info.player_money = save_info
+        .player_money
+        .saturating_sub(i32::try_from(lost).unwrap_or(i32::MAX));
+    if !difficulty.death_keeps_items {
+        // `sync_inventory_effects` removes despawned items from the inventory
+        for item in inventory.items.keys() {
+            cmds.entity(*item).despawn_recursive();
+        }
+    }
+    info!("run failed, lost {lost} coins");
+
+    let max_hp = stats.attrs().max_hp;
+    stats.set_health(max_hp);
+    cmds.entity(player).remove::<Dying>();
+    regen_event.send(RegenerateDungeonEvent {
+        reason: RegenReason::PlayerDeath,
+    });
+}
+
+/// gives dropped coins too the player once they walk over them
+fn collect_coin_drops(
+    mut cmds: Commands,
+    mut game_info: ResMut<CurrentRunInformation>,
+    mut save_info: ResMut<PlayerSaveInformation>,
+    mut player: Query<
+        (&Transform, &mut CharacterInventory),
+        (With<PlayerSelectedHero>, Without<Dying>),
+    >,
+    coin_drops: Query<(Entity, &Transform, &CoinDrop)>,
+) {
+    let Ok((player_transform, mut inventory)) = player.get_single_mut() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+
+    for (drop, transform, coins) in &coin_drops {
+        if transform.translation.truncate().distance(player_pos) > COIN_PICKUP_DISTANCE {
+            continue;
         }
+        inventory.coins += **coins;
+        game_info.coins_collected += **coins;
+        save_info.player_money = save_info
+            .player_money
+            .saturating_add(i32::try_from(**coins).unwrap_or(i32::MAX));
+        cmds.entity(drop).despawn_recursive();
     }
 }
 
+/// character is playing its death animation, its actions are locked until the timer finishes
+#[derive(Debug, Component, Deref, DerefMut)]
+pub struct Dying(pub Timer);
+
+/// coins dropped by a defeated enemy, collected by walking over them
+#[derive(Debug, Component, Deref)]
+pub struct CoinDrop(pub u64);
+
 /// triggers weapon attacks if weapon weapon exists
 fn delegate_attack_events(
     mut attack_events: EventReader<EventRequestAttack>,
@@ -161,8 +297,8 @@ pub enum AttackDirection {
     FromVector(Vec2),
 }
 
-/// information tracked for current run
-#[derive(Debug, Clone, Copy, Default, Resource)]
+/// information tracked for current run, reset when a new run starts
+#[derive(Debug, Clone, Default, Resource, Serialize, Deserialize)]
 pub struct CurrentRunInformation {
     /// damage dealt by player this run
     pub enemy_physical_damage_taken: f32,
@@ -170,18 +306,50 @@ pub struct CurrentRunInformation {
     pub player_physical_damage_taken: f32,
     /// enemies killed by player this run
     pub enemies_deaths: i32,
+    /// enemies killed this run for each `CharacterType`
+    pub enemies_killed: HashMap<CharacterType, i32>,
+    /// coins picked up from defeated enemies this run
+    pub coins_collected: u64,
+    /// dungeon rooms cleared of enemies this run
+    pub rooms_cleared: i32,
+    /// seconds spent on each finished floor this run
+    pub floor_times: Vec<f32>,
+    /// time spent on the current floor, paused outside `AppState::PlayingGame`
+    pub floor_timer: Stopwatch,
     /// times player has died
     pub player_deaths: i32,
     /// amount of damage enemy's have fired that hit player and didn't get counted
     pub enemy_damage_sent: f32,
     /// amount of damage player have fired that hit enemy and didn't get counted
     pub player_damage_sent: f32,
+    /// how this run ended, `None` while the run is being played
+    pub outcome: Option<RunOutcome>,
+}
+
+impl CurrentRunInformation {
+    /// seconds spent in the dungeon this run
+    pub fn total_time(&self) -> f32 {
+        self.floor_times.iter().sum::<f32>() + self.floor_timer.elapsed_secs()
+    }
 }
 
-//TODO: save this too file, load from file when rebooting game
-/// information tracked for player save state
-#[derive(Debug, Clone, Copy, Default, Resource)]
+/// how a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunOutcome {
+    /// player died in the dungeon
+    PlayerDied,
+    /// player finished the last dungeon floor
+    Completed,
+}
+
+/// lifetime progress for the player, saved too the profile by `loading::profile`
+#[derive(Debug, Clone, Default, PartialEq, Resource, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PlayerSaveInformation {
+    /// name shown in the profile picker
+    pub profile_name: String,
+    /// unix time in seconds this profile was last saved, 0 if never
+    pub last_played: u64,
     /// damage player has cause with this save
     pub all_time_damage: f32,
     /// amount of times player has finishes a run
@@ -190,10 +358,31 @@ pub struct PlayerSaveInformation {
     pub runs_started: i32,
     /// amount of money player has earned
     pub player_money: i32,
+    /// experience player has earned
+    pub player_xp: i32,
+    /// total amount of enemies player has defeated
+    pub total_kills: i32,
     /// total amount of player deaths
     pub total_deaths: i32,
     /// total amonut of items player has collected
     pub items_got: i32,
+    /// deepest dungeon floor player has reached
+    pub deepest_floor: i32,
+    /// seconds spent inside dungeons with this save
+    pub time_played: f32,
+}
+
+impl PlayerSaveInformation {
+    /// adds a finished `run` that reached dungeon `floor` too the lifetime stats.
+    ///
+    /// damage, kills, deaths and coins are added while playing
+    pub fn add_finished_run(&mut self, run: &CurrentRunInformation, floor: i32) {
+        if run.outcome == Some(RunOutcome::Completed) {
+            self.runs_completed += 1;
+        }
+        self.deepest_floor = self.deepest_floor.max(floor);
+        self.time_played += run.total_time();
+    }
 }
 
 /// A custom filter that ignores contacts if both contact entities share the same '`EntityCreator`'
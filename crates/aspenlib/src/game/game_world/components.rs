@@ -91,7 +91,9 @@ pub struct PlayerStartLocation {
 }
 
 /// Marks Exits too dungeon rooms
-#[derive(Debug, Component, Clone, Default, PartialEq, Eq, Reflect)]
+#[derive(
+    Debug, Component, Clone, Default, PartialEq, Eq, Reflect, serde::Deserialize, serde::Serialize,
+)]
 #[reflect(Component)]
 pub struct RoomExit {
     /// room this exit is part of
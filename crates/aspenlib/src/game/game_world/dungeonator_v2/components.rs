@@ -14,6 +14,8 @@ use bevy_ecs_ldtk::{
     LevelIid,
 };
 use bevy_ecs_tilemap::prelude::TilemapSize;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     consts::TILE_SIZE,
@@ -23,6 +25,7 @@ use crate::{
             hallways::HallWayBlueprint, room_graph::RoomGraph, tile_graph::TileGraph,
         },
     },
+    loading::registry::RegistryIdentifier,
 };
 
 /// bundle for easy spawning of dungeon
@@ -95,16 +98,51 @@ pub struct DungeonRoomDatabase {
     pub huge_long_rooms: Vec<RoomPreset>,
 }
 
+impl DungeonRoomDatabase {
+    /// adds `room` too the list matching its `RoomType` and `RoomShape`
+    pub fn insert(&mut self, room: RoomPreset) {
+        match &room.descriptor.rtype {
+            RoomType::Hideout => self.hideouts.push(room),
+            RoomType::DungeonStart => self.start_rooms.push(room),
+            RoomType::DungeonEnd => self.end_rooms.push(room),
+            RoomType::Normal | RoomType::Special | RoomType::MiniBoss => {
+                match room.descriptor.shape {
+                    RoomShape::NonStandard => self.special_rooms.push(room),
+                    RoomShape::SmallShort => self.small_short_rooms.push(room),
+                    RoomShape::SmallLong => self.small_long_rooms.push(room),
+                    RoomShape::MediumShort => self.medium_short_rooms.push(room),
+                    RoomShape::MediumLong => self.medium_long_rooms.push(room),
+                    RoomShape::LargeShort => self.large_short_rooms.push(room),
+                    RoomShape::LargeLong => self.large_long_rooms.push(room),
+                    RoomShape::HugeShort => self.huge_short_rooms.push(room),
+                    RoomShape::HugeLong => self.huge_long_rooms.push(room),
+                }
+            }
+        }
+    }
+}
+
+/// floor of the current run, 1 is the first dungeon.
+///
+/// a run ends after `DifficultyScales.max_dungeon_amount` floors
+#[derive(Debug, Resource, Reflect, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct DungeonFloor(pub i32);
+
 // TODO: add dungeon level too settings
 /// settings to configure the dungeon generator,
 /// `useable_rooms` and hallways are filled by other systems
-#[derive(Debug, Clone, Default, Reflect)]
+#[derive(Debug, Clone, Default, Reflect, Serialize, Deserialize)]
 pub struct DungeonSettings {
+    /// seed for `DungeonRng`, the same seed and room database always give the same layout
+    #[serde(default)]
+    pub seed: u64,
     /// level for leveled rooms
     pub level: RoomLevel,
     /// border around outside of dungeon in tiles
     pub border: u32,
     /// how wide/tall this dungeon should be in tiles
+    #[serde(with = "TilemapSizeDef")]
     pub size: TilemapSize,
     // TODO: readd, disabled because it breaks maths
     // /// minimum space between dungeon rooms, in tiles
@@ -114,6 +152,24 @@ pub struct DungeonSettings {
     /// percentage of paths between
     /// rooms that are chosen to loop
     pub hallway_loop_chance: f32,
+    /// longest hallway in tiles that can be added as a loop, any length if unset
+    #[serde(default)]
+    pub max_loop_length: Option<u32>,
+    /// names of special room presets always placed in this dungeon,
+    /// `distribution.special` adds random special rooms on top
+    #[serde(default)]
+    pub special_rooms: Vec<String>,
+    /// max enemies alive in each room, scaled by difficulty.
+    /// `DifficultyScales.max_enemies_per_room` is used if unset
+    #[serde(default)]
+    pub enemy_budget: Option<u32>,
+    /// bosses that can spawn in this dungeon, boss spawns are swapped for one of these.
+    /// any boss can spawn if empty
+    #[serde(default)]
+    pub boss_pool: Vec<RegistryIdentifier>,
+    /// asset path of the music played in this dungeon, the game soundtrack if unset
+    #[serde(default)]
+    pub music: Option<String>,
 }
 
 impl DungeonSettings {
@@ -129,6 +185,16 @@ impl DungeonSettings {
     }
 }
 
+/// serde mirror of `TilemapSize`
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "TilemapSize")]
+struct TilemapSizeDef {
+    /// tiles wide
+    x: u32,
+    /// tiles tall
+    y: u32,
+}
+
 /// self contained dungeon data component
 #[derive(Debug, Component, Default, Reflect)]
 #[reflect(Component)]
@@ -140,10 +206,49 @@ pub struct Dungeon {
     /// graph of rooms and hallway connections
     #[reflect(ignore)]
     pub room_graph: RoomGraph,
+    /// rng for every random choice made while generating this dungeon
+    #[reflect(ignore)]
+    pub rng: DungeonRng,
+}
+
+/// seeded rng shared by each dungeon generation step,
+/// created from `DungeonSettings.seed`
+#[derive(Debug, Clone)]
+pub struct DungeonRng(pub StdRng);
+
+impl DungeonRng {
+    /// creates rng that always gives the same values for `seed`
+    pub fn from_seed(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for DungeonRng {
+    fn default() -> Self {
+        Self::from_seed(0)
+    }
+}
+
+impl RngCore for DungeonRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.0.try_fill_bytes(dest)
+    }
 }
 
 /// current boss combat state
-#[derive(Debug, Default, Reflect, Clone, PartialEq, Eq, PartialOrd)]
+#[derive(Debug, Default, Reflect, Clone, PartialEq, Eq, PartialOrd, Serialize, Deserialize)]
 pub enum BossState {
     /// boss has not been spawned yet
     #[default]
@@ -172,6 +277,26 @@ pub struct RoomPreset {
     pub exits: Vec<IVec2>,
 }
 
+impl RoomPreset {
+    /// creates a preset that is not backed by a level asset, for headless layouts.
+    ///
+    /// `size` and `exits` are in px, exits are the lower left corner of the exit tile
+    pub fn headless(
+        name: &str,
+        descriptor: RoomDescriptor,
+        size: IVec2,
+        exits: Vec<IVec2>,
+    ) -> Self {
+        Self {
+            descriptor,
+            room_asset_id: LevelIid::from(name.to_string()),
+            name: name.to_string(),
+            size,
+            exits,
+        }
+    }
+}
+
 impl RoomBlueprint {
     /// creates `RoomBlueprint` from a room preset
     pub fn from_preset(preset: &RoomPreset, position: IVec2, id: u32) -> Self {
@@ -205,16 +330,31 @@ impl RoomBlueprint {
 }
 
 /// unique id of room per dungeon spawn
-#[derive(Debug, Clone, Copy, Reflect, Component, Default, PartialEq, PartialOrd, Ord, Eq, Hash)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Reflect,
+    Component,
+    Default,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
 pub struct RoomID(pub u32);
 
 /// room placed by room placer
-#[derive(Debug, Clone, Reflect, Component, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Reflect, Component, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[reflect(Component)]
 pub struct RoomBlueprint {
     /// information used too describe room
     pub descriptor: RoomDescriptor,
     /// what room asset should this blueprint pull from
+    #[serde(with = "level_iid")]
     pub asset_id: LevelIid,
     /// what exits does this room possess
     pub exits: Vec<RoomExit>,
@@ -226,8 +366,26 @@ pub struct RoomBlueprint {
     pub id: RoomID,
 }
 
+/// stores `LevelIid` as its string
+mod level_iid {
+    use bevy_ecs_ldtk::LevelIid;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// writes `iid` as a string
+    pub fn serialize<S: Serializer>(iid: &LevelIid, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(iid.get())
+    }
+
+    /// reads a `LevelIid` from a string
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LevelIid, D::Error> {
+        String::deserialize(deserializer).map(LevelIid::from)
+    }
+}
+
 /// room stats, describes room for placing algorithm
-#[derive(Debug, Clone, Reflect, Default, Eq, PartialOrd, Ord, PartialEq)]
+#[derive(
+    Debug, Clone, Reflect, Default, Eq, PartialOrd, Ord, PartialEq, Serialize, Deserialize,
+)]
 pub struct RoomDescriptor {
     /// room shape as enum
     pub shape: RoomShape,
@@ -238,7 +396,7 @@ pub struct RoomDescriptor {
 }
 
 /// amounts of each room that should be spawned
-#[derive(Debug, Clone, Default, Reflect)]
+#[derive(Debug, Clone, Default, Reflect, Serialize, Deserialize)]
 pub struct RoomDistribution {
     /// max amount of this room too spawn
     pub small_short: i32,
@@ -261,7 +419,9 @@ pub struct RoomDistribution {
 }
 
 /// what level is this room
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Default, Ord, Reflect)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Default, Ord, Reflect, Serialize, Deserialize,
+)]
 pub enum RoomLevel {
     /// DEBUG LEVEL
     Level0,
@@ -277,6 +437,16 @@ pub enum RoomLevel {
 }
 
 impl RoomLevel {
+    /// room level used for dungeon `floor`
+    pub const fn from_floor(floor: i32) -> Self {
+        match floor {
+            i32::MIN..=1 => Self::Level1,
+            2 => Self::Level2,
+            3 => Self::Level3,
+            _ => Self::Level4,
+        }
+    }
+
     /// returns the advanced '`RoomLevel`'
     pub const fn next_level(self) -> Self {
         match self {
@@ -289,7 +459,9 @@ impl RoomLevel {
 }
 
 /// what function does this room serve in the dungeon
-#[derive(Debug, Clone, Reflect, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[derive(
+    Debug, Clone, Reflect, PartialEq, Eq, PartialOrd, Default, Ord, Serialize, Deserialize,
+)]
 pub enum RoomType {
     /// room player is moved too when dungeon generation finishes
     DungeonStart,
@@ -308,7 +480,9 @@ pub enum RoomType {
 }
 
 /// what size/shape is this room
-#[derive(Debug, Clone, Reflect, PartialEq, Eq, PartialOrd, Default, Ord)]
+#[derive(
+    Debug, Clone, Reflect, PartialEq, Eq, PartialOrd, Default, Ord, Serialize, Deserialize,
+)]
 pub enum RoomShape {
     /// shape doesnt fit below definitions.
     NonStandard,
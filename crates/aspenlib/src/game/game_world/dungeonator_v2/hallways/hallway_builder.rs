@@ -47,7 +47,11 @@ pub fn build_hallways(
     } = &mut *dungeon_info;
 
     let (hallway_container, mut hallway_storage) = hallway_layer.single_mut();
-    for (_, mut hallway, _) in &mut hallways {
+    // hallways are built in a fixed order so overlapping sections always match
+    let mut sorted_hallways: Vec<Mut<HallWayBlueprint>> =
+        hallways.iter_mut().map(|(_, hallway, _)| hallway).collect();
+    sorted_hallways.sort_by_key(|f| f.connected_rooms);
+    for mut hallway in sorted_hallways {
         if hallway.built || hallway.node_path.len() == 2 {
             continue;
         }
@@ -586,15 +590,16 @@ pub struct HallwayPoints {
 }
 
 /// finds path start and end nodes and returns a `Vec<NodeIndex>` between the 2
+///
+/// `tile_graph` must be centered in the same space as the hallways positions
 pub fn create_path_simple(
     settings: &DungeonSettings,
     tile_graph: &TileGraph,
-    hallway: &Mut<HallWayBlueprint>,
-    center: Vec2,
+    hallway: &HallWayBlueprint,
 ) -> Option<VecDeque<NodeIndex>> {
     trace!("getting hallway side nodes");
-    let start_node_position = center.as_ivec2() + hallway.start_pos + IVec2::splat(16);
-    let end_node_position = center.as_ivec2() + hallway.end_pos + IVec2::splat(16);
+    let start_node_position = hallway.start_pos + IVec2::splat(16);
+    let end_node_position = hallway.end_pos + IVec2::splat(16);
 
     let start_pos = tile_graph.get_node_at_translation(settings, start_node_position);
     let end_pos = tile_graph.get_node_at_translation(settings, end_node_position);
@@ -25,7 +25,7 @@ pub mod walls;
 pub struct SiblingCount(pub u32);
 
 /// hallway representation
-#[derive(Debug, Reflect, Clone, Component)]
+#[derive(Debug, Reflect, Clone, Component, serde::Deserialize, serde::Serialize)]
 pub struct HallWayBlueprint {
     /// hallway start pos
     pub start_pos: IVec2,
@@ -37,9 +37,13 @@ pub struct HallWayBlueprint {
     pub connected_rooms: (RoomID, RoomID),
     /// list of nodes in tilegraph that are hallway tiles
     #[reflect(ignore)]
+    #[serde(skip)]
     pub node_path: VecDeque<NodeIndex>,
     /// hallway finished building
     pub built: bool,
+    /// hallway was added after the spanning tree too make a loop
+    #[serde(default)]
+    pub is_loop: bool,
 }
 
 /// marker component for hallway tile storage
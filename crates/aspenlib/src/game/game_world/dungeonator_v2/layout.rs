@@ -0,0 +1,381 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use bevy::{
+    log::{error, info},
+    math::{IVec2, Rect, Vec2},
+    utils::{HashMap, HashSet},
+};
+use petgraph::{
+    data::FromElements,
+    prelude::{EdgeRef, NodeIndex},
+    Graph,
+};
+use rand::Rng;
+
+use crate::{
+    consts::TILE_SIZE,
+    game::game_world::dungeonator_v2::{
+        components::{
+            DungeonRng, DungeonRoomDatabase, DungeonSettings, RoomBlueprint, RoomID, RoomLevel,
+            RoomType,
+        },
+        hallways::{
+            hallway_builder::{create_path_simple, mark_path_as_hallway_tiles},
+            HallWayBlueprint,
+        },
+        room_graph::RoomGraph,
+        tile_graph::{
+            r#impl::{actual_map_tile_size, exit_lane, populate_tilegraph},
+            TileGraph,
+        },
+        utils,
+    },
+};
+
+/// rooms, hallways and graphs of a dungeon, made from `RoomPreset` data only.
+///
+/// positions are relative too the dungeon root, `DungeonGeneratorPlugin` turns this into entities
+#[derive(Debug, Clone, Default)]
+pub struct DungeonLayout {
+    /// settings this layout was made with, `size` is the laid out map size in tiles
+    pub settings: DungeonSettings,
+    /// placed rooms, the start room is first
+    pub rooms: Vec<RoomBlueprint>,
+    /// hallways between room exits and there tile paths
+    pub hallways: Vec<HallWayBlueprint>,
+    /// rooms, exits and the hallway connections between them
+    pub room_graph: RoomGraph,
+    /// every tile position of the laid out map
+    pub tile_graph: TileGraph,
+}
+
+/// why a dungeon couldnt be laid out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorError {
+    /// room database has no start room for this level
+    MissingStartRoom(RoomLevel),
+    /// room database has no end room for this level
+    MissingEndRoom(RoomLevel),
+}
+
+impl Display for GeneratorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingStartRoom(level) => write!(f, "no start room for {level:?}"),
+            Self::MissingEndRoom(level) => write!(f, "no end room for {level:?}"),
+        }
+    }
+}
+
+/// lays out a dungeon for `settings` using presets from `room_database`.
+///
+/// the same settings, room database and `seed` always give the same layout
+///
+/// # Errors
+/// returns an error if `room_database` has no start or end room for `settings.level`
+pub fn generate_layout(
+    settings: &DungeonSettings,
+    room_database: &DungeonRoomDatabase,
+    seed: u64,
+) -> Result<DungeonLayout, GeneratorError> {
+    let mut rng = DungeonRng::from_seed(seed);
+
+    info!("creating dungeon room blueprints");
+    let positioned_presets = create_dungeon_blueprint(settings, room_database, &mut rng)?;
+
+    info!("creating room graph from blueprints");
+    let mut room_graph = RoomGraph::new(&positioned_presets);
+
+    info!("connecting graph");
+    room_graph.connect_graph_randomly(&mut rng);
+    let candidate_hallways = room_graph.exit_edges();
+
+    info!("computing minimum spanning tree of graph");
+    room_graph.graph = Graph::from_elements(petgraph::algo::min_spanning_tree(&room_graph.graph));
+
+    info!("verifying graph connectivity");
+    room_graph.verify_graph_connections();
+
+    info!("adding loop hallways");
+    let loops = room_graph.add_loop_hallways(&candidate_hallways, settings, &mut rng);
+    info!("added {} loop hallways", loops.len());
+
+    let hallways = hallways_from_graph(&room_graph);
+    Ok(DungeonLayout::from_parts(settings, room_graph, hallways))
+}
+
+impl DungeonLayout {
+    /// creates the tile graph and hallway paths for the rooms in `room_graph`
+    ///
+    /// used by `generate_layout` and too lay out suspended runs again
+    pub fn from_parts(
+        settings: &DungeonSettings,
+        mut room_graph: RoomGraph,
+        mut hallways: Vec<HallWayBlueprint>,
+    ) -> Self {
+        let rooms: Vec<RoomBlueprint> = room_graph.get_rooms().into_iter().cloned().collect();
+        let mut settings = settings.clone();
+        let mut tile_graph = TileGraph::default();
+
+        info!("getting laid out map dimensions");
+        (settings.size, tile_graph.center_world) = actual_map_tile_size(&settings, &rooms);
+
+        info!("checking positions for tiles and creating nodes");
+        populate_tilegraph(&settings, &mut tile_graph, &rooms);
+        info!("finished populating tile graph");
+
+        // hallways are pathed in a fixed order so marked tiles only depend on the seed,
+        // loops go last and are removed if there path fails or uses another hallways tiles
+        hallways.sort_by_key(|f| (f.is_loop, f.connected_rooms));
+        let hallway_total = hallways.len();
+        let mut hallway_tiles: HashSet<NodeIndex> = HashSet::new();
+        let mut removed_loops = Vec::new();
+        for (i, hallway) in hallways.iter_mut().enumerate() {
+            info!("generating path for hallway {}/{}", i + 1, hallway_total);
+            let hallway_path =
+                create_path_simple(&settings, &tile_graph, hallway).filter(|path| !path.is_empty());
+            if hallway.is_loop
+                && hallway_path
+                    .as_ref()
+                    .is_none_or(|path| path.iter().any(|f| hallway_tiles.contains(f)))
+            {
+                info!(
+                    "removing loop hallway {:?}, it crosses another hallway",
+                    hallway.connected_rooms
+                );
+                room_graph.remove_hallway(hallway.start_pos, hallway.end_pos);
+                removed_loops.push((hallway.start_pos, hallway.end_pos));
+                continue;
+            }
+            let Some(hallway_path) = hallway_path else {
+                hallway.built = true;
+                continue;
+            };
+
+            hallway_tiles.extend(hallway_path.iter().copied());
+            mark_path_as_hallway_tiles(&hallway_path, &mut tile_graph);
+            hallway.node_path = hallway_path;
+        }
+        hallways.retain(|f| !removed_loops.contains(&(f.start_pos, f.end_pos)));
+
+        Self {
+            settings,
+            rooms,
+            hallways,
+            room_graph,
+            tile_graph,
+        }
+    }
+}
+
+impl DungeonLayout {
+    /// checks the guarantees every generated layout should keep
+    ///
+    /// # Errors
+    /// returns a message for the first broken guarantee: a missing start or end room,
+    /// overlapping rooms, a hallway that doesnt path between 2 exits,
+    /// a hallway crossing a room, a loop hallway crossing another hallway
+    /// or a room that cant be reached from the start room
+    pub fn verify(&self) -> Result<(), String> {
+        let start_room = self
+            .rooms
+            .iter()
+            .find(|f| f.descriptor.rtype == RoomType::DungeonStart)
+            .ok_or("layout has no start room")?;
+        if !self
+            .rooms
+            .iter()
+            .any(|f| f.descriptor.rtype == RoomType::DungeonEnd)
+        {
+            return Err("layout has no end room".to_string());
+        }
+
+        for (i, room) in self.rooms.iter().enumerate() {
+            if let Some(other) = self
+                .rooms
+                .iter()
+                .skip(i + 1)
+                .find(|other| !room.room_space.intersect(other.room_space).is_empty())
+            {
+                return Err(format!("rooms {} and {} overlap", room.name, other.name));
+            }
+        }
+
+        // exits and there lanes are the only room tiles hallways may use
+        let mut open_room_tiles = HashSet::new();
+        for room in &self.rooms {
+            for exit in &room.exits {
+                open_room_tiles.insert(exit.position);
+                open_room_tiles.extend(exit_lane(&room.room_space, exit.position));
+            }
+        }
+
+        let mut room_links: HashMap<RoomID, Vec<RoomID>> = HashMap::new();
+        for hallway in &self.hallways {
+            let (start_id, end_id) = hallway.connected_rooms;
+            let hallway_name = format!("hallway {start_id:?}->{end_id:?}");
+            if !self.room_has_exit(start_id, hallway.start_pos)
+                || !self.room_has_exit(end_id, hallway.end_pos)
+            {
+                return Err(format!("{hallway_name} does not connect 2 room exits"));
+            }
+
+            let path_tiles = hallway
+                .node_path
+                .iter()
+                .map(|node| self.tile_corner(*node))
+                .collect::<Option<Vec<IVec2>>>()
+                .ok_or_else(|| format!("{hallway_name} path has nodes missing from tile graph"))?;
+            if path_tiles.first() != Some(&hallway.start_pos)
+                || path_tiles.last() != Some(&hallway.end_pos)
+            {
+                return Err(format!(
+                    "{hallway_name} path does not run between its exits"
+                ));
+            }
+            if path_tiles
+                .windows(2)
+                .any(|pair| (pair[0] - pair[1]).abs().element_sum() != TILE_SIZE as i32)
+            {
+                return Err(format!("{hallway_name} path skips tiles"));
+            }
+            if let Some(tile) = path_tiles.iter().find(|tile| {
+                !open_room_tiles.contains(*tile)
+                    && self.rooms.iter().any(|room| {
+                        tile.cmpge(room.room_space.min).all()
+                            && tile.cmplt(room.room_space.max).all()
+                    })
+            }) {
+                return Err(format!("{hallway_name} crosses a room at {tile}"));
+            }
+
+            room_links.entry(start_id).or_default().push(end_id);
+            room_links.entry(end_id).or_default().push(start_id);
+        }
+
+        for hallway in self.hallways.iter().filter(|f| f.is_loop) {
+            let loop_tiles: HashSet<&NodeIndex> = hallway.node_path.iter().collect();
+            if let Some(other) = self.hallways.iter().find(|other| {
+                other.start_pos != hallway.start_pos
+                    && other.node_path.iter().any(|f| loop_tiles.contains(f))
+            }) {
+                return Err(format!(
+                    "loop hallway {:?} crosses hallway {:?}",
+                    hallway.connected_rooms, other.connected_rooms
+                ));
+            }
+        }
+
+        let mut reached = HashSet::new();
+        reached.insert(start_room.id);
+        let mut to_visit = vec![start_room.id];
+        while let Some(room_id) = to_visit.pop() {
+            for linked in room_links.get(&room_id).into_iter().flatten() {
+                if reached.insert(*linked) {
+                    to_visit.push(*linked);
+                }
+            }
+        }
+        if let Some(room) = self.rooms.iter().find(|room| !reached.contains(&room.id)) {
+            return Err(format!(
+                "room {} can not be reached from the start room",
+                room.name
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// does room `id` have an exit at `position`
+    fn room_has_exit(&self, id: RoomID, position: IVec2) -> bool {
+        self.rooms
+            .iter()
+            .any(|room| room.id == id && room.exits.iter().any(|exit| exit.position == position))
+    }
+
+    /// lower left corner of tile graph `node`, relative too the dungeon root
+    fn tile_corner(&self, node: NodeIndex) -> Option<IVec2> {
+        let tile = self.tile_graph.node_weight(node)?.tile;
+        let center = self
+            .tile_graph
+            .get_tiles_translation_world(&self.settings, tile);
+        Some((center - TILE_SIZE / 2.0).as_ivec2())
+    }
+}
+
+/// creates a hallway for each exit too exit edge in `room_graph`
+fn hallways_from_graph(room_graph: &RoomGraph) -> Vec<HallWayBlueprint> {
+    room_graph
+        .edge_references()
+        .filter_map(|edge| {
+            let source = room_graph.graph.node_weight(edge.source()).expect("msg");
+            let target = room_graph.graph.node_weight(edge.target()).expect("msg");
+
+            if target.is_exit() && source.is_exit() && source != target {
+                Some(HallWayBlueprint {
+                    start_pos: source.get_nodes_offset(),
+                    end_pos: target.get_nodes_offset(),
+                    distance: edge.weight().length,
+                    node_path: VecDeque::new(),
+                    connected_rooms: (*source.get_node_id(), *target.get_node_id()),
+                    built: false,
+                    is_loop: edge.weight().is_loop,
+                })
+            } else {
+                info!("bad graph edge");
+                None
+            }
+        })
+        .collect()
+}
+
+// TODO: use a quad-tree structure too improve performance?
+// tbh this part of the module is actually pretty quick compared too building the tilegraph
+/// returns random list of room blueprints for a dungeon
+///
+/// # Errors
+/// returns an error if `room_database` has no start or end room for `settings.level`
+fn create_dungeon_blueprint(
+    settings: &DungeonSettings,
+    room_database: &DungeonRoomDatabase,
+    rng: &mut impl Rng,
+) -> Result<Vec<RoomBlueprint>, GeneratorError> {
+    let mut room_positions = Vec::new();
+    let progress_level = &settings.level;
+
+    // choose presets
+    let mut presets = utils::choose_filler_presets(settings, room_database, rng);
+    if presets.is_empty() {
+        error!("presets could not be chosen from room database");
+        error!("database {:?}", room_database);
+    }
+
+    // add start and end presets
+    presets.push_back(
+        utils::get_leveled_preset(&room_database.end_rooms, progress_level, rng)
+            .ok_or_else(|| GeneratorError::MissingEndRoom(progress_level.clone()))?,
+    );
+    presets.push_front(
+        utils::get_leveled_preset(&room_database.start_rooms, progress_level, rng)
+            .ok_or_else(|| GeneratorError::MissingStartRoom(progress_level.clone()))?,
+    );
+
+    // turn room blueprint
+    let mut positioned_blueprints = Vec::new();
+    for (i, preset) in presets.iter().enumerate() {
+        let room_size = preset.size.as_vec2();
+        let rooms_space = if preset.descriptor.rtype == RoomType::DungeonStart {
+            utils::snap_too_tiles(Rect::from_center_size(Vec2::ZERO, room_size).min, room_size)
+        } else {
+            utils::random_room_positon(&room_positions, room_size, settings, rng)
+        };
+        room_positions.push(rooms_space);
+        positioned_blueprints.push(RoomBlueprint::from_preset(
+            preset,
+            rooms_space.min.as_ivec2(),
+            i as u32,
+        ));
+    }
+
+    Ok(positioned_blueprints)
+}
@@ -1,10 +1,13 @@
 use bevy_ecs_tilemap::prelude::TilemapSize;
-use petgraph::{data::FromElements, prelude::EdgeRef, Graph};
+use petgraph::Graph;
 use rand::prelude::Rng;
 use std::collections::VecDeque;
 
 use bevy::{prelude::*, reflect::Reflect};
-use bevy_ecs_ldtk::{assets::LdtkExternalLevel, prelude::LdtkProject};
+use bevy_ecs_ldtk::{
+    assets::LdtkExternalLevel,
+    prelude::{LdtkProject, LevelEvent, LevelIid},
+};
 
 use crate::{
     consts::TILE_SIZE,
@@ -14,18 +17,25 @@ use crate::{
             components::{ActorTeleportEvent, RoomExit, TpTriggerEffect},
             dungeonator_v2::{
                 components::{
-                    Dungeon, DungeonContainerBundle, DungeonHallWayBundle, DungeonRoomBundle,
-                    DungeonRoomDatabase, DungeonSettings, RoomBlueprint, RoomDistribution,
-                    RoomPreset, RoomType,
+                    Dungeon, DungeonContainerBundle, DungeonFloor, DungeonHallWayBundle,
+                    DungeonRng, DungeonRoomBundle, DungeonRoomDatabase, DungeonSettings,
+                    RoomBlueprint, RoomDistribution, RoomLevel, RoomPreset,
                 },
                 hallways::{create_hallway_layer, HallWayBlueprint, HallwayLayer},
+                layout::{generate_layout, DungeonLayout},
                 room_graph::RoomGraph,
                 tile_graph::TileGraph,
             },
+            hideout::ReturnToHideoutEvent,
             random_point_inside,
+            suspend::ResumingRun,
         },
     },
-    loading::assets::AspenMapHandles,
+    loading::{
+        assets::{AspenDefinitionHandles, AspenMapHandles},
+        config::{launch::LaunchOptions, update_difficulty_settings},
+        custom_assets::floor_definitions::FloorDefinition,
+    },
     register_types,
 };
 
@@ -33,6 +43,8 @@ use crate::{
 pub mod components;
 /// hallway creation system
 pub mod hallways;
+/// bevy independent room, graph and hallway layout
+pub mod layout;
 /// room selection and creation
 pub mod room_database;
 /// per dungeon graph of rooms and connections
@@ -78,11 +90,13 @@ impl Plugin for DungeonGeneratorPlugin {
                 HallWayBlueprint,
                 RoomDistribution,
                 DungeonSettings,
-                DungeonRoomDatabase
+                DungeonRoomDatabase,
+                DungeonFloor
             ]
         );
 
         app.init_state::<GeneratorState>();
+        app.init_resource::<DungeonFloor>();
 
         // create a new room database anytime we get new room assets
         app.add_systems(
@@ -99,6 +113,7 @@ impl Plugin for DungeonGeneratorPlugin {
         app.add_systems(
             OnEnter(GeneratorState::LayoutDungeon),
             (
+                update_difficulty_settings,
                 spawn_new_dungeon,
                 apply_deferred,
                 layout_dungeon,
@@ -109,9 +124,7 @@ impl Plugin for DungeonGeneratorPlugin {
 
         app.add_systems(
             Update,
-            (tile_graph::create_tile_graph, apply_deferred)
-                .chain()
-                .run_if(in_state(GeneratorState::CompleteHallways)),
+            wait_for_room_levels.run_if(in_state(GeneratorState::CompleteHallways)),
         );
 
         app.add_systems(
@@ -129,59 +142,68 @@ impl Plugin for DungeonGeneratorPlugin {
     }
 }
 
-/// spawns dungeon root
+/// spawns dungeon root, room level is picked from the current `DungeonFloor`.
+///
+/// settings come from the packs `FloorDefinition` for that level, or `fallback_floor_settings`.
+/// the dungeon seed comes from `LaunchOptions.seed` and the floor if set, else its random.
+/// resumed runs reuse the suspended dungeons position and settings
+#[allow(clippy::too_many_arguments)]
 fn spawn_new_dungeon(
     mut cmds: Commands,
     ldtk_project_handles: Res<AspenMapHandles>,
-    dungeon_root: Query<(Entity, &Dungeon)>,
+    definition_handles: Res<AspenDefinitionHandles>,
+    floor_definitions: Res<Assets<FloorDefinition>>,
+    dungeon_root: Query<Entity, With<Dungeon>>,
+    floor: Res<DungeonFloor>,
+    launch: Res<LaunchOptions>,
+    resuming: Option<Res<ResumingRun>>,
 ) {
-    // TODO: proper dungeon end system with cleanup
-    let level = if let Ok((ent, dungeon)) = dungeon_root.get_single() {
+    if let Ok(ent) = dungeon_root.get_single() {
         cmds.entity(ent).despawn_recursive(); // this happens next frame so dungeon still exists
-        dungeon.settings.level.clone().next_level()
-    } else {
-        components::RoomLevel::Level1
-    };
+    }
+    let level = RoomLevel::from_floor(floor.0);
 
     let span = 15000.0;
-    let mut rng = rand::thread_rng();
-    let origin = Transform::from_xyz(
+    let seed = launch.seed.map_or_else(rand::random, |seed| {
+        seed.wrapping_add(floor.0.unsigned_abs().into())
+    });
+    let mut settings = definition_handles
+        .floors
+        .as_ref()
+        .and_then(|handle| floor_definitions.get(handle))
+        .and_then(|floors| floors.settings_for(&level))
+        .cloned()
+        .unwrap_or_else(|| {
+            warn!("no floor definition for {level:?}, using fallback settings");
+            fallback_floor_settings()
+        });
+    settings.seed = seed;
+    settings.level = level;
+    if let Some(resuming) = &resuming {
+        settings.clone_from(&resuming.settings);
+    }
+
+    let mut rng = DungeonRng::from_seed(settings.seed);
+    let mut origin = Transform::from_xyz(
         ensure_tile_pos(rng.gen_range(-span..span)),
         ensure_tile_pos(rng.gen_range(-span..span)),
         0.0,
     );
-    info!("spawning dungeon at {origin:?}");
+    if let Some(resuming) = &resuming {
+        origin.translation = resuming.origin.extend(0.0);
+    }
+    info!("spawning dungeon at {origin:?} with seed {}", settings.seed);
 
     cmds.spawn(DungeonContainerBundle {
         name: "The Aspen Halls".into(),
         dungeon: Dungeon {
-            settings: DungeonSettings {
-                level,
-                // border is applied too each room asset so 0 here
-                border: 4,
-                // room placing settings
-                size: TilemapSize { x: 64, y: 64 },
-                // TODO: use this but make it working
-                // tiles_between_rooms: 4,
-                distribution: RoomDistribution {
-                    small_short: 3,
-                    small_long: 2,
-                    medium_short: 1,
-                    medium_long: 1,
-                    large_short: 0,
-                    large_long: 0,
-                    huge_short: 0,
-                    huge_long: 0,
-                    special: 2,
-                },
-                // hallway placing settings/data
-                hallway_loop_chance: 0.08,
-            },
+            settings,
             tile_graph: TileGraph {
                 graph: Graph::new_undirected(),
                 center_world: origin.translation.truncate(),
             },
             room_graph: RoomGraph::default(),
+            rng,
         },
         ldtk_project: ldtk_project_handles.default_levels.clone(),
         spatial: SpatialBundle {
@@ -191,133 +213,157 @@ fn spawn_new_dungeon(
     });
 }
 
-/// creates vec of `RoomPreset` too be built into `DungeonRoomBundles` using  `DungeonSettings` and current progress in the dungeon
+/// dungeon settings used when the loaded packs dont define floors
+fn fallback_floor_settings() -> DungeonSettings {
+    DungeonSettings {
+        border: 4,
+        size: TilemapSize { x: 64, y: 64 },
+        distribution: RoomDistribution {
+            small_short: 3,
+            small_long: 2,
+            medium_short: 1,
+            medium_long: 1,
+            large_short: 0,
+            large_long: 0,
+            huge_short: 0,
+            huge_long: 0,
+            special: 2,
+        },
+        hallway_loop_chance: 0.08,
+        ..default()
+    }
+}
+
+/// lays out the dungeon with `generate_layout` and spawns its rooms and hallways
+///
+/// resumed runs lay out the suspended rooms and hallways instead,
+/// the player is sent back too the hideout if the dungeon cant be laid out
 pub fn layout_dungeon(
     mut cmds: Commands,
     room_database: Res<DungeonRoomDatabase>,
     mut dungeon_root: Query<(Entity, &mut Dungeon, &Transform)>,
     player_query: Query<Entity, With<PlayerSelectedHero>>,
     mut tp_events: EventWriter<ActorTeleportEvent>,
+    mut hideout_events: EventWriter<ReturnToHideoutEvent>,
+    resuming: Option<Res<ResumingRun>>,
 ) {
-    let (dungon_id, dungeon, dungeon_transform) = dungeon_root.single_mut();
-
-    info!("creating dungeon room blueprints");
-    let mut positioned_presets = create_dungeon_blueprint(dungeon, room_database);
-
-    info!("creating room graph from blueprints");
-    let mut room_graph = RoomGraph::new(positioned_presets.make_contiguous());
-
-    info!("connecting graph");
-    room_graph.connect_graph_randomly();
-
-    info!("computing minimum spanning tree of graph");
-    room_graph.graph = Graph::from_elements(petgraph::algo::min_spanning_tree(&room_graph.graph));
-
-    info!("verifying graph connectivity");
-    room_graph.verify_graph_connections();
+    let (dungon_id, mut dungeon, dungeon_transform) = dungeon_root.single_mut();
+    let dungeon_center = dungeon_transform.translation.truncate();
+
+    let layout = if let Some(resuming) = resuming {
+        info!("laying out suspended rooms and hallways");
+        let hallways = resuming
+            .hallways
+            .iter()
+            .map(|hallway| HallWayBlueprint {
+                node_path: VecDeque::new(),
+                built: false,
+                ..hallway.clone()
+            })
+            .collect();
+        DungeonLayout::from_parts(&dungeon.settings, RoomGraph::new(&resuming.rooms), hallways)
+    } else {
+        match generate_layout(&dungeon.settings, &room_database, dungeon.settings.seed) {
+            Ok(layout) => layout,
+            Err(e) => {
+                error!("couldnt lay out dungeon: {e}, returning too hideout");
+                hideout_events.send(ReturnToHideoutEvent);
+                return;
+            }
+        }
+    };
 
     info!("spawning rooms");
-    room_graph.node_weights().for_each(|weight| {
-        if let room_graph::RoomGraphNode::Room(bp) = weight {
-            cmds.entity(dungon_id).with_children(|rooms| {
-                rooms.spawn(DungeonRoomBundle {
-                    name: bp.name.clone().into(),
-                    id: bp.asset_id.clone(),
-                    room: bp.clone(),
-                    spatial: SpatialBundle::from_transform(Transform::from_translation(
-                        bp.room_space.min.as_vec2().extend(0.0),
-                    )),
-                });
-            });
-        };
-    });
+    for blueprint in &layout.rooms {
+        spawn_room(&mut cmds, dungon_id, blueprint);
+    }
 
     teleport_player_too_start_location(
-        dungeon_transform.translation.truncate(),
+        dungeon_center,
+        &mut dungeon.rng,
         &player_query,
         &mut tp_events,
     );
 
     info!("spawning hallways");
-    room_graph.edge_references().for_each(|edge| {
-        let source = room_graph.graph.node_weight(edge.source()).expect("msg");
-        let target = room_graph.graph.node_weight(edge.target()).expect("msg");
-
-        if target.is_exit() && source.is_exit() && source != target {
-            let hallway_name = format!(
-                "Hallway{:?}->{:?}",
-                source.get_node_id(),
-                target.get_node_id()
-            );
-
-            let start_pos = source.get_nodes_offset();
-            let end_pos = target.get_nodes_offset();
-
-            cmds.spawn(DungeonHallWayBundle {
-                name: Name::new(hallway_name),
-                hallway: HallWayBlueprint {
-                    start_pos,
-                    end_pos,
-                    distance: edge.weight().length,
-                    node_path: VecDeque::new(),
-                    connected_rooms: (*source.get_node_id(), *target.get_node_id()),
-                    built: false,
-                },
-                spatial: SpatialBundle::from_transform(Transform::from_translation(
-                    start_pos.as_vec2().extend(0.0),
-                )),
-            })
-            .set_parent(dungon_id);
-        } else {
-            info!("bad graph edge");
-        }
-    });
+    for hallway in &layout.hallways {
+        spawn_hallway(&mut cmds, dungon_id, hallway.clone());
+    }
+
+    // layouts are made around the dungeon root, the tile graph is used in worldspace
+    let DungeonLayout {
+        settings,
+        room_graph,
+        mut tile_graph,
+        ..
+    } = layout;
+    tile_graph.center_world += dungeon_center;
+    dungeon.settings = settings;
+    dungeon.room_graph = room_graph;
+    dungeon.tile_graph = tile_graph;
 
     cmds.insert_resource(NextState::Pending(GeneratorState::CompleteHallways));
 }
 
-// TODO: use a quad-tree structure too improve performance?
-// tbh this part of the module is actually pretty quick compared too building the tilegraph
-/// returns random list of room blueprints for a dungeon
-fn create_dungeon_blueprint(
-    dungeon: Mut<Dungeon>,
-    room_database: Res<DungeonRoomDatabase>,
-) -> VecDeque<RoomBlueprint> {
-    let mut room_positions = Vec::new();
-    let progress_level = &dungeon.settings.level;
-
-    // choose presets
-    let mut presets = utils::choose_filler_presets(&dungeon.settings, &room_database);
-    if presets.is_empty() {
-        error!("presets could not be chosen from room database");
-        error!("database {:?}", room_database);
+/// waits for every room level too be spawned before hallways are built
+fn wait_for_room_levels(
+    mut cmds: Commands,
+    level_query: Query<(&LevelIid, &RoomBlueprint)>,
+    mut spawn_events: EventReader<LevelEvent>,
+) {
+    let mut transformed_count = 0;
+    let level_amount = level_query.iter().count();
+
+    for event in spawn_events.read() {
+        match event {
+            LevelEvent::Transformed(_) => transformed_count += 1,
+            LevelEvent::SpawnTriggered(_) | LevelEvent::Spawned(_) | LevelEvent::Despawned(_) => {}
+        }
+    }
+    if transformed_count < level_amount {
+        info!("not all tiles are spawned yet");
+        return;
     }
 
-    // add start and end presets
-    presets.push_back(utils::get_leveled_preset(&room_database.end_rooms, progress_level).unwrap());
-    presets
-        .push_front(utils::get_leveled_preset(&room_database.start_rooms, progress_level).unwrap());
+    cmds.insert_resource(NextState::Pending(GeneratorState::FinalizeHallways));
+}
 
-    // turn room blueprint
-    let mut positioned_blueprints: VecDeque<RoomBlueprint> = VecDeque::new();
-    for (i, preset) in presets.iter().enumerate() {
-        let rooms_space = if preset.descriptor.rtype == RoomType::DungeonStart {
-            Rect::from_center_size(Vec2::ZERO, preset.size.as_vec2())
-        } else {
-            utils::random_room_positon(&room_positions, preset.size.as_vec2(), &dungeon.settings)
-        };
-        room_positions.push(rooms_space);
-        positioned_blueprints.push_back(RoomBlueprint::from_preset(
-            preset,
-            IVec2 {
-                x: ensure_tile_pos(rooms_space.min.x) as i32,
-                y: ensure_tile_pos(rooms_space.min.y) as i32,
-            },
-            i as u32,
-        ));
-    }
+/// spawns room `bp` as a child of dungeon `dungon_id`
+fn spawn_room(cmds: &mut Commands, dungon_id: Entity, bp: &RoomBlueprint) {
+    cmds.entity(dungon_id).with_children(|rooms| {
+        rooms.spawn(DungeonRoomBundle {
+            name: bp.name.clone().into(),
+            id: bp.asset_id.clone(),
+            room: bp.clone(),
+            spatial: SpatialBundle::from_transform(Transform::from_translation(
+                bp.room_space.min.as_vec2().extend(0.0),
+            )),
+        });
+    });
+}
 
-    positioned_blueprints
+/// spawns `hallway` as a child of dungeon `dungon_id`
+fn spawn_hallway(cmds: &mut Commands, dungon_id: Entity, hallway: HallWayBlueprint) {
+    let hallway_name = format!(
+        "{}{:?}->{:?}",
+        if hallway.is_loop {
+            "LoopHallway"
+        } else {
+            "Hallway"
+        },
+        hallway.connected_rooms.0,
+        hallway.connected_rooms.1
+    );
+    let start_pos = hallway.start_pos;
+
+    cmds.spawn(DungeonHallWayBundle {
+        name: Name::new(hallway_name),
+        hallway,
+        spatial: SpatialBundle::from_transform(Transform::from_translation(
+            start_pos.as_vec2().extend(0.0),
+        )),
+    })
+    .set_parent(dungon_id);
 }
 
 /// rounds `element` too nearest multiple of tilesize
@@ -329,6 +375,7 @@ fn ensure_tile_pos(element: f32) -> f32 {
 #[allow(clippy::type_complexity)]
 fn teleport_player_too_start_location(
     dungeon_center: Vec2,
+    rng: &mut impl Rng,
     player_query: &Query<Entity, With<PlayerSelectedHero>>,
     tp_events: &mut EventWriter<ActorTeleportEvent>,
 ) {
@@ -345,7 +392,7 @@ fn teleport_player_too_start_location(
         },
     };
 
-    let pos = random_point_inside(&start_loc_rect, 1.0).unwrap_or(dungeon_center);
+    let pos = random_point_inside(&start_loc_rect, 1.0, rng).unwrap_or(dungeon_center);
 
     warn!("teleporting player too start location: {}", pos);
     let player_ent = player_query.single();
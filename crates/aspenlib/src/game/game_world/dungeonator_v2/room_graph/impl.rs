@@ -1,11 +1,17 @@
-use bevy::{prelude::*, utils::HashSet};
-use petgraph::{prelude::NodeIndex, Graph};
-use rand::seq::IteratorRandom;
-
-use crate::game::game_world::dungeonator_v2::{
-    components::{RoomBlueprint, RoomID},
-    hallways::SiblingCount,
-    room_graph::{RoomGraph, RoomGraphEdge, RoomGraphNode},
+use bevy::{math::FloatOrd, prelude::*, utils::HashSet};
+use petgraph::{
+    prelude::{EdgeRef, NodeIndex},
+    Graph,
+};
+use rand::{seq::IteratorRandom, Rng};
+
+use crate::{
+    consts::TILE_SIZE,
+    game::game_world::dungeonator_v2::{
+        components::{DungeonSettings, RoomBlueprint, RoomID},
+        hallways::SiblingCount,
+        room_graph::{RoomGraph, RoomGraphEdge, RoomGraphNode},
+    },
 };
 
 impl RoomGraph {
@@ -33,7 +39,14 @@ impl RoomGraph {
                     exit: exit.clone(),
                     brothers: SiblingCount(preset.exits.len() as u32),
                 });
-                graph.add_edge(room, exit, RoomGraphEdge { length: 0.0 });
+                graph.add_edge(
+                    room,
+                    exit,
+                    RoomGraphEdge {
+                        length: 0.0,
+                        is_loop: false,
+                    },
+                );
             }
         }
 
@@ -41,115 +54,73 @@ impl RoomGraph {
     }
 
     /// finds unconnected groups of rooms in room graph and connects them with a hallway edge
+    ///
+    /// the smallest group is joined too the closest exit of any other group until 1 group is left,
+    /// exits that already have a hallway are only used if a group has no free exits
     pub fn verify_graph_connections(&mut self) {
-        let max_loop_amount = 25;
-        let mut current_amount = 0;
         let room_graph = &mut self.graph;
-        let mut connected_components: Vec<Vec<NodeIndex>> =
-            petgraph::algo::kosaraju_scc(&*room_graph);
 
         loop {
-            if connected_components.len() > 1 {
-                if current_amount >= max_loop_amount {
-                    break;
-                }
-                warn!(
-                    "graph is unconnected, fixing graph. groups : {}",
-                    connected_components.len()
-                );
-                connected_components.sort_by_key(Vec::len);
-
-                let (smallest_group, other_groups) = connected_components
-                    .split_first()
-                    .expect("graph should have len > 2");
-
-                // TODO: collect node index into 2 lists of (exit, position),
-                // get closest pair of exits in lists
-                let Some(exit1_id) = other_groups
-                    .first()
-                    .expect("should not be empty")
-                    .iter()
-                    .find(|f| {
-                        room_graph.node_weight(**f).expect("msg").is_exit()
-                            && room_graph.edges(**f).count() == 1
-                    })
-                else {
-                    current_amount += 1;
-                    info!("no valid node in other groups");
-                    continue;
-                };
-
-                let RoomGraphNode::Exit {
-                    exit: exit1_data,
-                    brothers: _exit1_brothers,
-                } = room_graph
-                    .node_weight(*exit1_id)
-                    .expect("filtering based on if exit")
-                else {
-                    current_amount += 1;
-                    info!("chosent node was not an exit");
-                    continue;
-                };
-
-                let Some(exit2_id) = smallest_group
-                    .iter()
-                    .filter(|f| {
-                        room_graph
-                            .node_weight(**f)
-                            .expect("node should exist")
-                            .is_exit()
-                            && room_graph.edges(**f).count() == 1
-                    })
-                    .min_by(|a, b| {
-                        let a_pos = room_graph
-                            .node_weight(**a)
-                            .expect("node should exist")
-                            .get_nodes_offset();
-                        let b_pos = room_graph
-                            .node_weight(**b)
-                            .expect("node should exist")
-                            .get_nodes_offset();
-                        // not saving distance, dont need too square
-                        let a_distance = ((a_pos - exit1_data.position).abs().length_squared()
-                            as f32)
-                            .sqrt() as i32;
-                        let b_distance = ((b_pos - exit1_data.position).abs().length_squared()
-                            as f32)
-                            .sqrt() as i32;
-                        a_distance.cmp(&b_distance)
-                    })
-                else {
-                    current_amount += 1;
-                    info!("could not get a close exit from smallest group");
-                    continue;
-                };
-
-                let RoomGraphNode::Exit {
-                    exit: exit2_data,
-                    brothers: _exit2_brothers,
-                } = room_graph
-                    .node_weight(*exit2_id)
-                    .expect("filtering based on if exit")
-                else {
-                    current_amount += 1;
-                    info!("exit 2 id was not an exit");
-                    continue;
-                };
-
-                // we are sqrting this distance so its not obsene
-                let distance = (exit1_data.position.distance_squared(exit2_data.position) as f32)
-                    .abs()
-                    .sqrt();
-                room_graph.add_edge(*exit1_id, *exit2_id, RoomGraphEdge { length: distance });
-            } else {
+            let mut connected_components: Vec<Vec<NodeIndex>> =
+                petgraph::algo::kosaraju_scc(&*room_graph);
+            if connected_components.len() <= 1 {
                 break;
             }
+            warn!(
+                "graph is unconnected, fixing graph. groups : {}",
+                connected_components.len()
+            );
+            connected_components.sort_by_key(Vec::len);
+
+            let (smallest_group, other_groups) = connected_components
+                .split_first()
+                .expect("graph should have len > 2");
+            let group_exits: Vec<NodeIndex> = smallest_group
+                .iter()
+                .copied()
+                .filter(|f| room_graph[*f].is_exit())
+                .collect();
+            let other_exits: Vec<NodeIndex> = other_groups
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|f| room_graph[*f].is_exit())
+                .collect();
+
+            let Some((exit1_id, exit2_id)) = group_exits
+                .iter()
+                .flat_map(|a| other_exits.iter().map(move |b| (*a, *b)))
+                .min_by_key(|(a, b)| {
+                    // exits only have there room edge until a hallway is added
+                    let used = room_graph.edges(*a).count() > 1 || room_graph.edges(*b).count() > 1;
+                    let distance = (room_graph[*a].get_nodes_offset()
+                        - room_graph[*b].get_nodes_offset())
+                    .as_vec2()
+                    .length_squared();
+                    (used, FloatOrd(distance))
+                })
+            else {
+                warn!("room group has no exits, graph can not be connected");
+                break;
+            };
+
+            let distance = (room_graph[exit1_id].get_nodes_offset()
+                - room_graph[exit2_id].get_nodes_offset())
+            .as_vec2()
+            .length();
+            room_graph.add_edge(
+                exit1_id,
+                exit2_id,
+                RoomGraphEdge {
+                    length: distance,
+                    is_loop: false,
+                },
+            );
         }
     }
 
     /// randomly connects exits inside roomgraph,
-    pub fn connect_graph_randomly(&mut self) {
-        let mut rng = rand::thread_rng();
+    pub fn connect_graph_randomly(&mut self, rng: &mut impl Rng) {
         let room_graph = &mut self.graph;
         let graph_copy = room_graph.clone();
 
@@ -166,7 +137,7 @@ impl RoomGraph {
             let Some(first_exit) = exit_nodes
                 .iter()
                 .filter(|f| !exits_connected.contains(&f.0))
-                .choose(&mut rng)
+                .choose(rng)
             else {
                 break;
             };
@@ -218,12 +189,99 @@ impl RoomGraph {
                     second_exit.0,
                     RoomGraphEdge {
                         length: distance.sqrt(),
+                        is_loop: false,
                     },
                 );
             }
         }
     }
 
+    /// returns every exit too exit edge as its 2 exits and length
+    pub fn exit_edges(&self) -> Vec<(NodeIndex, NodeIndex, f32)> {
+        self.edge_references()
+            .filter(|edge| self[edge.source()].is_exit() && self[edge.target()].is_exit())
+            .map(|edge| (edge.source(), edge.target(), edge.weight().length))
+            .collect()
+    }
+
+    /// adds `candidates` edges the spanning tree removed back as loop hallways.
+    ///
+    /// each candidate becomes a loop with `settings.hallway_loop_chance` if it is no longer than
+    /// `settings.max_loop_length`, both exits are still free and it doesnt cross another hallway.
+    /// returns the added loop edges
+    pub fn add_loop_hallways(
+        &mut self,
+        candidates: &[(NodeIndex, NodeIndex, f32)],
+        settings: &DungeonSettings,
+        rng: &mut impl Rng,
+    ) -> Vec<(NodeIndex, NodeIndex)> {
+        // `gen_bool` panics on NaN, broken chances never loop
+        let chance = if settings.hallway_loop_chance.is_finite() {
+            f64::from(settings.hallway_loop_chance).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let max_length = settings
+            .max_loop_length
+            .map_or(f32::INFINITY, |tiles| tiles as f32 * TILE_SIZE);
+        let mut loops = Vec::new();
+
+        for (a, b, length) in candidates {
+            // rolled first so every candidate uses the rng the same amount
+            if !rng.gen_bool(chance) || self.find_edge(*a, *b).is_some() {
+                continue;
+            }
+            let rooms = (self[*a].get_node_id(), self[*b].get_node_id());
+            if *length > max_length {
+                debug!("loop {rooms:?} is too long: {length}");
+                continue;
+            }
+            // exits only have there room edge until a hallway is added
+            if self.edges(*a).count() > 1 || self.edges(*b).count() > 1 {
+                debug!("loop {rooms:?} exit already has a hallway");
+                continue;
+            }
+            let (start, end) = (self[*a].get_nodes_offset(), self[*b].get_nodes_offset());
+            if self.exit_edges().iter().any(|(c, d, _)| {
+                segments_cross(
+                    start,
+                    end,
+                    self[*c].get_nodes_offset(),
+                    self[*d].get_nodes_offset(),
+                )
+            }) {
+                debug!("loop {rooms:?} crosses a hallway");
+                continue;
+            }
+
+            info!("hallway {rooms:?} became a loop");
+            self.add_edge(
+                *a,
+                *b,
+                RoomGraphEdge {
+                    length: *length,
+                    is_loop: true,
+                },
+            );
+            loops.push((*a, *b));
+        }
+
+        loops
+    }
+
+    /// removes the hallway edge between the exits at `start` and `end`
+    pub fn remove_hallway(&mut self, start: IVec2, end: IVec2) {
+        let exit_at = |position: IVec2| {
+            self.node_indices()
+                .find(|f| self[*f].is_exit() && self[*f].get_nodes_offset() == position)
+        };
+        if let (Some(a), Some(b)) = (exit_at(start), exit_at(end))
+            && let Some(edge) = self.find_edge(a, b)
+        {
+            self.remove_edge(edge);
+        }
+    }
+
     /// returns references to room blueprints inside roomgraph
     pub fn get_rooms(&self) -> Vec<&RoomBlueprint> {
         self.node_weights()
@@ -238,6 +296,25 @@ impl RoomGraph {
     }
 }
 
+/// do the lines `a1`-`a2` and `b1`-`b2` cross or touch
+fn segments_cross(a1: IVec2, a2: IVec2, b1: IVec2, b2: IVec2) -> bool {
+    let orientation = |p: IVec2, q: IVec2, r: IVec2| {
+        let (p, q, r) = (p.as_i64vec2(), q.as_i64vec2(), r.as_i64vec2());
+        (q - p).perp_dot(r - p).signum()
+    };
+    let on_segment =
+        |p: IVec2, q: IVec2, r: IVec2| r.cmpge(p.min(q)).all() && r.cmple(p.max(q)).all();
+
+    let (d1, d2) = (orientation(b1, b2, a1), orientation(b1, b2, a2));
+    let (d3, d4) = (orientation(a1, a2, b1), orientation(a1, a2, b2));
+
+    (d1 * d2 < 0 && d3 * d4 < 0)
+        || (d1 == 0 && on_segment(b1, b2, a1))
+        || (d2 == 0 && on_segment(b1, b2, a2))
+        || (d3 == 0 && on_segment(a1, a2, b1))
+        || (d4 == 0 && on_segment(a1, a2, b2))
+}
+
 impl RoomGraphNode {
     /// is graph node an exit
     pub const fn is_exit(&self) -> bool {
@@ -37,4 +37,6 @@ pub enum RoomGraphNode {
 pub struct RoomGraphEdge {
     /// how long is this connection between rooms
     pub length: f32,
+    /// connection was added after the spanning tree too make a loop
+    pub is_loop: bool,
 }
@@ -1,7 +1,5 @@
-use bevy::prelude::*;
-use bevy_ecs_ldtk::GridCoords;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_ecs_tilemap::prelude::TilemapSize;
-use bevy_rapier2d::geometry::Collider;
 
 use image::{ImageBuffer, Rgba};
 use petgraph::{prelude::NodeIndex, visit::IntoNodeReferences, Graph, Undirected};
@@ -9,43 +7,35 @@ use std::fs;
 
 use crate::{
     consts::TILE_SIZE,
-    game::game_world::{
-        components::RoomExitTile,
-        dungeonator_v2::{
-            components::{Dungeon, DungeonSettings},
-            ensure_tile_pos,
-            hallways::hallway_builder::distance_tiles,
-            tile_graph::{TileGraph, TileGraphEdge, TileGraphNode, TileType},
-        },
-        RoomBoundryTile,
+    game::game_world::dungeonator_v2::{
+        components::{DungeonSettings, RoomBlueprint},
+        hallways::hallway_builder::distance_tiles,
+        tile_graph::{TileGraph, TileGraphEdge, TileGraphNode, TileType},
     },
 };
 
-#[allow(clippy::type_complexity)]
-/// check each tile position for a tile in gameworld and add node too list
+/// adds a node for each tile position in `settings.size`, typed from `rooms`
+///
+/// room tiles are `Floor`, exits are `RoomExit` and the lane from each exit
+/// too its rooms closest edge is `Unused` so hallways can reach the exit
 pub fn populate_tilegraph(
-    dungeon: &mut Dungeon,
-    tile_query: Query<
-        (
-            &GlobalTransform,
-            Option<&Collider>,
-            Option<&RoomExitTile>,
-            Option<&RoomBoundryTile>,
-        ),
-        With<GridCoords>,
-    >,
+    settings: &DungeonSettings,
+    tile_graph: &mut TileGraph,
+    rooms: &[RoomBlueprint],
 ) {
-    let tile_graph = &mut dungeon.tile_graph;
-    let settings = &dungeon.settings;
+    let room_tiles = room_tile_types(rooms);
 
-    assert!(!tile_query.is_empty(), "no tiles too map");
-    for x in 0..=settings.size.x {
-        for y in 0..=settings.size.y {
+    for x in 0..settings.size.x {
+        for y in 0..settings.size.y {
             let coords = UVec2 { x, y };
 
-            // Calculate the global translation of the tile based on dungeon position and coords
-            let tile_translation = tile_graph.get_tiles_translation_world(settings, coords);
-            let tile_on_position = tile_occupies_position(&tile_query, tile_translation);
+            // room tiles are stored by there lower left corner
+            let tile_corner =
+                tile_graph.get_tiles_translation_world(settings, coords) - TILE_SIZE / 2.0;
+            let tile_on_position = room_tiles
+                .get(&tile_corner.as_ivec2())
+                .copied()
+                .unwrap_or(TileType::Unused);
 
             tile_graph.add_node(TileGraphNode {
                 tile: coords,
@@ -55,155 +45,105 @@ pub fn populate_tilegraph(
     }
 
     info!("connecting adjectent nodes in graph");
-    connect_adjacent_nodes(dungeon);
+    connect_adjacent_nodes(settings, tile_graph);
     info!("finished connecting adjacent nodes");
-
-    // TODO: for RoomNode in graph, for n.min.x..n.max.x
-    // does not add borders too rooms for paths
-    // let graph_copy = tile_graph.clone();
-    // let room_graph = &dungeon.room_graph;
-    // for x in 0..=settings.size.x {
-    //     for y in 0..=settings.size.y {
-    //         let coords = UVec2 { x, y };
-    //         let tile_on_position = TileType::Unused;
-
-    //         tile_graph.add_node(TileGraphNode {
-    //             tile: coords,
-    //             data: tile_on_position,
-    //         });
-    //     }
-    // }
-    // let room_areas: Vec<(UVec2, UVec2)> = room_graph.get_rooms().iter().map(|f| {
-    //     (
-    //         tile_graph.get_position_tile_coords(settings, f.position.as_vec2()),
-    //         tile_graph.get_position_tile_coords(settings, (f.position + f.size).as_vec2()),
-    //     )
-    // }).collect();
-
-    // for room in room_areas {
-    //     for x in room.0.x..=room.1.x {
-    //         for y in room.0.y..=room.1.y {
-    //             let coords = UVec2 { x, y };
-    //             // Calculate the actual translation of the tile based on dungeon position
-    //             let tile_translation = tile_graph.get_tiles_translation_world(settings, coords);
-    //             let tile_on_position = tile_occupies_position(&tile_query, tile_translation);
-    //             let tile_id = tile_graph.get_node_at_coord(coords).expect("msg");
-
-    //             if tile_on_position.is_wall() {
-    //                 // remove all edges of this tile
-    //                 let edges = graph_copy.edges(tile_id.id());
-    //                 for edge in edges {
-    //                     tile_graph.remove_edge(edge.id());
-    //                 }
-    //             }
-
-    //             if !tile_on_position.is_unused() {
-    //                 // get tile idx and mutably change value too proper tiletype
-    //                 tile_graph.node_weight_mut(tile_id).expect("msg").data = tile_on_position
-    //             }
-    //         }
-    //     }
-    // }
 }
 
-// clippy really doesnt like this code
-// TODO: find a better solution for this garbage
-#[allow(clippy::type_complexity)]
-#[allow(clippy::unnecessary_find_map)]
-/// does position have a tile
-pub fn tile_occupies_position(
-    tiles: &Query<
-        (
-            &GlobalTransform,
-            Option<&Collider>,
-            Option<&RoomExitTile>,
-            Option<&RoomBoundryTile>,
-        ),
-        With<GridCoords>,
-    >,
-    position: Vec2,
-) -> TileType {
+/// tile type of every tile covered by `rooms`, keyed by the tiles lower left corner
+fn room_tile_types(rooms: &[RoomBlueprint]) -> HashMap<IVec2, TileType> {
+    let tile_size = TILE_SIZE as i32;
+    let mut tiles = HashMap::new();
+
+    for room in rooms {
+        let IRect { min, max } = room.room_space;
+        for x in (min.x..max.x).step_by(tile_size as usize) {
+            for y in (min.y..max.y).step_by(tile_size as usize) {
+                tiles.insert(IVec2 { x, y }, TileType::Floor);
+            }
+        }
+    }
+
+    for room in rooms {
+        for exit in &room.exits {
+            for lane_tile in exit_lane(&room.room_space, exit.position) {
+                tiles.insert(lane_tile, TileType::Unused);
+            }
+        }
+    }
+
+    // exits are added last so lanes passing other exits dont hide them
+    for exit in rooms.iter().flat_map(|room| &room.exits) {
+        tiles.insert(exit.position, TileType::RoomExit);
+    }
+
     tiles
-        .iter()
-        .filter(|(transform, ..)| transform.translation().truncate() == position)
-        .find_map(
-            |(_, exit, collider, roomboundry)| match (exit, collider, roomboundry) {
-                (Some(_), None, None) => Some(TileType::Wall),
-                (None, Some(_), None) => Some(TileType::RoomExit),
-                (None, None, Some(_)) => Some(TileType::Unused),
-                (None, None, None) => Some(TileType::Floor),
-                _ => panic!("tile had exit collider and room boundry or some combination of these"),
-            },
-        )
-        .unwrap_or(TileType::Unused)
 }
 
-//TODO: performance-: precalculate empty spaces using room dimensions, then check inside room dimensions for walkability
-// it might be faster too do this 'destructively'.
-// I.e. create edges as tilegraph is being made, for every tile, then remove all edges for tiles that are unwalkable
+/// tiles between `exit` and the closest edge of `room_space`, not including the exit
+pub fn exit_lane(room_space: &IRect, exit: IVec2) -> Vec<IVec2> {
+    let tile_size = TILE_SIZE as i32;
+    let IRect { min, max } = *room_space;
+
+    let step = [
+        (exit.x - min.x, IVec2::NEG_X),
+        (max.x - tile_size - exit.x, IVec2::X),
+        (exit.y - min.y, IVec2::NEG_Y),
+        (max.y - tile_size - exit.y, IVec2::Y),
+    ]
+    .into_iter()
+    .min_by_key(|(distance, _)| *distance)
+    .map_or(IVec2::ZERO, |(_, direction)| direction * tile_size);
+
+    let mut lane = Vec::new();
+    let mut position = exit + step;
+    while step != IVec2::ZERO && position.cmpge(min).all() && position.cmplt(max).all() {
+        lane.push(position);
+        position += step;
+    }
 
-/// connects adjacent nodes in graph
-/// 1.041611 seconds
-/// 0.874678 | 0.677892 | 0.452816 with `last_node` sort
-pub fn connect_adjacent_nodes(dungeon: &mut Dungeon) {
-    // let references: Vec<(NodeIndex, &TileGraphNode)> = graph.node_references().collect();
-    let mut edges: Vec<(NodeIndex, NodeIndex, TileGraphEdge)> = Vec::new();
-    let tilegraph = &mut dungeon.tile_graph;
-
-    info!("filtering adjacent nodes in tilegraph for walkability");
-    let mut walkable_nodes: Vec<NodeIndex> = tilegraph
-        .node_indices()
-        .filter(|f| tilegraph[*f].data.can_be_hallway())
-        .collect::<Vec<NodeIndex>>();
-
-    let tile_center: UVec2 = <TilemapSize as Into<UVec2>>::into(dungeon.settings.size) / 2;
-
-    let mut last_node = tile_center;
-    // sort by distance from center, this makes checking for
-    walkable_nodes.sort_by(|a, b| {
-        let TileGraphNode { tile: tile_a, .. } = tilegraph[*a];
-        let TileGraphNode { tile: tile_b, .. } = tilegraph[*b];
-        let distance_a = distance_tiles(last_node, tile_a);
-        let distance_b = distance_tiles(last_node, tile_b);
-        last_node = if distance_a > distance_b {
-            tile_a
-        } else {
-            tile_b
-        };
-        distance_a.cmp(&distance_b)
-    });
+    lane
+}
 
-    info!("pairing walkable nodes");
-    for (i, current_idx) in walkable_nodes.iter().enumerate() {
-        let current = &tilegraph[*current_idx];
-        let mut connect_count = 0;
+/// connects each hallway capable node too the nodes above and right of it.
+///
+/// nodes are added column by column so neighbors are found by index
+pub fn connect_adjacent_nodes(settings: &DungeonSettings, tile_graph: &mut TileGraph) {
+    let column_height = settings.size.y as usize;
+    let mut edges: Vec<(NodeIndex, NodeIndex, TileGraphEdge)> = Vec::new();
 
-        if connect_count == 4 {
+    for current_idx in tile_graph.node_indices() {
+        let current = &tile_graph[current_idx];
+        if !current.data.can_be_hallway() {
             continue;
         }
 
-        for other_idx in walkable_nodes.iter().skip(i + 1) {
-            let other = &tilegraph[*other_idx];
-
-            if is_adjacent(current.tile, other.tile) {
-                connect_count += 1;
-                edges.push((
-                    *current_idx,
-                    *other_idx,
-                    TileGraphEdge {
-                        cost: calculate_weight(
-                            &(*current_idx, current),
-                            &(*other_idx, other),
-                            tilegraph,
-                        ),
-                    },
-                ));
+        let above =
+            (current.tile.y + 1 < settings.size.y).then(|| NodeIndex::new(current_idx.index() + 1));
+        let right = (current.tile.x + 1 < settings.size.x)
+            .then(|| NodeIndex::new(current_idx.index() + column_height));
+
+        for other_idx in [above, right].into_iter().flatten() {
+            let other = &tile_graph[other_idx];
+            if !other.data.can_be_hallway() {
+                continue;
             }
+
+            edges.push((
+                current_idx,
+                other_idx,
+                TileGraphEdge {
+                    cost: calculate_weight(
+                        &(current_idx, current),
+                        &(other_idx, other),
+                        &tile_graph.graph,
+                    ),
+                },
+            ));
         }
     }
 
     info!("extending graph with edges");
-    tilegraph.extend_with_edges(edges);
+    tile_graph.extend_with_edges(edges);
 }
 
 /// calculates edge weight for 2 graph nodes
@@ -260,80 +200,35 @@ pub const fn is_adjacent(pos1: UVec2, pos2: UVec2) -> bool {
     (row_diff == 1 && col_diff == 0) || (row_diff == 0 && col_diff == 1) //|| (row_diff == 1 && col_diff == 1)
 }
 
-#[allow(clippy::type_complexity)]
-/// gets tilemap size in tiles
+/// gets tilemap size in tiles and its center for `rooms`,
+/// with `settings.border` tiles (minimum 1) of space around the outer rooms
 pub fn actual_map_tile_size(
-    _settings: &DungeonSettings,
-    tile_query: &Query<
-        (
-            &GlobalTransform,
-            Option<&Collider>,
-            Option<&RoomExitTile>,
-            Option<&RoomBoundryTile>,
-        ),
-        With<GridCoords>,
-    >,
+    settings: &DungeonSettings,
+    rooms: &[RoomBlueprint],
 ) -> (TilemapSize, Vec2) {
-    let (max_x, max_y, min_x, min_y) = calculate_tile_extents(tile_query);
-
-    let max_tile_pos = Vec2 { x: max_x, y: max_y };
-    let min_tile_pos = Vec2 { x: min_x, y: min_y };
-
-    let expanded_rect = Rect::from_corners(max_tile_pos, min_tile_pos);
+    let tile_size = TILE_SIZE as i32;
+    // exit lanes on the outer rooms need a tile too open onto
+    let border = IVec2::splat(settings.border.max(1) as i32 * tile_size);
+    let room_extents = rooms
+        .iter()
+        .map(|room| room.room_space)
+        .reduce(|acc, room_space| acc.union(room_space))
+        .unwrap_or_default();
 
-    let y_size = (expanded_rect.max.y - expanded_rect.min.y).abs();
-    let x_size = (expanded_rect.max.x - expanded_rect.min.x).abs();
+    let min = room_extents.min - border;
+    let max = room_extents.max + border;
+    // even sizes keep tile translations on tile centers
+    let tiles = ((max - min) / tile_size + 1) / 2 * 2;
 
     (
         TilemapSize {
-            x: (x_size / TILE_SIZE) as u32,
-            y: (y_size / TILE_SIZE) as u32,
-        },
-        Vec2 {
-            x: ensure_tile_pos(expanded_rect.center().x), // - 16.0,
-            y: ensure_tile_pos(expanded_rect.center().y), // - 16.0,
+            x: tiles.x as u32,
+            y: tiles.y as u32,
         },
+        min.as_vec2() + tiles.as_vec2() * TILE_SIZE / 2.0,
     )
 }
 
-/// finds furthest 4 corners of tile map
-#[allow(clippy::type_complexity)]
-pub fn calculate_tile_extents(
-    tile_query: &Query<
-        (
-            &GlobalTransform,
-            Option<&Collider>,
-            Option<&RoomExitTile>,
-            Option<&RoomBoundryTile>,
-        ),
-        With<GridCoords>,
-    >,
-) -> (f32, f32, f32, f32) {
-    // Use map to extract the Vec2 translations from the iterator
-    let translations: Vec<Vec2> = tile_query
-        .iter()
-        .filter(|f| f.3.is_some())
-        // turn tile query into positions
-        .map(|f| f.0.translation().truncate())
-        // map data too translations
-        .collect();
-
-    // Find the maximum and minimum x and y values using iterators
-    let (max_x, max_y) = translations
-        .iter()
-        .fold((f32::NEG_INFINITY, f32::NEG_INFINITY), |acc, t| {
-            (acc.0.max(t.x), acc.1.max(t.y))
-        });
-    let (min_x, min_y) = translations
-        .iter()
-        .fold((f32::INFINITY, f32::INFINITY), |acc, t| {
-            (acc.0.min(t.x), acc.1.min(t.y))
-        });
-
-    // (ensure_tile_pos(max_x), ensure_tile_pos(max_y), ensure_tile_pos(min_x), ensure_tile_pos(min_y))
-    (max_x, max_y, min_x, min_y)
-}
-
 impl TileGraph {
     /// turn tile coordinates into world translation
     pub fn get_tiles_translation_world(&self, settings: &DungeonSettings, coords: UVec2) -> Vec2 {
@@ -1,15 +1,11 @@
 use std::collections::VecDeque;
 
 use bevy::{
-    ecs::system::Res,
     log::{info, warn},
     math::{Rect, Vec2},
 };
 
-use rand::{
-    prelude::{Rng, ThreadRng},
-    seq::IteratorRandom,
-};
+use rand::{prelude::Rng, seq::IteratorRandom};
 
 use crate::{
     consts::TILE_SIZE,
@@ -19,30 +15,32 @@ use crate::{
 };
 
 /// gets ANY random preset from `presets`
-pub fn get_random_preset(presets: &[RoomPreset]) -> Option<&RoomPreset> {
-    let mut rng = ThreadRng::default();
-
-    presets.iter().choose(&mut rng)
+pub fn get_random_preset<'a>(
+    presets: &'a [RoomPreset],
+    rng: &mut impl Rng,
+) -> Option<&'a RoomPreset> {
+    presets.iter().choose(rng)
 }
 
 /// get random preset that matches `level` from `presets`
 pub fn get_leveled_preset<'a>(
     presets: &'a [RoomPreset],
     _level: &'a RoomLevel,
+    rng: &mut impl Rng,
 ) -> Option<&'a RoomPreset> {
-    let mut rng = ThreadRng::default();
-
     presets
         .iter()
         // TODO ADD LEVELED START/END rooms
         // .filter(|f| f.descriptor.level == *level)
-        .choose(&mut rng)
+        .choose(rng)
 }
 
-/// chooses selected amount of rooms for each room class
+/// chooses selected amount of rooms for each room class,
+/// `settings.special_rooms` are always chosen
 pub fn choose_filler_presets<'a>(
     settings: &'a DungeonSettings,
-    room_database: &'a Res<'a, DungeonRoomDatabase>,
+    room_database: &'a DungeonRoomDatabase,
+    rng: &mut impl Rng,
 ) -> VecDeque<&'a RoomPreset> {
     let mut chosen_presets: VecDeque<&RoomPreset> = VecDeque::new();
     let room_cfg: &super::components::RoomDistribution = &settings.distribution;
@@ -52,56 +50,71 @@ pub fn choose_filler_presets<'a>(
     // if small prefer smaller rooms
     for _ in 0..room_cfg.small_short {
         if !room_database.small_short_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.small_short_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.small_short_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.small_long {
         if !room_database.small_long_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.small_long_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.small_long_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.medium_short {
         if !room_database.medium_short_rooms.is_empty() {
             chosen_presets
-                .push_front(get_random_preset(&room_database.medium_short_rooms).unwrap());
+                .push_front(get_random_preset(&room_database.medium_short_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.medium_long {
         if !room_database.medium_long_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.medium_long_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.medium_long_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.large_short {
         if !room_database.large_short_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.large_short_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.large_short_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.large_long {
         if !room_database.large_long_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.large_long_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.large_long_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.huge_short {
         if !room_database.huge_short_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.huge_short_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.huge_short_rooms, rng).unwrap());
         }
     }
 
     for _ in 0..room_cfg.huge_long {
         if !room_database.huge_long_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.huge_long_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.huge_long_rooms, rng).unwrap());
+        }
+    }
+
+    for name in &settings.special_rooms {
+        match room_database.special_rooms.iter().find(|f| &f.name == name) {
+            Some(preset) => chosen_presets.push_front(preset),
+            None => warn!("special room {name} is not in the room database"),
         }
     }
 
     for _ in 0..room_cfg.special {
         if !room_database.special_rooms.is_empty() {
-            chosen_presets.push_front(get_random_preset(&room_database.special_rooms).unwrap());
+            chosen_presets
+                .push_front(get_random_preset(&room_database.special_rooms, rng).unwrap());
         }
     }
     chosen_presets
@@ -110,18 +123,20 @@ pub fn choose_filler_presets<'a>(
 #[allow(clippy::redundant_else)]
 /// Creates randomly positioned `Rect` that doesnt overlap any `Rect` in `occupied_positions`
 ///
-/// configured with `DungeonSettings`
+/// configured with `DungeonSettings`, rooms are placed on the tile grid
+/// with at least `settings.border` tiles (minimum 1) between them
 pub fn random_room_positon(
     filled_positions: &[Rect],
     room_size: Vec2,
     settings: &DungeonSettings,
+    rng: &mut impl Rng,
 ) -> Rect {
-    let mut rng = ThreadRng::default();
     let mut attempt_count = 0;
     let max_attempts = 100;
+    let spacing = settings.border.max(1) as f32 * TILE_SIZE;
 
-    let px_size_x = settings.size.x as f32 * TILE_SIZE;
-    let px_size_y = settings.size.y as f32 * TILE_SIZE;
+    let px_size_x = settings.size.x.max(1) as f32 * TILE_SIZE;
+    let px_size_y = settings.size.y.max(1) as f32 * TILE_SIZE;
 
     let mut expanding_halfsize_x = px_size_x / 2.0;
     let mut expanding_halfsize_y = px_size_y / 2.0;
@@ -142,23 +157,27 @@ pub fn random_room_positon(
             y: height,
         } = room_size;
 
-        let valid_origins: Vec<Rect> = vec![
+        let valid_origins: Vec<Rect> = [
             Rect::from_center_size(Vec2 { x, y }, room_size),
             Rect::new(x, y, x - width, y - height),
             Rect::new(x, y, x + width, y - height),
             Rect::new(x, y, x - width, y + height),
             Rect::new(x, y, x + width, y + height),
-        ];
+        ]
+        .into_iter()
+        .map(|rect| snap_too_tiles(rect.min, room_size))
+        .collect();
 
         // test if test_rect has no intersections with currently spawned recs
-        if filled_positions
-            .iter()
-            .all(|f| valid_origins.iter().any(|o| o.intersect(*f).is_empty()))
-        {
+        if filled_positions.iter().all(|f| {
+            valid_origins
+                .iter()
+                .any(|o| o.intersect(f.inflate(spacing)).is_empty())
+        }) {
             if let Some(rect) = valid_origins.iter().find(|new| {
                 filled_positions
                     .iter()
-                    .all(|filled| filled.intersect(**new).is_empty())
+                    .all(|filled| filled.inflate(spacing).intersect(**new).is_empty())
             }) {
                 return rect.to_owned();
             };
@@ -167,3 +186,9 @@ pub fn random_room_positon(
         attempt_count += 1;
     }
 }
+
+/// creates a `Rect` of `size` with its `min` moved too the closest tile corner
+pub fn snap_too_tiles(min: Vec2, size: Vec2) -> Rect {
+    let min = (min / TILE_SIZE).round() * TILE_SIZE;
+    Rect::from_corners(min, min + size)
+}
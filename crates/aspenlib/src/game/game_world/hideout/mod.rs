@@ -3,9 +3,9 @@ use bevy::{
     log::{error, info},
     math::Vec2,
     prelude::{
-        in_state, on_event, Commands, DespawnRecursiveExt, Entity, EventReader, GlobalTransform,
-        IntoSystemConfigs, OnExit, OrthographicProjection, Plugin, Query, Transform, Update, With,
-        Without,
+        in_state, not, on_event, Commands, DespawnRecursiveExt, Entity, Event, EventReader,
+        EventWriter, GlobalTransform, IntoSystemConfigs, NextState, OnExit, OrthographicProjection,
+        Plugin, Query, Transform, Update, With, Without,
     },
 };
 use bevy_ecs_ldtk::prelude::{LevelEvent, LevelSet};
@@ -19,13 +19,14 @@ use crate::{
     consts::ACTOR_Z_INDEX,
     game::{
         characters::{
-            components::CharacterMoveState,
+            components::{CharacterMoveState, CharacterType},
             player::{PlayerSelectedHero, SelectThisHeroForPlayer},
         },
         game_world::{
-            components::HeroLocation,
+            components::{ActorTeleportEvent, HeroLocation, TpTriggerEffect},
             dungeonator_v2::GeneratorState,
             hideout::systems::{spawn_world_container, teleporter_collisions},
+            DungeonCleanup,
         },
         items::weapons::components::AttackDamage,
     },
@@ -47,6 +48,7 @@ pub struct HideOutPlugin;
 impl Plugin for HideOutPlugin {
     fn build(&self, app: &mut bevy::app::App) {
         info!("registering ldtk map cells and adding teleport event");
+        app.add_event::<ReturnToHideoutEvent>();
         app.add_systems(OnExit(AppState::Loading), spawn_world_container);
         app.add_systems(OnExit(GeneratorState::NoDungeon), despawn_hideout);
         app.add_systems(
@@ -56,11 +58,24 @@ impl Plugin for HideOutPlugin {
                 teleporter_collisions.run_if(on_event::<CollisionEvent>()),
                 create_playable_heroes
                     .run_if(in_state(AppState::StartMenu).and_then(on_event::<LevelEvent>())),
+                (despawn_dungeon, spawn_world_container)
+                    .chain()
+                    .run_if(on_event::<ReturnToHideoutEvent>()),
+                place_player_in_hideout.run_if(
+                    not(in_state(AppState::StartMenu))
+                        .and_then(in_state(GeneratorState::NoDungeon))
+                        .and_then(on_event::<LevelEvent>()),
+                ),
             ),
         );
     }
 }
 
+/// send too leave the dungeon and respawn the hideout,
+/// sent when a run ends
+#[derive(Debug, Event)]
+pub struct ReturnToHideoutEvent;
+
 /// spawns selectable heroes at each available `HeroSpot`
 fn create_playable_heroes(
     mut level_spawn_events: EventReader<LevelEvent>,
@@ -72,7 +87,11 @@ fn create_playable_heroes(
     for event in level_spawn_events.read() {
         if let LevelEvent::Transformed(_iid) = event {
             let hero_spots: Vec<&GlobalTransform> = hero_spots.iter().collect();
-            if registry.characters.heroes.is_empty() {
+            if registry
+                .characters_of_type(CharacterType::Hero)
+                .next()
+                .is_none()
+            {
                 error!("no heroes too pick from");
             }
             if hero_spots.is_empty() {
@@ -117,20 +136,22 @@ fn populate_hero_spots(
 
     info!("placing heroes");
     // TODO: swap this around for better expandability?
-    registry.characters.heroes.values().for_each(|thing| {
-        let Some(spot) = hero_spots.next() else {
-            error!("no more hero spots");
-            return;
-        };
-        let mut bundle = thing.clone();
-        bundle.aseprite.sprite_bundle.transform.translation =
-            spot.translation().truncate().extend(ACTOR_Z_INDEX);
-        commands.spawn((
-            bundle,
-            PickableBundle::default(),
-            On::<Pointer<Down>>::send_event::<SelectThisHeroForPlayer>(),
-        ));
-    });
+    registry
+        .characters_of_type(CharacterType::Hero)
+        .for_each(|(_, thing)| {
+            let Some(spot) = hero_spots.next() else {
+                error!("no more hero spots");
+                return;
+            };
+            let mut bundle = thing.clone();
+            bundle.aseprite.sprite_bundle.transform.translation =
+                spot.translation().truncate().extend(ACTOR_Z_INDEX);
+            commands.spawn((
+                bundle,
+                PickableBundle::default(),
+                On::<Pointer<Down>>::send_event::<SelectThisHeroForPlayer>(),
+            ));
+        });
 }
 
 // TODO: find all uses of cmds.spawn(()) and add cleanup component
@@ -153,3 +174,42 @@ fn despawn_hideout(
         commands.entity(ent).despawn_recursive();
     }
 }
+
+/// despawns dungeon and everything in it except the player
+fn despawn_dungeon(mut commands: Commands, mut cleanup: DungeonCleanup) {
+    info!("leaving dungeon for hideout");
+    cleanup.despawn_dungeon();
+    commands.insert_resource(NextState::Pending(GeneratorState::NoDungeon));
+}
+
+/// moves player too the hero spots once the hideout level is spawned again
+fn place_player_in_hideout(
+    mut level_spawn_events: EventReader<LevelEvent>,
+    mut tp_events: EventWriter<ActorTeleportEvent>,
+    player: Query<Entity, With<PlayerSelectedHero>>,
+    hero_spots: Query<&GlobalTransform, With<HeroLocation>>,
+) {
+    for event in level_spawn_events.read() {
+        if let LevelEvent::Transformed(_iid) = event {
+            let Ok(player) = player.get_single() else {
+                return;
+            };
+            let hero_spots: Vec<Vec2> = hero_spots
+                .iter()
+                .map(|f| f.translation().truncate())
+                .collect();
+            if hero_spots.is_empty() {
+                error!("no hero spots too put player at");
+                return;
+            }
+
+            let pos = hero_spots.iter().sum::<Vec2>() / hero_spots.len() as f32;
+            info!("moving player back too hideout: {pos}");
+            tp_events.send(ActorTeleportEvent {
+                tp_type: TpTriggerEffect::Global(pos),
+                target: Some(player),
+                sender: Some(player),
+            });
+        }
+    }
+}
@@ -1,11 +1,11 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 use bevy_ecs_ldtk::{
     prelude::{EntityIid, LdtkEntityAppExt},
     TileEnumTags,
 };
 
 use leafwing_input_manager::action_state::ActionState;
-use rand::prelude::{Rng, ThreadRng};
+use rand::prelude::Rng;
 
 use crate::{
     consts::{ACTOR_Z_INDEX, TILE_SIZE},
@@ -14,6 +14,8 @@ use crate::{
             components::{CharacterMoveState, CharacterType, TeleportStatus},
             player::PlayerSelectedHero,
         },
+        combat::{CoinDrop, CurrentRunInformation, PlayerSaveInformation, RunOutcome},
+        components::TimeToLive,
         game_world::{
             self,
             collisions::handle_and_removed_collider_tag,
@@ -22,7 +24,11 @@ use crate::{
                 RoomBoundryTile, RoomExitTile, SpawnerTimer, SpawnerWave, Teleporter,
                 TpTriggerEffect, WeaponSpawner,
             },
-            dungeonator_v2::{components::Dungeon, GeneratorState},
+            dungeonator_v2::{
+                components::{Dungeon, DungeonFloor},
+                GeneratorState,
+            },
+            hideout::ReturnToHideoutEvent,
             world_objects::{
                 LdtkCharacterSpawner, LdtkHeroLocation, LdtkSpawnerWave, LdtkStartLocation,
                 LdtkTeleporter, LdtkWeaponSpawner,
@@ -31,7 +37,7 @@ use crate::{
         input::action_maps,
         items::EventSpawnItem,
     },
-    loading::registry::RegistryIdentifier,
+    loading::{config::DifficultyScales, profile::SaveProfileEvent, registry::RegistryIdentifier},
     register_types, AppState,
 };
 
@@ -45,6 +51,8 @@ pub mod dungeonator_v2;
 pub mod hideout;
 /// player progression module
 pub mod progress;
+/// suspend and resume dungeon runs
+pub mod suspend;
 /// bundles for entities that are defined inside ldtk
 mod world_objects;
 /// game world plugin handles home area and dungeon generator functions
@@ -75,6 +83,7 @@ impl Plugin for GameWorldPlugin {
             .add_event::<ActorTeleportEvent>()
             .add_plugins((
                 progress::GameProgressPlugin,
+                suspend::SuspendPlugin,
                 hideout::HideOutPlugin,
                 dungeonator_v2::DungeonGeneratorPlugin,
             ))
@@ -102,43 +111,117 @@ impl Plugin for GameWorldPlugin {
             )
             .add_systems(
                 OnExit(GeneratorState::FinalizeHallways),
-                populate_start_room,
+                populate_start_room.run_if(not(resource_exists::<suspend::ResumingRun>)),
             );
     }
 }
 
-/// listens for dungeon rebuild request if dungeon is finished spawning
-#[allow(clippy::type_complexity)]
+/// listens for dungeon rebuild request if dungeon is finished spawning.
+///
+/// new runs start at floor 1, resumed runs and manual regens rebuild the current floor,
+/// defeating the boss advances the floor until
+/// `DifficultyScales.max_dungeon_amount` is reached. when the player dies or
+/// finishes the last floor the run is added too the save profile, the player is
+/// sent back too the hideout and `AppState::RunSummary` is shown.
+/// `CurrentRunInformation` is reset when the next run starts
+#[allow(clippy::too_many_arguments)]
 fn listen_rebuild_dungeon_request(
     mut regen_events: EventReader<RegenerateDungeonEvent>,
     mut cmds: Commands,
     _generator_state: Res<State<GeneratorState>>,
-    actors: Query<
-        Entity,
-        (
-            With<RegistryIdentifier>,
-            Without<PlayerSelectedHero>,
-            Without<Parent>,
-        ),
-    >,
+    difficulty: Res<DifficultyScales>,
+    mut floor: ResMut<DungeonFloor>,
+    mut run_info: ResMut<CurrentRunInformation>,
+    mut save_info: ResMut<PlayerSaveInformation>,
+    mut save_events: EventWriter<SaveProfileEvent>,
+    mut hideout_events: EventWriter<ReturnToHideoutEvent>,
+    mut cleanup: DungeonCleanup,
 ) {
     if let Some(regen_event) = regen_events.read().next() {
         if regen_event.reason == RegenReason::FirstGeneration {
             warn!("laying out first dungeon");
-            cmds.insert_resource(NextState::Pending(GeneratorState::LayoutDungeon));
-            return;
+            floor.0 = 1;
+            save_info.runs_started += 1;
+            *run_info = CurrentRunInformation::default();
+        } else if regen_event.reason == RegenReason::ResumeSuspendedRun {
+            info!("rebuilding suspended dungeon floor {}", floor.0);
+        } else if regen_event.reason == RegenReason::ManualRegen {
+            info!("rebuilding dungeon floor {} on request", floor.0);
+        } else {
+            let floor_time = run_info.floor_timer.elapsed_secs();
+            run_info.floor_times.push(floor_time);
+            run_info.floor_timer.reset();
+            info!("floor {} took {floor_time:.1} seconds", floor.0);
+
+            run_info.outcome = match regen_event.reason {
+                RegenReason::PlayerDeath => Some(RunOutcome::PlayerDied),
+                RegenReason::BossDefeat if floor.0 >= difficulty.max_dungeon_amount => {
+                    Some(RunOutcome::Completed)
+                }
+                _ => None,
+            };
+            if run_info.outcome.is_some() {
+                info!("run finished: {:?}", *run_info);
+                save_info.add_finished_run(&run_info, floor.0);
+                save_events.send(SaveProfileEvent);
+                hideout_events.send(ReturnToHideoutEvent);
+                cmds.insert_resource(NextState::Pending(AppState::RunSummary));
+                regen_events.clear();
+                return;
+            }
+
+            floor.0 += 1;
+            info!("building dungeon floor {}", floor.0);
         }
 
         info!("despawning old actors");
-        actors.iter().for_each(|f| {
-            cmds.entity(f).despawn_recursive();
-        });
+        cleanup.despawn_actors();
 
         cmds.insert_resource(NextState::Pending(GeneratorState::LayoutDungeon));
     }
     regen_events.clear();
 }
 
+/// despawns whats left of the current dungeon, the player is kept
+#[allow(clippy::type_complexity)]
+#[derive(SystemParam)]
+pub struct DungeonCleanup<'w, 's> {
+    /// despawns the entities
+    cmds: Commands<'w, 's>,
+    /// dungeon root, rooms and hallways are its children
+    dungeon: Query<'w, 's, Entity, With<Dungeon>>,
+    /// characters and items not carried by anyone
+    actors: Query<
+        'w,
+        's,
+        Entity,
+        (
+            With<RegistryIdentifier>,
+            Without<PlayerSelectedHero>,
+            Without<Parent>,
+        ),
+    >,
+    /// projectiles and coin drops
+    leftovers: Query<'w, 's, Entity, (Or<(With<TimeToLive>, With<CoinDrop>)>, Without<Parent>)>,
+}
+
+impl DungeonCleanup<'_, '_> {
+    /// despawns actors, projectiles and coin drops
+    pub fn despawn_actors(&mut self) {
+        for ent in self.actors.iter().chain(&self.leftovers) {
+            self.cmds.entity(ent).despawn_recursive();
+        }
+    }
+
+    /// despawns the dungeon and everything left inside it
+    pub fn despawn_dungeon(&mut self) {
+        self.despawn_actors();
+        for ent in &self.dungeon {
+            self.cmds.entity(ent).despawn_recursive();
+        }
+    }
+}
+
 /// send dungeon regen event for debug purposes
 fn debug_regen_dungeon(
     actions: Res<ActionState<action_maps::Gameplay>>,
@@ -169,6 +252,8 @@ pub enum RegenReason {
     PlayerDeath,
     /// player started game and dungeon should be generated
     FirstGeneration,
+    /// player continued a suspended run and its floor should be rebuilt
+    ResumeSuspendedRun,
 }
 
 // /// holds all things related too game data for heirarchy, this might change
@@ -284,6 +369,14 @@ fn populate_start_room(
         spawn_data: (RegistryIdentifier("smallpistol".to_string()), 1),
         requester: dungeon,
     });
+    // armor, trinket and food too try out in develop builds
+    #[cfg(feature = "develop")]
+    for item in ["leathervest", "luckycharm", "apple"] {
+        ew.send(EventSpawnItem {
+            spawn_data: (RegistryIdentifier(item.to_string()), 1),
+            requester: dungeon,
+        });
+    }
 }
 
 /// Takes `TileEnumTags` that is added from ldtk editor
@@ -346,8 +439,7 @@ fn handle_and_removed_misc_tag(
 }
 
 /// returns a point inside the rect with -`inset`. `inset` is multiplied by `TILE_SIZE`
-fn random_point_inside(rect: &Rect, inset: f32) -> Option<Vec2> {
-    let mut rng = ThreadRng::default();
+fn random_point_inside(rect: &Rect, inset: f32, rng: &mut impl Rng) -> Option<Vec2> {
     let useable_space = rect.inflate(-(TILE_SIZE * inset));
     let Rect {
         min: usable_min,
@@ -10,7 +10,15 @@ use crate::{
             components::CharacterType,
             player::PlayerSelectedHero,
         },
-        game_world::dungeonator_v2::components::{BossState, RoomBlueprint},
+        combat::CurrentRunInformation,
+        game_world::{
+            components::CharacterSpawner,
+            dungeonator_v2::{
+                components::{BossState, RoomBlueprint},
+                GeneratorState,
+            },
+            RegenReason, RegenerateDungeonEvent,
+        },
     },
     register_types, AppState,
 };
@@ -27,19 +35,48 @@ impl Plugin for GameProgressPlugin {
         app.add_systems(OnEnter(AppState::StartMenu), initialize_progress_manager);
         app.add_systems(
             FixedUpdate,
-            (update_boss_state, update_player_current_room).run_if(in_state(AppState::PlayingGame)),
+            (
+                update_boss_state,
+                (update_player_current_room, update_cleared_rooms).chain(),
+            )
+                .run_if(in_state(AppState::PlayingGame)),
+        );
+        app.add_systems(
+            Update,
+            tick_floor_timer.run_if(
+                in_state(AppState::PlayingGame)
+                    .and_then(in_state(GeneratorState::FinishedDungeonGen)),
+            ),
         );
     }
 }
 
-/// player progression tracker
+/// player progression tracker.
+///
+/// progress unrelated too the CURRENT dungeon is kept in `PlayerSaveInformation`
 #[derive(Debug, Reflect, Component, Clone)]
 #[reflect(Component)]
 pub struct ProgressManager {
     /// player progress in CURRENT dungeon
     current: CurrentLevelState,
-    /// player progress unrelated too CURRENT dungeon
-    overall: OverallProgressState,
+}
+
+impl ProgressManager {
+    /// rooms the player has cleared on the current floor
+    pub fn cleared_rooms(&self) -> &[Entity] {
+        &self.current.cleared_rooms
+    }
+
+    /// boss combat state on the current floor
+    pub const fn boss_state(&self) -> &BossState {
+        &self.current.boss_state
+    }
+
+    /// restores floor progress from a suspended run
+    pub fn restore(&mut self, cleared_rooms: Vec<Entity>, boss_state: BossState) {
+        self.current.cleared_rooms = cleared_rooms;
+        self.current.boss_state = boss_state;
+    }
 }
 
 /// current dungeon progression for player
@@ -51,23 +88,12 @@ pub struct CurrentLevelState {
     current_room: Option<Entity>,
     /// boss entity id
     boss_id: Option<Entity>,
-}
-
-/// overall progress for player
-#[derive(Debug, Reflect, Component, Clone)]
-pub struct OverallProgressState {
-    /// how much coin player has earned
-    coin: i32,
-    /// how much xp player has earnend
-    xp: i32,
-    /// how many enemies player has defeated
-    kills: i32,
+    /// rooms the player has cleared of enemies
+    cleared_rooms: Vec<Entity>,
 }
 
 /// creates entity for tracking player progress inside dungeon
 fn initialize_progress_manager(mut cmds: Commands) {
-    // load character save state here?
-
     cmds.spawn((
         Name::new("ProgressManager"),
         ProgressManager {
@@ -75,11 +101,7 @@ fn initialize_progress_manager(mut cmds: Commands) {
                 boss_state: BossState::UnSpawned,
                 current_room: None,
                 boss_id: None,
-            },
-            overall: OverallProgressState {
-                coin: 0,
-                xp: 0,
-                kills: 0,
+                cleared_rooms: Vec::new(),
             },
         },
     ));
@@ -108,8 +130,68 @@ fn update_player_current_room(
     progress_manager.current.current_room = current_room;
 }
 
-/// updates boss state based on boss ai status
+/// counts the players current room as cleared once its spawners are
+/// out of waves and no enemies are left inside it
+fn update_cleared_rooms(
+    mut progress_manager: Query<&mut ProgressManager>,
+    mut run_info: ResMut<CurrentRunInformation>,
+    room_query: Query<(&GlobalTransform, &RoomBlueprint)>,
+    spawners: Query<(Entity, &CharacterSpawner)>,
+    characters: Query<(&Transform, &CharacterType)>,
+    parents: Query<&Parent>,
+) {
+    let Ok(mut progress_manager) = progress_manager.get_single_mut() else {
+        return;
+    };
+    let Some(room) = progress_manager.current.current_room else {
+        return;
+    };
+    if progress_manager.current.cleared_rooms.contains(&room) {
+        return;
+    }
+    let Ok((room_transform, room_blueprint)) = room_query.get(room) else {
+        return;
+    };
+
+    let room_spawners: Vec<&CharacterSpawner> = spawners
+        .iter()
+        .filter(|(spawner, _)| parents.iter_ancestors(*spawner).any(|f| f == room))
+        .map(|(_, spawner)| spawner)
+        .collect();
+    // rooms without spawners have nothing too clear
+    if room_spawners.is_empty() {
+        progress_manager.current.cleared_rooms.push(room);
+        return;
+    }
+    let spawners_finished = room_spawners
+        .iter()
+        .all(|spawner| spawner.waves.is_empty() && !spawner.random_wave);
+
+    let room_xy = room_transform.translation().xy();
+    let room_rect = Rect::from_corners(
+        room_xy,
+        room_xy + room_blueprint.room_space.size().as_vec2(),
+    );
+    let enemies_left = characters.iter().any(|(transform, character_type)| {
+        character_type.is_enemy() && room_rect.contains(transform.translation.xy())
+    });
+
+    if spawners_finished && !enemies_left {
+        info!("room cleared");
+        progress_manager.current.cleared_rooms.push(room);
+        run_info.rooms_cleared += 1;
+    }
+}
+
+/// counts time spent on the current dungeon floor
+fn tick_floor_timer(time: Res<Time>, mut run_info: ResMut<CurrentRunInformation>) {
+    run_info.floor_timer.tick(time.delta());
+}
+
+/// updates boss state based on boss ai status,
+/// the next floor is requested when an engaged boss is defeated
 fn update_boss_state(
+    mut regen_events: EventWriter<RegenerateDungeonEvent>,
     mut progress_manager: Query<&mut ProgressManager>,
     boss_query: Query<(Entity, &Transform, &CharacterType)>,
     has_thinkers: Query<&HasThinker>,
@@ -158,7 +240,11 @@ fn update_boss_state(
         }
         None => {
             if current_state == BossState::Engaged {
+                info!("boss defeated, building next floor");
                 progress_manager.current.boss_state = BossState::Defeated;
+                regen_events.send(RegenerateDungeonEvent {
+                    reason: RegenReason::BossDefeat,
+                });
             } else {
                 progress_manager.current.boss_state = BossState::UnSpawned;
             }
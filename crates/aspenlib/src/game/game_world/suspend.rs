@@ -0,0 +1,482 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{
+        attributes_stats::{CharacterStats, EffectQueue},
+        characters::{
+            components::{CharacterInventory, CharacterType, ItemSlot, WeaponSlot},
+            player::PlayerSelectedHero,
+        },
+        combat::{CurrentRunInformation, Dying},
+        game_world::{
+            components::{ActorTeleportEvent, CharacterSpawner, TpTriggerEffect},
+            dungeonator_v2::{
+                components::{
+                    BossState, Dungeon, DungeonFloor, DungeonSettings, RoomBlueprint, RoomID,
+                },
+                hallways::HallWayBlueprint,
+                GeneratorState,
+            },
+            progress::ProgressManager,
+            RegenReason, RegenerateDungeonEvent,
+        },
+        items::{
+            components::ItemFunction,
+            inventory::stow_item,
+            weapons::components::{CurrentAmmo, WeaponCarrier, WeaponHolder},
+        },
+    },
+    loading::{
+        config::save_load::ConfigStorage,
+        profile::ProfileStorage,
+        registry::{ActorRegistry, RegistryIdentifier},
+    },
+    AppState,
+};
+
+/// current suspended run layout version, older snapshots are discarded
+pub const SUSPENDED_RUN_VERSION: u32 = 1;
+
+/// saves the current dungeon floor on `SuspendRunEvent` and rebuilds it on `ContinueRunEvent`
+pub struct SuspendPlugin;
+
+impl Plugin for SuspendPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SuspendRunEvent>()
+            .add_event::<ContinueRunEvent>()
+            .add_systems(
+                Update,
+                (
+                    continue_suspended_run.run_if(
+                        in_state(AppState::StartMenu).and_then(on_event::<ContinueRunEvent>()),
+                    ),
+                    discard_suspended_run.run_if(on_event::<RegenerateDungeonEvent>()),
+                    clear_restored_spawners.run_if(resource_exists::<ResumingRun>),
+                ),
+            )
+            .add_systems(
+                OnEnter(AppState::PlayingGame),
+                start_resumed_run.run_if(
+                    resource_exists::<ResumingRun>.and_then(in_state(GeneratorState::NoDungeon)),
+                ),
+            )
+            .add_systems(
+                OnEnter(GeneratorState::FinishedDungeonGen),
+                restore_suspended_player.run_if(resource_exists::<ResumingRun>),
+            )
+            .add_systems(
+                OnExit(GeneratorState::FinishedDungeonGen),
+                finish_resumed_floor.run_if(resource_exists::<ResumingRun>),
+            )
+            .add_systems(Last, suspend_run.run_if(on_event::<SuspendRunEvent>()));
+    }
+}
+
+/// send too save the current dungeon floor so it can be continued later
+#[derive(Debug, Event)]
+pub struct SuspendRunEvent;
+
+/// send too continue the suspended run of the active save slot
+#[derive(Debug, Event)]
+pub struct ContinueRunEvent;
+
+/// suspended run being rebuilt, removed when the resumed floor is left
+#[derive(Debug, Resource, Deref)]
+pub struct ResumingRun(pub SuspendedRun);
+
+/// everything needed too rebuild a dungeon floor and put the player back into it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedRun {
+    /// `SUSPENDED_RUN_VERSION` this snapshot was written with
+    pub version: u32,
+    /// hero the player was playing as
+    pub hero: RegistryIdentifier,
+    /// dungeon floor the player was on
+    pub floor: i32,
+    /// dungeon root position
+    pub origin: Vec2,
+    /// settings and seed the dungeon was generated with
+    pub settings: DungeonSettings,
+    /// placed rooms
+    pub rooms: Vec<RoomBlueprint>,
+    /// hallways between rooms, paths are rebuilt from the rooms
+    pub hallways: Vec<HallWayBlueprint>,
+    /// rooms the player had cleared
+    pub cleared_rooms: Vec<RoomID>,
+    /// boss combat state
+    pub boss_state: BossState,
+    /// stats tracked for this run
+    pub run_info: CurrentRunInformation,
+    /// player state
+    pub player: SuspendedPlayer,
+}
+
+/// player state saved with a suspended run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedPlayer {
+    /// player position in the dungeon
+    pub position: Vec2,
+    /// player health, mana and attributes
+    pub stats: CharacterStats,
+    /// coins carried
+    pub coins: u64,
+    /// carried armor and trinkets
+    pub items: Vec<(RegistryIdentifier, ItemSlot)>,
+    /// equipped weapons
+    pub weapons: Vec<SuspendedWeapon>,
+    /// weapon slot that was drawn
+    pub drawn_slot: Option<WeaponSlot>,
+}
+
+/// equipped weapon saved with a suspended run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedWeapon {
+    /// weapon registry id
+    pub identifier: RegistryIdentifier,
+    /// slot the weapon was equipped in
+    pub slot: WeaponSlot,
+    /// ammo left in the weapon
+    pub ammo: Option<CurrentAmmo>,
+}
+
+/// storage for the suspended run of save `slot`
+pub fn run_storage(config_storage: &ConfigStorage, slot: u32) -> ConfigStorage {
+    config_storage.sibling(&format!("run_{slot}.save"))
+}
+
+/// reads the suspended run stored in `storage`
+///
+/// # Errors
+/// returns an error if the snapshot cant be read, parsed or is from another version
+pub fn read_suspended_run(storage: &ConfigStorage) -> Result<Option<SuspendedRun>, String> {
+    let Some(text) = storage.read()? else {
+        return Ok(None);
+    };
+    let run: SuspendedRun =
+        ron::from_str(&text).map_err(|e| format!("malformed suspended run: {e}"))?;
+    if run.version != SUSPENDED_RUN_VERSION {
+        return Err(format!(
+            "suspended run version {} is unknown, this game reads version {SUSPENDED_RUN_VERSION}",
+            run.version
+        ));
+    }
+    Ok(Some(run))
+}
+
+/// writes the current dungeon floor and player too the active save slot
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn suspend_run(
+    generator_state: Res<State<GeneratorState>>,
+    config_storage: Res<ConfigStorage>,
+    profile_storage: Res<ProfileStorage>,
+    floor: Res<DungeonFloor>,
+    run_info: Res<CurrentRunInformation>,
+    dungeon: Query<(&Dungeon, &Transform)>,
+    rooms: Query<&RoomBlueprint>,
+    hallways: Query<&HallWayBlueprint>,
+    progress: Query<&ProgressManager>,
+    player: Query<
+        (
+            &RegistryIdentifier,
+            &Transform,
+            &CharacterStats,
+            &CharacterInventory,
+            &WeaponCarrier,
+        ),
+        (With<PlayerSelectedHero>, Without<Dying>),
+    >,
+    weapons: Query<(&RegistryIdentifier, Option<&CurrentAmmo>)>,
+) {
+    if generator_state.get() != &GeneratorState::FinishedDungeonGen {
+        info!("not in a dungeon, nothing too suspend");
+        return;
+    }
+    if !profile_storage.writable {
+        warn!("not suspending run, save slot is read only");
+        return;
+    }
+    let (Ok((dungeon, dungeon_transform)), Ok((hero, transform, stats, inventory, carrier))) =
+        (dungeon.get_single(), player.get_single())
+    else {
+        error!("couldnt suspend run, dungeon or living player is missing");
+        return;
+    };
+
+    let (cleared_rooms, boss_state) = progress.get_single().map_or_else(
+        |_| (Vec::new(), BossState::default()),
+        |progress| {
+            (
+                progress
+                    .cleared_rooms()
+                    .iter()
+                    .filter_map(|f| rooms.get(*f).ok())
+                    .map(|f| f.id)
+                    .collect(),
+                progress.boss_state().clone(),
+            )
+        },
+    );
+
+    let suspended = SuspendedRun {
+        version: SUSPENDED_RUN_VERSION,
+        hero: hero.clone(),
+        floor: floor.0,
+        origin: dungeon_transform.translation.truncate(),
+        settings: dungeon.settings.clone(),
+        rooms: rooms.iter().cloned().collect(),
+        hallways: hallways.iter().cloned().collect(),
+        cleared_rooms,
+        boss_state,
+        run_info: run_info.clone(),
+        player: SuspendedPlayer {
+            position: transform.translation.truncate(),
+            stats: *stats,
+            coins: inventory.coins,
+            items: inventory.items.values().cloned().collect(),
+            weapons: carrier
+                .weapon_slots
+                .iter()
+                .filter_map(|(slot, weapon)| {
+                    let (identifier, ammo) = weapons.get((*weapon)?).ok()?;
+                    Some(SuspendedWeapon {
+                        identifier: identifier.clone(),
+                        slot: *slot,
+                        ammo: ammo.copied(),
+                    })
+                })
+                .collect(),
+            drawn_slot: carrier.drawn_slot,
+        },
+    };
+
+    let storage = run_storage(&config_storage, profile_storage.slot);
+    match ron::ser::to_string_pretty(&suspended, ron::ser::PrettyConfig::default()) {
+        Ok(text) => match storage.write(&text) {
+            Ok(()) => info!("suspended run too {storage}"),
+            Err(e) => error!("couldnt suspend run: {e}"),
+        },
+        Err(e) => error!("couldnt convert suspended run too `String`: {e}"),
+    }
+}
+
+/// loads the suspended run of the active save slot and restores the run progress
+fn continue_suspended_run(
+    mut cmds: Commands,
+    registry: Res<ActorRegistry>,
+    config_storage: Res<ConfigStorage>,
+    profile_storage: Res<ProfileStorage>,
+    mut floor: ResMut<DungeonFloor>,
+    mut run_info: ResMut<CurrentRunInformation>,
+) {
+    let storage = run_storage(&config_storage, profile_storage.slot);
+    match read_suspended_run(&storage) {
+        Ok(Some(suspended))
+            if !registry
+                .characters_of_type(CharacterType::Hero)
+                .any(|(id, _)| *id == suspended.hero) =>
+        {
+            error!(
+                "cant continue suspended run, {:?} is not a hero",
+                suspended.hero
+            );
+        }
+        Ok(Some(suspended)) => {
+            info!("continuing suspended run on floor {}", suspended.floor);
+            floor.0 = suspended.floor;
+            run_info.clone_from(&suspended.run_info);
+            cmds.insert_resource(ResumingRun(suspended));
+        }
+        Ok(None) => warn!("no suspended run at {storage}"),
+        Err(e) => {
+            error!("{storage}: {e}");
+            if let Err(e) = storage.remove() {
+                error!("couldnt remove suspended run: {e}");
+            }
+        }
+    }
+}
+
+/// rebuilds the suspended floor once the player has been selected
+fn start_resumed_run(mut regen_events: EventWriter<RegenerateDungeonEvent>) {
+    regen_events.send(RegenerateDungeonEvent {
+        reason: RegenReason::ResumeSuspendedRun,
+    });
+}
+
+/// resumed floor was left, later floors are generated normally
+fn finish_resumed_floor(mut cmds: Commands) {
+    cmds.remove_resource::<ResumingRun>();
+}
+
+/// suspended runs are removed when a new run starts
+fn discard_suspended_run(
+    mut regen_events: EventReader<RegenerateDungeonEvent>,
+    config_storage: Res<ConfigStorage>,
+    profile_storage: Res<ProfileStorage>,
+) {
+    if regen_events
+        .read()
+        .any(|f| f.reason == RegenReason::FirstGeneration)
+    {
+        let storage = run_storage(&config_storage, profile_storage.slot);
+        if let Err(e) = storage.remove() {
+            error!("couldnt remove suspended run: {e}");
+        }
+    }
+}
+
+/// empties spawners in rooms that were cleared before the run was suspended
+fn clear_restored_spawners(
+    resuming: Res<ResumingRun>,
+    mut spawners: Query<(Entity, &mut CharacterSpawner), Added<CharacterSpawner>>,
+    rooms: Query<&RoomBlueprint>,
+    parents: Query<&Parent>,
+) {
+    for (spawner_entity, mut spawner) in &mut spawners {
+        let cleared = parents
+            .iter_ancestors(spawner_entity)
+            .find_map(|f| rooms.get(f).ok())
+            .is_some_and(|room| resuming.cleared_rooms.contains(&room.id));
+        if cleared {
+            spawner.waves.clear();
+            spawner.random_wave = false;
+        }
+    }
+}
+
+/// puts the player back where they suspended the run with there stats, items and weapons.
+///
+/// the suspended run is removed once it is restored
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+fn restore_suspended_player(
+    mut cmds: Commands,
+    resuming: Res<ResumingRun>,
+    registry: Res<ActorRegistry>,
+    config_storage: Res<ConfigStorage>,
+    profile_storage: Res<ProfileStorage>,
+    mut tp_events: EventWriter<ActorTeleportEvent>,
+    mut player: Query<
+        (
+            Entity,
+            &mut CharacterStats,
+            &mut CharacterInventory,
+            &mut WeaponCarrier,
+            &mut EffectQueue,
+        ),
+        With<PlayerSelectedHero>,
+    >,
+    rooms: Query<(Entity, &RoomBlueprint)>,
+    mut progress: Query<&mut ProgressManager>,
+) {
+    let Ok((player_entity, mut stats, mut inventory, mut carrier, mut effects)) =
+        player.get_single_mut()
+    else {
+        error!("no player too restore suspended run into");
+        return;
+    };
+    let suspended = &resuming.player;
+
+    *stats = suspended.stats;
+    inventory.coins = suspended.coins;
+
+    for weapon in &suspended.weapons {
+        let Some(bundle) = registry.get_weapon(&weapon.identifier) else {
+            warn!(
+                "suspended weapon {:?} is not in registry",
+                weapon.identifier
+            );
+            continue;
+        };
+        let mut bundle = bundle.clone();
+        bundle.holder = WeaponHolder(Some((weapon.slot, player_entity)));
+        bundle.sprite.sprite_bundle.transform = Transform::IDENTITY;
+        let mut weapon_entity = cmds.spawn(bundle);
+        if let Some(ammo) = weapon.ammo {
+            weapon_entity.insert(ammo);
+        }
+        let weapon_entity = weapon_entity.set_parent(player_entity).id();
+        carrier
+            .weapon_slots
+            .insert(weapon.slot, Some(weapon_entity));
+    }
+    carrier.drawn_slot = suspended.drawn_slot;
+
+    for (identifier, slot) in &suspended.items {
+        let Some(bundle) = registry.get_item(identifier) else {
+            warn!("suspended item {:?} is not in registry", identifier);
+            continue;
+        };
+        let function = bundle.function;
+        let item = spawn_restored_item(&mut cmds, bundle.clone(), player_entity);
+        if let ItemFunction::Trinket(effect) = function {
+            effects.push_effect(effect.from_source(item));
+        }
+        inventory.items.insert(item, (identifier.clone(), *slot));
+    }
+
+    if let Ok(mut progress) = progress.get_single_mut() {
+        let cleared_rooms = rooms
+            .iter()
+            .filter(|(_, room)| resuming.cleared_rooms.contains(&room.id))
+            .map(|(entity, _)| entity)
+            .collect();
+        progress.restore(cleared_rooms, resuming.boss_state.clone());
+    }
+
+    tp_events.send(ActorTeleportEvent {
+        tp_type: TpTriggerEffect::Global(suspended.position),
+        target: Some(player_entity),
+        sender: Some(player_entity),
+    });
+
+    info!("restored suspended run");
+    let storage = run_storage(&config_storage, profile_storage.slot);
+    if let Err(e) = storage.remove() {
+        error!("couldnt remove suspended run: {e}");
+    }
+}
+
+/// spawns a carried item from a suspended run inside `player`.
+///
+/// items are spawned without pickup colliders and stowed like a picked up item
+fn spawn_restored_item(cmds: &mut Commands, bundle: impl Bundle, player: Entity) -> Entity {
+    let item = cmds.spawn(bundle).id();
+    stow_item(cmds, item, player);
+    item
+}
+
+/// suspended run restore tests
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::world::CommandQueue;
+    use bevy_rapier2d::prelude::{RigidBody, RigidBodyDisabled, Velocity};
+
+    use super::*;
+    use crate::bundles::RigidBodyBundle;
+
+    /// restored items are stowed in the player with there rigid body disabled
+    #[test]
+    fn restored_item_has_no_active_rigid_body() {
+        let mut world = World::new();
+        let player = world.spawn(Name::new("Player")).id();
+
+        let mut queue = CommandQueue::default();
+        let mut cmds = Commands::new(&mut queue, &world);
+        let mut rigidbody = RigidBodyBundle::DEFAULT_CHARACTER;
+        rigidbody.velocity = Velocity::linear(Vec2::new(10.0, 5.0));
+        let item = spawn_restored_item(&mut cmds, (Name::new("Item"), rigidbody), player);
+        queue.apply(&mut world);
+
+        let item = world.entity(item);
+        assert_eq!(item.get::<RigidBody>(), Some(&RigidBody::Dynamic));
+        assert!(item.contains::<RigidBodyDisabled>());
+        assert_eq!(item.get::<Velocity>(), Some(&Velocity::zero()));
+        assert_eq!(item.get::<Visibility>(), Some(&Visibility::Hidden));
+        assert_eq!(item.get::<Parent>().map(Parent::get), Some(player));
+        assert!(
+            item.get::<Children>().is_none(),
+            "restored items have no colliders"
+        );
+    }
+}
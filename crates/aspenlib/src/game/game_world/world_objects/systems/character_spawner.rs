@@ -1,18 +1,25 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 use bevy_ecs_ldtk::prelude::EntityIid;
 
 use crate::{
     consts::CHARACTER_SPAWNERS_DISABLED,
     game::{
         characters::{components::CharacterType, EventSpawnCharacter},
-        game_world::components::{CharacterSpawner, SpawnerTimer, SpawnerWave},
+        game_world::{
+            components::{CharacterSpawner, SpawnerTimer, SpawnerWave},
+            dungeonator_v2::components::{Dungeon, RoomBlueprint},
+        },
     },
-    loading::registry::ActorRegistry,
+    loading::{config::DifficultyScales, registry::ActorRegistry},
 };
 
 // TODO: get waves from parent (spawner parent is entity layer),
 // spawn each in wave and then remove wave, if no more waves then spawner is `empty` variable on spawner that is wave count, initialized at value and ticks down per wave
-/// spawner timer system, send `SpawnEvent` based on spawner type and timer
+/// spawner timer system, send `SpawnEvent` based on spawner type and timer.
+///
+/// spawner limits are scaled by `DifficultyScales` and spawners in the same dungeon
+/// room share the dungeons `enemy_budget`, or `DifficultyScales.max_enemies_per_room`
+#[allow(clippy::too_many_arguments)]
 pub fn character_spawners_system(
     mut cmds: Commands,
     time: Res<Time>,
@@ -27,16 +34,43 @@ pub fn character_spawners_system(
     spawner_waves: Query<(Entity, &EntityIid, &SpawnerWave)>,
     all_characters: Query<(&Transform, &CharacterType)>,
     actor_registry: Res<ActorRegistry>,
+    difficulty: Res<DifficultyScales>,
+    parents: Query<&Parent>,
+    rooms: Query<(), With<RoomBlueprint>>,
+    dungeon: Query<&Dungeon>,
 ) {
     if CHARACTER_SPAWNERS_DISABLED {
         error_once!("Character spawning disabled");
         return;
     }
 
+    let room_of = |spawner: Entity| parents.iter_ancestors(spawner).find(|f| rooms.contains(*f));
+    let max_per_room = dungeon
+        .get_single()
+        .ok()
+        .and_then(|f| f.settings.enemy_budget)
+        .map_or(difficulty.max_enemies_per_room, |budget| {
+            (budget as f32 * difficulty.enemy_amount_scale()).round() as i32
+        });
+    let max_per_room = usize::try_from(max_per_room).unwrap_or(0);
+    let mut room_enemies: HashMap<Entity, usize> = HashMap::new();
+    for (spawner_entity, _, spawner_state, _, _) in &spawner_query {
+        if let Some(room) = room_of(spawner_entity) {
+            *room_enemies.entry(room).or_default() += spawner_state.spawned_characters.len();
+        }
+    }
+
     for (spawner_entity, spawner_transform, mut spawner_state, mut spawner_timer, _entity_level) in
         &mut spawner_query
     {
         spawner_timer.tick(time.delta());
+        let max_spawned = ((spawner_state.max_spawned as f32 * difficulty.enemy_amount_scale())
+            .round() as usize)
+            .max(1);
+        let room = room_of(spawner_entity);
+        let room_budget = room.map_or(usize::MAX, |room| {
+            max_per_room.saturating_sub(room_enemies.get(&room).copied().unwrap_or(0))
+        });
         let enemies_in_spawn_area = all_characters
             .iter()
             .filter(|(_, character_type)| {
@@ -56,7 +90,9 @@ pub fn character_spawners_system(
             || (spawner_state.waves.is_empty() && !spawner_state.random_wave) // spawner has used all waves and isnt infinite
             || enemies_in_spawn_area // spawner has required amount of enemies near it
                 .max(spawner_state.spawned_characters.len())
-                .ge(&(spawner_state.max_spawned as usize))
+                .ge(&max_spawned)
+            || room_budget == 0
+        // room has its max amount of enemies
         {
             // skip this spawner
             continue;
@@ -64,8 +100,11 @@ pub fn character_spawners_system(
 
         if spawner_state.random_wave {
             // get random entity from actor registry
-            let wave = actor_registry.characters.random_creep_wave();
-            for iid in wave {
+            let wave = actor_registry.random_creep_wave();
+            if let Some(room) = room {
+                *room_enemies.entry(room).or_default() += wave.len().min(room_budget);
+            }
+            for iid in wave.into_iter().take(room_budget) {
                 event_writer.send(EventSpawnCharacter {
                     identifier: iid.clone(),
                     requester: spawner_entity,
@@ -80,7 +119,10 @@ pub fn character_spawners_system(
             .find(|f| **f.1 == spawn_wave_id)
             .expect("wave did not exist in world");
 
-        for iid in &wave.too_spawn {
+        if let Some(room) = room {
+            *room_enemies.entry(room).or_default() += wave.too_spawn.len().min(room_budget);
+        }
+        for iid in wave.too_spawn.iter().take(room_budget) {
             event_writer.send(EventSpawnCharacter {
                 identifier: iid.clone(),
                 requester: spawner_entity,
@@ -1,26 +1,129 @@
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 
+/// button actions players can rebind, `Move` and `Look` use fixed axes
+pub const REBINDABLE_ACTIONS: &[Gameplay] = &[
+    Gameplay::Sprint,
+    Gameplay::Attack,
+    Gameplay::Interact,
+    Gameplay::DropItem,
+    Gameplay::CycleWeapon,
+    Gameplay::UseAction1,
+    Gameplay::UseAction2,
+    Gameplay::UseAction3,
+    Gameplay::UseAction4,
+    Gameplay::UseAction5,
+    Gameplay::ZoomAdd,
+    Gameplay::ZoomSubtract,
+    Gameplay::Pause,
+    Gameplay::DebugF1,
+    Gameplay::DebugF2,
+    Gameplay::Melee,
+    Gameplay::Heal,
+];
+
+/// keys `VirtualDPad::wasd` uses for `Move`, buttons can not be bound too these
+pub const MOVE_KEYS: [KeyCode; 4] = [KeyCode::KeyW, KeyCode::KeyA, KeyCode::KeyS, KeyCode::KeyD];
+
+/// keyboard or mouse button bound too an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, serde::Deserialize, serde::Serialize)]
+pub enum KbmBinding {
+    /// keyboard key
+    Key(KeyCode),
+    /// mouse button
+    Mouse(MouseButton),
+}
+
+impl KbmBinding {
+    /// fixed axis action already using this button, if any
+    pub fn fixed_axis_conflict(self) -> Option<Gameplay> {
+        match self {
+            Self::Key(key) if MOVE_KEYS.contains(&key) => Some(Gameplay::Move),
+            _ => None,
+        }
+    }
+}
+
+/// which device a binding is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BindingDevice {
+    /// keyboard and mouse
+    Kbm,
+    /// gamepad buttons
+    Gamepad,
+}
+
+/// keyboard/mouse and gamepad buttons bound too a single `Gameplay` action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, serde::Deserialize, serde::Serialize)]
+pub struct ActionBinding {
+    /// action these buttons trigger
+    pub action: Gameplay,
+    /// keyboard or mouse button
+    pub keyboard: Option<KbmBinding>,
+    /// gamepad button
+    pub gamepad: Option<GamepadButtonType>,
+}
+
+/// default keyboard/mouse binding for `action`
+pub const fn default_kbm_binding(action: Gameplay) -> Option<KbmBinding> {
+    let key = match action {
+        Gameplay::Sprint => KeyCode::ShiftLeft,
+        Gameplay::Attack => KeyCode::Space,
+        Gameplay::Interact => KeyCode::KeyE,
+        Gameplay::DropItem => KeyCode::KeyQ,
+        Gameplay::CycleWeapon => KeyCode::AltLeft,
+        Gameplay::UseAction1 => KeyCode::Digit1,
+        Gameplay::UseAction2 => KeyCode::Digit2,
+        Gameplay::UseAction3 => KeyCode::Digit3,
+        Gameplay::UseAction4 => KeyCode::Digit4,
+        Gameplay::UseAction5 => KeyCode::Digit5,
+        Gameplay::ZoomAdd => KeyCode::NumpadAdd,
+        Gameplay::ZoomSubtract => KeyCode::NumpadSubtract,
+        Gameplay::Pause => KeyCode::Escape,
+        Gameplay::DebugF1 => KeyCode::F1,
+        Gameplay::DebugF2 => KeyCode::F2,
+        Gameplay::Melee => KeyCode::KeyF,
+        Gameplay::Heal => KeyCode::KeyC,
+        Gameplay::Look | Gameplay::Move => return None,
+    };
+    Some(KbmBinding::Key(key))
+}
+
+/// default gamepad binding for `action`
+pub const fn default_gamepad_binding(action: Gameplay) -> Option<GamepadButtonType> {
+    match action {
+        Gameplay::Sprint => Some(GamepadButtonType::West),
+        Gameplay::Attack => Some(GamepadButtonType::South),
+        Gameplay::Interact => Some(GamepadButtonType::East),
+        Gameplay::CycleWeapon => Some(GamepadButtonType::North),
+        Gameplay::ZoomAdd => Some(GamepadButtonType::DPadUp),
+        Gameplay::ZoomSubtract => Some(GamepadButtonType::DPadDown),
+        Gameplay::Pause => Some(GamepadButtonType::Start),
+        Gameplay::Melee => Some(GamepadButtonType::DPadLeft),
+        Gameplay::Heal => Some(GamepadButtonType::DPadRight),
+        _ => None,
+    }
+}
+
+/// default bindings for every rebindable action
+pub fn default_bindings() -> Vec<ActionBinding> {
+    REBINDABLE_ACTIONS
+        .iter()
+        .map(|action| ActionBinding {
+            action: *action,
+            keyboard: default_kbm_binding(*action),
+            gamepad: default_gamepad_binding(*action),
+        })
+        .collect()
+}
+
 /// default keyboard/mouse input map
 pub fn build_kbm_map(input_map: &mut InputMap<Gameplay>) {
-    input_map.insert_multiple([
-        (Gameplay::Sprint, KeyCode::ShiftLeft),
-        (Gameplay::Attack, KeyCode::Space),
-        (Gameplay::Interact, KeyCode::KeyE),
-        (Gameplay::CycleWeapon, KeyCode::AltLeft),
-        (Gameplay::UseAction1, KeyCode::Digit1),
-        (Gameplay::UseAction2, KeyCode::Digit2),
-        (Gameplay::UseAction3, KeyCode::Digit3),
-        (Gameplay::UseAction4, KeyCode::Digit4),
-        (Gameplay::UseAction5, KeyCode::Digit5),
-        (Gameplay::ZoomAdd, KeyCode::NumpadAdd),
-        (Gameplay::ZoomSubtract, KeyCode::NumpadSubtract),
-        (Gameplay::Pause, KeyCode::Escape),
-        (Gameplay::DebugF1, KeyCode::F1),
-        (Gameplay::DebugF2, KeyCode::F2),
-        (Gameplay::Melee, KeyCode::KeyF),
-        (Gameplay::Heal, KeyCode::KeyC),
-    ]);
+    for action in REBINDABLE_ACTIONS {
+        if let Some(binding) = default_kbm_binding(*action) {
+            insert_kbm_binding(input_map, *action, binding);
+        }
+    }
     input_map.insert_dual_axis(Gameplay::Move, VirtualDPad::wasd());
 }
 
@@ -28,17 +131,19 @@ pub fn build_kbm_map(input_map: &mut InputMap<Gameplay>) {
 pub fn build_gamepad_map(input_map: &mut InputMap<Gameplay>) {
     input_map.insert_dual_axis(Gameplay::Move, GamepadStick::LEFT);
     input_map.insert_dual_axis(Gameplay::Look, GamepadStick::RIGHT);
-    input_map.insert_multiple([
-        (Gameplay::Sprint, GamepadButtonType::West),
-        (Gameplay::Attack, GamepadButtonType::South),
-        (Gameplay::Interact, GamepadButtonType::East),
-        (Gameplay::CycleWeapon, GamepadButtonType::North),
-        (Gameplay::ZoomAdd, GamepadButtonType::DPadUp),
-        (Gameplay::ZoomSubtract, GamepadButtonType::DPadDown),
-        (Gameplay::Pause, GamepadButtonType::Start),
-        (Gameplay::Melee, GamepadButtonType::DPadLeft),
-        (Gameplay::Heal, GamepadButtonType::DPadRight),
-    ]);
+    for action in REBINDABLE_ACTIONS {
+        if let Some(button) = default_gamepad_binding(*action) {
+            input_map.insert(*action, button);
+        }
+    }
+}
+
+/// adds keyboard or mouse `binding` too `input_map`
+fn insert_kbm_binding(input_map: &mut InputMap<Gameplay>, action: Gameplay, binding: KbmBinding) {
+    match binding {
+        KbmBinding::Key(key) => input_map.insert(action, key),
+        KbmBinding::Mouse(button) => input_map.insert(action, button),
+    };
 }
 
 impl Gameplay {
@@ -51,10 +156,53 @@ impl Gameplay {
 
         input_map
     }
+
+    /// input map from configured bindings, actions missing from `bindings` use defaults
+    pub fn input_map_from_bindings(bindings: &[ActionBinding]) -> InputMap<Self> {
+        let mut input_map: InputMap<Self> = InputMap::default();
+        input_map.insert_dual_axis(Self::Move, VirtualDPad::wasd());
+        input_map.insert_dual_axis(Self::Move, GamepadStick::LEFT);
+        input_map.insert_dual_axis(Self::Look, GamepadStick::RIGHT);
+
+        for action in REBINDABLE_ACTIONS {
+            let binding = bindings
+                .iter()
+                .find(|f| f.action == *action)
+                .copied()
+                .unwrap_or(ActionBinding {
+                    action: *action,
+                    keyboard: default_kbm_binding(*action),
+                    gamepad: default_gamepad_binding(*action),
+                });
+            if let Some(kbm) = binding.keyboard {
+                if let Some(fixed) = kbm.fixed_axis_conflict() {
+                    warn!("{action:?} can not use {kbm:?}, it is used by {fixed:?}");
+                } else {
+                    insert_kbm_binding(&mut input_map, *action, kbm);
+                }
+            }
+            if let Some(button) = binding.gamepad {
+                input_map.insert(*action, button);
+            }
+        }
+
+        input_map
+    }
 }
 
 /// non menu actions
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug, Reflect)]
+#[derive(
+    Actionlike,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Hash,
+    Debug,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+)]
 pub enum Gameplay {
     /// cursor position onscreen
     /// gathered from gamepad sticks and translated into a cursor position
@@ -70,6 +218,8 @@ pub enum Gameplay {
     Attack,
     /// E for keyboard
     Interact,
+    /// Q for keyboard, drops the last carried armor or trinket
+    DropItem,
     /// cycles equipped weapons
     CycleWeapon,
 
@@ -97,6 +247,6 @@ pub enum Gameplay {
     DebugF2,
     /// F for keyboard
     Melee,
-    /// Q for keyboard
+    /// C for keyboard
     Heal,
 }
@@ -6,7 +6,10 @@ use leafwing_input_manager::{
     prelude::{ActionState, InputMap},
 };
 
-use crate::{loading::splashscreen::MainCamera, register_types};
+use crate::{
+    loading::{config::ControlSettings, splashscreen::MainCamera},
+    register_types,
+};
 
 // / entity selection in playing stage handled with this plugin
 // / TODO: implement targeting system for interactions/combat
@@ -59,6 +62,10 @@ impl Plugin for InputPlugin {
             PreUpdate,
             (update_cursor_position_resource.before(AspenInputSystemSet::SoftwareCursor),),
         );
+        app.add_systems(
+            Update,
+            apply_control_settings.run_if(resource_exists_and_changed::<ControlSettings>),
+        );
 
         app.configure_sets(
             PreUpdate,
@@ -84,6 +91,14 @@ pub struct AspenCursorPosition {
     pub screen: Vec2,
 }
 
+/// rebuilds `InputMap<Gameplay>` from `ControlSettings` when bindings are loaded or changed
+fn apply_control_settings(control_settings: Res<ControlSettings>, mut cmds: Commands) {
+    info!("applying control bindings");
+    cmds.insert_resource(action_maps::Gameplay::input_map_from_bindings(
+        &control_settings.bindings,
+    ));
+}
+
 /// updates cursor position resource with joystick priority
 fn update_cursor_position_resource(
     window_query: Query<&Window, With<PrimaryWindow>>,
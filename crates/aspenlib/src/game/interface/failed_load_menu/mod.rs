@@ -0,0 +1,198 @@
+use crate::{
+    game::{
+        interface::{
+            ui_widgets::{spawn_button, spawn_menu_title},
+            InterfaceRootTag,
+        },
+        AppState,
+    },
+    loading::{
+        assets::AspenInitHandles,
+        failures::{AssetLoadFailure, AssetLoadFailures},
+        splashscreen::OnlySplashScreen,
+    },
+    utilities::despawn_with,
+};
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+/// shows what failed too load when `AppState::FailedLoadInit` or `AppState::FailedLoadMenu` is entered
+pub struct FailedLoadMenuPlugin;
+
+impl Plugin for FailedLoadMenuPlugin {
+    fn build(&self, app: &mut App) {
+        for state in [AppState::FailedLoadInit, AppState::FailedLoadMenu] {
+            app.add_systems(
+                OnEnter(state),
+                (despawn_with::<OnlySplashScreen>, spawn_failed_load_menu),
+            );
+            app.add_systems(OnExit(state), despawn_with::<FailedLoadMenuTag>);
+        }
+        app.add_systems(
+            Update,
+            (retry_button_interaction, quit_button_interaction).run_if(
+                in_state(AppState::FailedLoadInit).or_else(in_state(AppState::FailedLoadMenu)),
+            ),
+        );
+    }
+}
+
+/// failed load menu marker component for querys
+#[derive(Debug, Component)]
+pub struct FailedLoadMenuTag;
+
+/// marks retry button for query
+#[derive(Debug, Component)]
+pub struct RetryLoadTag;
+
+/// marks quit button for query
+#[derive(Debug, Component)]
+pub struct QuitGameTag;
+
+/// formats a failure as `key (pack): path - error`
+fn describe_failure(failure: &AssetLoadFailure) -> String {
+    match &failure.key {
+        Some((key, pack)) => format!("{key} ({pack}): {} - {}", failure.path, failure.error),
+        None => format!("{}: {}", failure.path, failure.error),
+    }
+}
+
+/// spawns failed load menu with a line for each failed asset
+fn spawn_failed_load_menu(
+    mut cmds: Commands,
+    game_state: Res<State<AppState>>,
+    init_assets: Option<Res<AspenInitHandles>>,
+    failures: Res<AssetLoadFailures>,
+    interface_root: Query<Entity, With<InterfaceRootTag>>,
+) {
+    // init assets are missing if `AppState::BootingApp` failed, use bevys default font
+    let (font_title, font_regular) = init_assets.map_or_else(
+        || (Handle::default(), Handle::default()),
+        |f| (f.font_title.clone(), f.font_regular.clone()),
+    );
+    let title = if game_state.get() == &AppState::FailedLoadInit {
+        "Failed Loading Game"
+    } else {
+        "Failed Loading Packs"
+    };
+
+    error!("{title}: {} assets failed too load", failures.len());
+    for failure in failures.iter() {
+        error!("{}", describe_failure(failure));
+    }
+
+    let Ok(interface_root) = interface_root.get_single() else {
+        error!("interface root missing, cant show failed load menu");
+        return;
+    };
+
+    cmds.entity(interface_root).with_children(|children| {
+        children
+            .spawn((
+                Name::new("FailedLoadMenu"),
+                FailedLoadMenuTag,
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        flex_direction: FlexDirection::Column,
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(crate::colors::BLACK.into()),
+                    ..default()
+                },
+            ))
+            .with_children(|menu| {
+                spawn_menu_title(menu, font_title, title);
+                menu.spawn((
+                    Name::new("FailureList"),
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            flex_grow: 1.0,
+                            overflow: Overflow::clip(),
+                            margin: UiRect::horizontal(Val::Percent(5.0)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    let lines = if failures.is_empty() {
+                        vec!["no failed assets were reported, check the log".to_string()]
+                    } else {
+                        failures.iter().map(describe_failure).collect()
+                    };
+                    for line in lines {
+                        list.spawn((
+                            Name::new("FailedAsset"),
+                            TextBundle::from_section(
+                                line,
+                                TextStyle {
+                                    font: font_regular.clone(),
+                                    font_size: 14.0,
+                                    color: crate::colors::RED.into(),
+                                },
+                            ),
+                        ));
+                    }
+                });
+                menu.spawn((
+                    Name::new("ButtonContainer"),
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            justify_content: JustifyContent::SpaceEvenly,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::vertical(Val::Px(15.0)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|buttons| {
+                    spawn_button(buttons, font_regular.clone(), "Retry", RetryLoadTag);
+                    spawn_button(buttons, font_regular.clone(), "Quit", QuitGameTag);
+                });
+            });
+    });
+}
+
+/// reloads failed assets and goes back too the loading state that failed
+fn retry_button_interaction(
+    mut cmds: Commands,
+    asset_server: Res<AssetServer>,
+    game_state: Res<State<AppState>>,
+    mut failures: ResMut<AssetLoadFailures>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<RetryLoadTag>)>,
+) {
+    for interaction in &interaction_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        for failure in failures.drain(..) {
+            asset_server.reload(failure.path);
+        }
+        let retry_state = if game_state.get() == &AppState::FailedLoadInit {
+            AppState::BootingApp
+        } else {
+            AppState::Loading
+        };
+        info!("retrying {:?}", retry_state);
+        cmds.insert_resource(NextState::Pending(retry_state));
+    }
+}
+
+/// exits game from failed load menu
+fn quit_button_interaction(
+    mut exit_event_writer: EventWriter<AppExit>,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<QuitGameTag>)>,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            exit_event_writer.send(AppExit::Success);
+        }
+    }
+}
@@ -2,10 +2,16 @@ use crate::game::AppState;
 use bevy::prelude::*;
 use rand::Rng;
 
+/// failed loading screen with retry/quit
+pub mod failed_load_menu;
 /// pause menu module
 pub mod pause_menu;
 /// playing game ui
 pub mod playing_ui;
+/// save slot picker
+pub mod profile_menu;
+/// end of run summary
+pub mod run_summary;
 /// game configuration menu
 pub mod settings_menu;
 /// start menu module
@@ -25,14 +31,26 @@ impl Plugin for InterfacePlugin {
         app.add_plugins((
             // start menu
             start_menu::StartMenuPlugin,
+            // save slots
+            profile_menu::ProfileMenuPlugin,
             // pause menu
             pause_menu::PauseMenuPlugin,
+            // run summary
+            run_summary::RunSummaryPlugin,
             // settings
             settings_menu::SettingsMenuPlugin,
             //playing ui
             playing_ui::PlayingUiPlugin,
+            // asset loading failed
+            failed_load_menu::FailedLoadMenuPlugin,
+            // sliders, toggles and dropdowns
+            ui_widgets::UiWidgetsPlugin,
         ));
-        app.add_systems(OnEnter(AppState::BootingApp), (spawn_interface_root,));
+        // `AppState::BootingApp` is entered again when retrying a failed load
+        app.add_systems(
+            OnEnter(AppState::BootingApp),
+            (spawn_interface_root.run_if(not(any_with_component::<InterfaceRootTag>)),),
+        );
         app.add_systems(Update, (update_button_color,));
     }
 }
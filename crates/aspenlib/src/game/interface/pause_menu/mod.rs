@@ -1,5 +1,6 @@
 use crate::{
     game::{
+        game_world::suspend::SuspendRunEvent,
         input::action_maps,
         interface::{
             random_color,
@@ -148,13 +149,15 @@ fn continue_button_interaction(
     }
 }
 
-/// send quit game request
+/// suspends the current run and sends quit game request
 fn exit_button_interaction(
+    mut suspend_events: EventWriter<SuspendRunEvent>,
     mut exit_event_writer: EventWriter<AppExit>,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<ExitGameTag>)>,
 ) {
     for interaction in &interaction_query {
         if matches!(interaction, Interaction::Pressed) {
+            suspend_events.send(SuspendRunEvent);
             exit_event_writer.send(AppExit::Success);
         }
     }
@@ -0,0 +1,508 @@
+use bevy::{
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+use crate::{
+    game::{
+        combat::PlayerSaveInformation,
+        interface::{
+            random_color,
+            start_menu::StartMenuTag,
+            ui_widgets::{spawn_button, spawn_menu_title, widget_text_style},
+            InterfaceRootTag,
+        },
+        AppState,
+    },
+    loading::{
+        assets::AspenInitHandles,
+        config::{save_load::ConfigStorage, ConfigFile, SaveSettingsEvent},
+        profile::{
+            encode_profile, load_slot, read_slot, slot_storage, unix_time_now, write_profile,
+            ProfileStorage, SlotContents, MAX_SAVE_SLOTS,
+        },
+    },
+};
+
+/// longest name a profile can be renamed too
+const MAX_PROFILE_NAME_LEN: usize = 24;
+
+/// save slot picker shown before hero selection
+pub struct ProfileMenuPlugin;
+
+impl Plugin for ProfileMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<OpenProfileMenuEvent>()
+            .init_resource::<SaveSlots>()
+            .init_resource::<ProfileMenuState>()
+            .add_systems(OnExit(AppState::Loading), spawn_profile_menu)
+            .add_systems(
+                Update,
+                (
+                    open_profile_menu.run_if(on_event::<OpenProfileMenuEvent>()),
+                    profile_back_interaction,
+                    slot_button_interaction,
+                    rename_text_input
+                        .run_if(|state: Res<ProfileMenuState>| state.renaming.is_some()),
+                    rebuild_slot_list.run_if(
+                        resource_changed::<SaveSlots>.or_else(resource_changed::<ProfileMenuState>),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::StartMenu)),
+            );
+    }
+}
+
+/// send too show the profile picker
+#[derive(Debug, Event)]
+pub struct OpenProfileMenuEvent;
+
+/// profile menu marker component for querys
+#[derive(Debug, Component)]
+pub struct ProfileMenuTag;
+
+/// container rebuilt with a row for each save slot
+#[derive(Debug, Component)]
+pub struct ProfileSlotListTag;
+
+/// marks button that goes back too the start menu
+#[derive(Debug, Component)]
+pub struct ProfileBackTag;
+
+/// button acting on a save slot
+#[derive(Debug, Clone, Copy, Component)]
+pub struct ProfileSlotButton {
+    /// save slot this button is for
+    pub slot: u32,
+    /// what the button does
+    pub action: SlotAction,
+}
+
+/// things that can be done too a save slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotAction {
+    /// load the slot, creating it if empty, and pick a hero
+    Play,
+    /// type a new name for the slot
+    Rename,
+    /// copy the slot into the first empty slot
+    Copy,
+    /// delete the slot, needs too be pressed twice
+    Delete,
+}
+
+/// contents of every save slot, read when the profile menu opens
+#[derive(Debug, Default, Resource)]
+pub struct SaveSlots(pub Vec<SlotContents>);
+
+impl SaveSlots {
+    /// reads every save slot from storage
+    pub fn read(config_storage: &ConfigStorage) -> Self {
+        Self(
+            (0..MAX_SAVE_SLOTS)
+                .map(|slot| read_slot(config_storage, slot))
+                .collect(),
+        )
+    }
+
+    /// first slot with nothing saved in it
+    fn first_empty(&self) -> Option<u32> {
+        self.0
+            .iter()
+            .position(|f| matches!(f, SlotContents::Empty))
+            .and_then(|f| u32::try_from(f).ok())
+    }
+
+    /// profile in `slot` if it can be read
+    fn profile(&self, slot: u32) -> Option<&PlayerSaveInformation> {
+        match self.0.get(slot as usize) {
+            Some(SlotContents::Profile(profile)) => Some(profile),
+            _ => None,
+        }
+    }
+}
+
+/// rename and delete state for the profile menu
+#[derive(Debug, Default, Resource)]
+pub struct ProfileMenuState {
+    /// slot being renamed and the name typed so far
+    pub renaming: Option<(u32, String)>,
+    /// slot that gets deleted if delete is pressed again
+    pub pending_delete: Option<u32>,
+    /// result of the last action
+    pub notice: String,
+}
+
+/// name used for profiles that were never named
+fn default_profile_name(slot: u32) -> String {
+    format!("Profile {}", slot + 1)
+}
+
+/// `last_played` as text like `3 days ago`
+fn last_played_text(last_played: u64) -> String {
+    if last_played == 0 {
+        return "never played".to_string();
+    }
+    let minutes = unix_time_now().saturating_sub(last_played) / 60;
+    match minutes {
+        0 => "just now".to_string(),
+        1..=59 => format!("{minutes} minutes ago"),
+        60..=1439 => format!("{} hours ago", minutes / 60),
+        _ => format!("{} days ago", minutes / 1440),
+    }
+}
+
+/// spawns the hidden profile menu
+fn spawn_profile_menu(
+    mut cmds: Commands,
+    assets: Res<AspenInitHandles>,
+    interface_root: Query<Entity, With<InterfaceRootTag>>,
+) {
+    cmds.entity(interface_root.single())
+        .with_children(|children| {
+            children
+                .spawn((
+                    Name::new("ProfileMenu"),
+                    ProfileMenuTag,
+                    NodeBundle {
+                        style: Style {
+                            display: Display::None,
+                            position_type: PositionType::Absolute,
+                            overflow: Overflow::clip(),
+                            flex_direction: FlexDirection::Column,
+                            min_height: Val::Percent(60.0),
+                            min_width: Val::Vw(60.0),
+                            align_self: AlignSelf::Center,
+                            justify_content: JustifyContent::FlexStart,
+                            margin: UiRect {
+                                left: Val::Vw(20.0),
+                                right: Val::Px(0.0),
+                                top: Val::Percent(10.0),
+                                bottom: Val::Percent(10.0),
+                            },
+                            ..default()
+                        },
+                        z_index: ZIndex::Local(2),
+                        background_color: BackgroundColor(random_color(Some(0.9))),
+                        ..default()
+                    },
+                ))
+                .with_children(|profile_menu| {
+                    spawn_menu_title(profile_menu, assets.font_title.clone(), "Profiles");
+                    profile_menu.spawn((
+                        Name::new("SlotList"),
+                        ProfileSlotListTag,
+                        NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                width: Val::Percent(90.0),
+                                margin: UiRect::horizontal(Val::Auto),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                    ));
+                    profile_menu
+                        .spawn(NodeBundle {
+                            style: Style {
+                                justify_content: JustifyContent::Center,
+                                margin: UiRect::all(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|buttons| {
+                            spawn_button(
+                                buttons,
+                                assets.font_regular.clone(),
+                                "Back",
+                                ProfileBackTag,
+                            );
+                        });
+                });
+        });
+}
+
+/// shows the profile menu with freshly read save slots
+fn open_profile_menu(
+    mut open_events: EventReader<OpenProfileMenuEvent>,
+    config_storage: Res<ConfigStorage>,
+    mut slots: ResMut<SaveSlots>,
+    mut state: ResMut<ProfileMenuState>,
+    mut profile_menu: Query<&mut Style, With<ProfileMenuTag>>,
+) {
+    open_events.clear();
+    *slots = SaveSlots::read(&config_storage);
+    *state = ProfileMenuState {
+        notice: "pick a profile too play".to_string(),
+        ..default()
+    };
+    profile_menu.single_mut().display = Display::Flex;
+}
+
+/// hides the profile menu and shows the start menu again
+fn profile_back_interaction(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ProfileBackTag>)>,
+    mut profile_menu: Query<&mut Style, (With<ProfileMenuTag>, Without<StartMenuTag>)>,
+    mut start_menu: Query<&mut Style, (With<StartMenuTag>, Without<ProfileMenuTag>)>,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            profile_menu.single_mut().display = Display::None;
+            start_menu.single_mut().display = Display::Flex;
+        }
+    }
+}
+
+/// plays, renames, copies or deletes the slot a button is for
+#[allow(clippy::too_many_arguments)]
+fn slot_button_interaction(
+    interaction_query: Query<(&Interaction, &ProfileSlotButton), Changed<Interaction>>,
+    config_storage: Res<ConfigStorage>,
+    mut slots: ResMut<SaveSlots>,
+    mut state: ResMut<ProfileMenuState>,
+    mut profile: ResMut<PlayerSaveInformation>,
+    mut profile_storage: ResMut<ProfileStorage>,
+    mut cfg_file: ResMut<ConfigFile>,
+    mut save_settings: EventWriter<SaveSettingsEvent>,
+    mut profile_menu: Query<&mut Style, With<ProfileMenuTag>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let slot = button.slot;
+        if button.action != SlotAction::Delete {
+            state.pending_delete = None;
+        }
+        state.renaming = None;
+
+        match button.action {
+            SlotAction::Play => {
+                let (mut new_profile, new_storage) = load_slot(&config_storage, slot);
+                if new_profile.profile_name.is_empty() {
+                    new_profile.profile_name = default_profile_name(slot);
+                }
+                if new_storage.writable {
+                    if let Err(e) = write_profile(&new_storage.storage, &mut new_profile) {
+                        error!("couldnt save profile: {e}");
+                    }
+                }
+                info!("playing as {}", new_profile.profile_name);
+                *profile = new_profile;
+                *profile_storage = new_storage;
+
+                cfg_file.active_save_slot = slot;
+                save_settings.send(SaveSettingsEvent);
+                profile_menu.single_mut().display = Display::None;
+            }
+            SlotAction::Rename => match slots.profile(slot) {
+                Some(slot_profile) => {
+                    state.renaming = Some((slot, slot_profile.profile_name.clone()));
+                    state.notice = "type a name, Enter saves, Escape cancels".to_string();
+                }
+                None => state.notice = "only saved profiles can be renamed".to_string(),
+            },
+            SlotAction::Copy => {
+                let Some(slot_profile) = slots.profile(slot) else {
+                    state.notice = "only saved profiles can be copied".to_string();
+                    continue;
+                };
+                let Some(target) = slots.first_empty() else {
+                    state.notice = "no empty slots, delete a profile first".to_string();
+                    continue;
+                };
+                let mut copy = slot_profile.clone();
+                copy.profile_name = format!("{} copy", copy.profile_name);
+                state.notice = match encode_profile(&copy)
+                    .and_then(|text| slot_storage(&config_storage, target).write(&text))
+                {
+                    Ok(()) => format!(
+                        "copied {} too slot {}",
+                        slot_profile.profile_name,
+                        target + 1
+                    ),
+                    Err(e) => format!("couldnt copy profile: {e}"),
+                };
+                *slots = SaveSlots::read(&config_storage);
+            }
+            SlotAction::Delete => {
+                if state.pending_delete != Some(slot) {
+                    state.pending_delete = Some(slot);
+                    state.notice = format!("press delete again too delete slot {}", slot + 1);
+                    continue;
+                }
+                state.pending_delete = None;
+                state.notice = match slot_storage(&config_storage, slot).remove() {
+                    Ok(()) => format!("deleted slot {}", slot + 1),
+                    Err(e) => format!("couldnt delete profile: {e}"),
+                };
+                // dont recreate the deleted profile on exit
+                if profile_storage.slot == slot {
+                    *profile = PlayerSaveInformation::default();
+                    profile_storage.writable = false;
+                }
+                *slots = SaveSlots::read(&config_storage);
+            }
+        }
+    }
+}
+
+/// types into the name of the slot being renamed
+fn rename_text_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    config_storage: Res<ConfigStorage>,
+    mut slots: ResMut<SaveSlots>,
+    mut state: ResMut<ProfileMenuState>,
+    mut profile: ResMut<PlayerSaveInformation>,
+    profile_storage: Res<ProfileStorage>,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        let Some((slot, name)) = state.renaming.as_mut() else {
+            return;
+        };
+        let slot = *slot;
+
+        match &event.logical_key {
+            Key::Character(text) => {
+                for character in text.chars().filter(|f| !f.is_control()) {
+                    if name.chars().count() < MAX_PROFILE_NAME_LEN {
+                        name.push(character);
+                    }
+                }
+            }
+            Key::Space if name.chars().count() < MAX_PROFILE_NAME_LEN => name.push(' '),
+            Key::Backspace => {
+                name.pop();
+            }
+            Key::Escape => {
+                state.renaming = None;
+                state.notice = "rename cancelled".to_string();
+            }
+            Key::Enter => {
+                let name = name.trim().to_string();
+                state.renaming = None;
+                let Some(mut slot_profile) = slots.profile(slot).cloned() else {
+                    return;
+                };
+                if name.is_empty() {
+                    state.notice = "profile names cant be empty".to_string();
+                    return;
+                }
+                slot_profile.profile_name.clone_from(&name);
+                state.notice = match encode_profile(&slot_profile)
+                    .and_then(|text| slot_storage(&config_storage, slot).write(&text))
+                {
+                    Ok(()) => format!("renamed slot {} too {name}", slot + 1),
+                    Err(e) => format!("couldnt rename profile: {e}"),
+                };
+                if profile_storage.slot == slot {
+                    profile.profile_name = name;
+                }
+                *slots = SaveSlots::read(&config_storage);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// respawns a row for each save slot
+fn rebuild_slot_list(
+    mut cmds: Commands,
+    assets: Res<AspenInitHandles>,
+    slots: Res<SaveSlots>,
+    state: Res<ProfileMenuState>,
+    profile_storage: Res<ProfileStorage>,
+    slot_list: Query<Entity, With<ProfileSlotListTag>>,
+) {
+    let Ok(slot_list) = slot_list.get_single() else {
+        return;
+    };
+    let font = assets.font_regular.clone();
+
+    cmds.entity(slot_list)
+        .despawn_descendants()
+        .with_children(|list| {
+            list.spawn((
+                Name::new("ProfileNotice"),
+                TextBundle::from_section(state.notice.clone(), widget_text_style(font.clone())),
+            ));
+
+            for (slot, contents) in (0..MAX_SAVE_SLOTS).zip(&slots.0) {
+                let active = if profile_storage.slot == slot {
+                    "> "
+                } else {
+                    ""
+                };
+                let (label, play) = match contents {
+                    SlotContents::Empty => (format!("{}: empty", slot + 1), "New"),
+                    SlotContents::Unreadable(e) => {
+                        (format!("{}: unreadable, {e}", slot + 1), "Play")
+                    }
+                    SlotContents::Profile(slot_profile) => {
+                        let name = match &state.renaming {
+                            Some((renaming, typed)) if *renaming == slot => format!("{typed}_"),
+                            _ if slot_profile.profile_name.is_empty() => default_profile_name(slot),
+                            _ => slot_profile.profile_name.clone(),
+                        };
+                        (
+                            format!(
+                                "{}: {name}, {}, {} runs completed, {} deaths",
+                                slot + 1,
+                                last_played_text(slot_profile.last_played),
+                                slot_profile.runs_completed,
+                                slot_profile.total_deaths
+                            ),
+                            "Play",
+                        )
+                    }
+                };
+
+                list.spawn((
+                    Name::new(format!("Slot{slot}Row")),
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            justify_content: JustifyContent::SpaceBetween,
+                            width: Val::Percent(100.0),
+                            margin: UiRect::vertical(Val::Px(4.0)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|row| {
+                    row.spawn((
+                        Name::new("SlotLabel"),
+                        TextBundle::from_section(
+                            format!("{active}{label}"),
+                            widget_text_style(font.clone()),
+                        ),
+                    ));
+                    row.spawn(NodeBundle::default()).with_children(|buttons| {
+                        for (text, action) in [
+                            (play, SlotAction::Play),
+                            ("Rename", SlotAction::Rename),
+                            ("Copy", SlotAction::Copy),
+                            ("Delete", SlotAction::Delete),
+                        ] {
+                            spawn_button(
+                                buttons,
+                                font.clone(),
+                                text,
+                                ProfileSlotButton { slot, action },
+                            );
+                        }
+                    });
+                });
+            }
+        });
+}
@@ -0,0 +1,223 @@
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::{
+    game::{
+        combat::{CurrentRunInformation, RunOutcome},
+        game_world::{
+            dungeonator_v2::components::DungeonFloor, DungeonCleanup, RegenReason,
+            RegenerateDungeonEvent,
+        },
+        interface::{
+            random_color,
+            ui_widgets::{spawn_button, spawn_menu_title, widget_text_style},
+            InterfaceRootTag,
+        },
+        AppState,
+    },
+    loading::{assets::AspenInitHandles, config::DifficultyScales},
+};
+
+/// end of run summary shown when the player dies or finishes the last floor
+pub struct RunSummaryPlugin;
+
+impl Plugin for RunSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(AppState::Loading), spawn_run_summary)
+            .add_systems(OnEnter(AppState::RunSummary), show_run_summary)
+            .add_systems(OnExit(AppState::RunSummary), hide_run_summary)
+            .add_systems(
+                Update,
+                (hideout_button_interaction, new_run_button_interaction)
+                    .run_if(in_state(AppState::RunSummary)),
+            );
+    }
+}
+
+/// run summary marker component for querys
+#[derive(Debug, Component)]
+pub struct RunSummaryTag;
+
+/// marks run summary stats text for query
+#[derive(Debug, Component)]
+pub struct RunSummaryTextTag;
+
+/// marks return too hideout button for query
+#[derive(Debug, Component)]
+pub struct ReturnToHideoutTag;
+
+/// marks new run button for query
+#[derive(Debug, Component)]
+pub struct NewRunTag;
+
+/// spawns hidden run summary with buttons
+fn spawn_run_summary(
+    mut cmds: Commands,
+    assets: Res<AspenInitHandles>,
+    interface_root: Query<Entity, With<InterfaceRootTag>>,
+) {
+    cmds.entity(interface_root.single())
+        .with_children(|children| {
+            children
+                .spawn((
+                    Name::new("RunSummary"),
+                    RunSummaryTag,
+                    NodeBundle {
+                        style: Style {
+                            display: Display::None,
+                            position_type: PositionType::Absolute,
+                            overflow: Overflow::clip(),
+                            flex_direction: FlexDirection::Column,
+                            min_height: Val::Percent(60.0),
+                            min_width: Val::Percent(30.0),
+                            align_self: AlignSelf::Center,
+                            justify_content: JustifyContent::FlexStart,
+                            margin: UiRect {
+                                left: Val::Percent(35.0),
+                                right: Val::Px(0.0),
+                                top: Val::Percent(10.0),
+                                bottom: Val::Percent(10.0),
+                            },
+                            ..default()
+                        },
+                        background_color: BackgroundColor(random_color(Some(0.8))),
+                        ..default()
+                    },
+                ))
+                .with_children(|summary| {
+                    spawn_menu_title(summary, assets.font_title.clone(), "Run Summary");
+                    summary.spawn((
+                        Name::new("RunStats"),
+                        RunSummaryTextTag,
+                        TextBundle::from_section(
+                            String::new(),
+                            widget_text_style(assets.font_regular.clone()),
+                        )
+                        .with_style(Style {
+                            margin: UiRect::all(Val::Px(15.0)),
+                            ..default()
+                        }),
+                    ));
+                    summary
+                        .spawn((
+                            Name::new("ButtonContainer"),
+                            NodeBundle {
+                                style: Style {
+                                    position_type: PositionType::Relative,
+                                    flex_direction: FlexDirection::Row,
+                                    justify_content: JustifyContent::SpaceEvenly,
+                                    align_items: AlignItems::Center,
+                                    width: Val::Percent(100.0),
+                                    margin: UiRect {
+                                        left: Val::Auto,
+                                        right: Val::Auto,
+                                        top: Val::Px(5.0),
+                                        bottom: Val::Px(15.0),
+                                    },
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                        ))
+                        .with_children(|buttons| {
+                            spawn_button(
+                                buttons,
+                                assets.font_regular.clone(),
+                                "Return To Hideout",
+                                ReturnToHideoutTag,
+                            );
+                            spawn_button(
+                                buttons,
+                                assets.font_regular.clone(),
+                                "New Run",
+                                NewRunTag,
+                            );
+                        });
+                });
+        });
+}
+
+/// fills run summary with `CurrentRunInformation` and shows it
+fn show_run_summary(
+    run_info: Res<CurrentRunInformation>,
+    floor: Res<DungeonFloor>,
+    difficulty: Res<DifficultyScales>,
+    mut summary: Query<&mut Style, With<RunSummaryTag>>,
+    mut stats_text: Query<&mut Text, With<RunSummaryTextTag>>,
+) {
+    stats_text.single_mut().sections[0].value =
+        run_summary_text(&run_info, *floor, difficulty.max_dungeon_amount);
+
+    summary.single_mut().display = Display::Flex;
+}
+
+/// hides run summary when leaving `AppState::RunSummary`
+fn hide_run_summary(mut summary: Query<&mut Style, With<RunSummaryTag>>) {
+    summary.single_mut().display = Display::None;
+}
+
+/// lines shown in the run summary for `run_info` that ended on `floor`
+pub fn run_summary_text(
+    run_info: &CurrentRunInformation,
+    floor: DungeonFloor,
+    max_floors: i32,
+) -> String {
+    let seconds = Duration::from_secs_f32(run_info.total_time().max(0.0)).as_secs();
+    let mut lines = vec![
+        match run_info.outcome {
+            Some(RunOutcome::Completed) => "Dungeon Cleared".to_string(),
+            Some(RunOutcome::PlayerDied) | None => "You Died".to_string(),
+        },
+        format!("Floor Reached: {} of {max_floors}", floor.0),
+        format!("Time: {}:{:02}", seconds / 60, seconds % 60),
+        format!("Damage Dealt: {:.0}", run_info.enemy_physical_damage_taken),
+        format!("Damage Taken: {:.0}", run_info.player_physical_damage_taken),
+        format!("Enemies Killed: {}", run_info.enemies_deaths),
+    ];
+
+    let mut kills: Vec<_> = run_info.enemies_killed.iter().collect();
+    kills.sort_by_key(|(character_type, _)| format!("{character_type:?}"));
+    lines.extend(
+        kills
+            .into_iter()
+            .map(|(character_type, amount)| format!("    {character_type:?}: {amount}")),
+    );
+
+    lines.push(format!("Rooms Cleared: {}", run_info.rooms_cleared));
+    lines.push(format!("Deaths: {}", run_info.player_deaths));
+    lines.push(format!("Coins Earned: {}", run_info.coins_collected));
+    lines.join("\n")
+}
+
+/// continues playing in the hideout when return too hideout is pressed,
+/// the dungeon was already left when the run ended
+fn hideout_button_interaction(
+    mut cmds: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ReturnToHideoutTag>)>,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            cmds.insert_resource(NextState::Pending(AppState::PlayingGame));
+        }
+    }
+}
+
+/// starts a new dungeon run when new run is pressed,
+/// whatever the last run left behind is despawned first
+fn new_run_button_interaction(
+    mut cmds: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<NewRunTag>)>,
+    mut regen_events: EventWriter<RegenerateDungeonEvent>,
+    mut cleanup: DungeonCleanup,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            info!("despawning actors from the last run");
+            cleanup.despawn_dungeon();
+            regen_events.send(RegenerateDungeonEvent {
+                reason: RegenReason::FirstGeneration,
+            });
+            cmds.insert_resource(NextState::Pending(AppState::PlayingGame));
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use bevy::{input::gamepad::GamepadButton, prelude::*};
+
+use crate::{
+    game::{
+        input::action_maps::{BindingDevice, Gameplay, KbmBinding, REBINDABLE_ACTIONS},
+        interface::{
+            settings_menu::SettingsListTag,
+            ui_widgets::{spawn_button, spawn_widget_row, widget_text_style},
+        },
+    },
+    loading::config::ControlSettings,
+};
+
+/// controls list marker component for querys
+#[derive(Debug, Component)]
+pub struct ControlsListTag;
+
+/// marks button that switches between settings and controls
+#[derive(Debug, Component)]
+pub struct ToggleControlsTag;
+
+/// marks button that resets bindings too defaults
+#[derive(Debug, Component)]
+pub struct ResetControlsTag;
+
+/// text showing rebind prompts and conflicts
+#[derive(Debug, Component)]
+pub struct RebindNotice;
+
+/// button that rebinds an action when pressed
+#[derive(Debug, Clone, Copy, Component)]
+pub struct RebindButton {
+    /// action too rebind
+    pub action: Gameplay,
+    /// device the button is for
+    pub device: BindingDevice,
+}
+
+/// action waiting for its next key or button press
+#[derive(Debug, Resource)]
+pub struct RebindCapture {
+    /// action being rebound
+    pub action: Gameplay,
+    /// device too listen too
+    pub device: BindingDevice,
+    /// false until the click that started the capture is released
+    pub armed: bool,
+}
+
+/// spawns the hidden controls list with a row for each rebindable action
+pub fn spawn_controls_list(
+    builder: &mut ChildBuilder,
+    font: &Handle<Font>,
+    control_settings: &ControlSettings,
+) {
+    builder
+        .spawn((
+            Name::new("ControlsList"),
+            ControlsListTag,
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(60.0),
+                    margin: UiRect::horizontal(Val::Auto),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|list| {
+            list.spawn((
+                Name::new("RebindNotice"),
+                RebindNotice,
+                TextBundle::from_section(
+                    "click a binding then press a key or button",
+                    widget_text_style(font.clone()),
+                ),
+            ));
+            for action in REBINDABLE_ACTIONS {
+                let binding = control_settings.binding(*action);
+                spawn_widget_row(list, font.clone(), &format!("{action:?}")).with_children(|row| {
+                    for (device, text) in [
+                        (BindingDevice::Kbm, format_kbm(binding.keyboard)),
+                        (BindingDevice::Gamepad, format_gamepad(binding.gamepad)),
+                    ] {
+                        spawn_rebind_button(row, font, &text, *action, device);
+                    }
+                });
+            }
+            spawn_button(list, font.clone(), "Reset Defaults", ResetControlsTag);
+        });
+}
+
+/// spawns a button showing the current binding for `action`
+fn spawn_rebind_button(
+    row: &mut ChildBuilder,
+    font: &Handle<Font>,
+    text: &str,
+    action: Gameplay,
+    device: BindingDevice,
+) {
+    row.spawn((
+        Name::new("RebindButton"),
+        RebindButton { action, device },
+        ButtonBundle {
+            style: Style {
+                width: Val::Px(140.0),
+                height: Val::Px(24.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            ..default()
+        },
+    ))
+    .with_children(|button| {
+        button.spawn((
+            Name::new("BindingText"),
+            TextBundle::from_section(text, widget_text_style(font.clone())),
+        ));
+    });
+}
+
+/// text for a keyboard/mouse binding
+fn format_kbm(binding: Option<KbmBinding>) -> String {
+    match binding {
+        Some(KbmBinding::Key(key)) => format!("{key:?}"),
+        Some(KbmBinding::Mouse(button)) => format!("Mouse {button:?}"),
+        None => "-".to_string(),
+    }
+}
+
+/// text for a gamepad binding
+fn format_gamepad(binding: Option<GamepadButtonType>) -> String {
+    binding.map_or_else(|| "-".to_string(), |f| format!("{f:?}"))
+}
+
+/// switches the settings menu between the settings and controls lists
+pub fn toggle_controls_interaction(
+    mut cmds: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ToggleControlsTag>)>,
+    mut settings_list: Query<&mut Style, (With<SettingsListTag>, Without<ControlsListTag>)>,
+    mut controls_list: Query<&mut Style, (With<ControlsListTag>, Without<SettingsListTag>)>,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            let mut settings_style = settings_list.single_mut();
+            let mut controls_style = controls_list.single_mut();
+            if controls_style.display == Display::None {
+                controls_style.display = Display::Flex;
+                settings_style.display = Display::None;
+            } else {
+                controls_style.display = Display::None;
+                settings_style.display = Display::Flex;
+                cmds.remove_resource::<RebindCapture>();
+            }
+        }
+    }
+}
+
+/// starts listening for a new binding when a rebind button is pressed
+pub fn start_rebind_interaction(
+    mut cmds: Commands,
+    interaction_query: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+    mut notice: Query<&mut Text, With<RebindNotice>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            let prompt = match button.device {
+                BindingDevice::Kbm => "press a key or mouse button",
+                BindingDevice::Gamepad => "press a gamepad button",
+            };
+            let cancel = if escape_cancels(button.action, button.device) {
+                ", Escape cancels"
+            } else {
+                ""
+            };
+            set_notice(
+                &mut notice,
+                &format!("{prompt} for {:?}{cancel}", button.action),
+            );
+            cmds.insert_resource(RebindCapture {
+                action: button.action,
+                device: button.device,
+                armed: false,
+            });
+        }
+    }
+}
+
+/// Escape cancels a rebind unless it is being bound too `Pause`
+fn escape_cancels(action: Gameplay, device: BindingDevice) -> bool {
+    !(action == Gameplay::Pause && device == BindingDevice::Kbm)
+}
+
+/// binds the next pressed key or button too the captured action.
+///
+/// Escape cancels, unless `Pause` is being bound too the keyboard.
+/// if the button was used by another action the two actions swap buttons,
+/// keys used by `Move` are refused and capture continues
+pub fn capture_rebind(
+    mut cmds: Commands,
+    capture: Option<ResMut<RebindCapture>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut control_settings: ResMut<ControlSettings>,
+    mut notice: Query<&mut Text, With<RebindNotice>>,
+) {
+    let Some(mut capture) = capture else {
+        return;
+    };
+    // the click on the rebind button would otherwise be captured
+    if !capture.armed {
+        capture.armed = mouse.get_pressed().next().is_none();
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::Escape) && escape_cancels(capture.action, capture.device) {
+        set_notice(&mut notice, "rebind cancelled");
+        cmds.remove_resource::<RebindCapture>();
+        return;
+    }
+
+    let (bound, conflict) = match capture.device {
+        BindingDevice::Kbm => {
+            let Some(button) = keys
+                .get_just_pressed()
+                .next()
+                .map(|f| KbmBinding::Key(*f))
+                .or_else(|| {
+                    mouse
+                        .get_just_pressed()
+                        .next()
+                        .map(|f| KbmBinding::Mouse(*f))
+                })
+            else {
+                return;
+            };
+            match control_settings.rebind_kbm(capture.action, button) {
+                Ok(conflict) => (format_kbm(Some(button)), conflict),
+                Err(fixed) => {
+                    // capture stays active so another button can be pressed
+                    set_notice(
+                        &mut notice,
+                        &format!(
+                            "{} is used by {fixed:?}, pick another button",
+                            format_kbm(Some(button))
+                        ),
+                    );
+                    return;
+                }
+            }
+        }
+        BindingDevice::Gamepad => {
+            let Some(button) = gamepad_buttons.get_just_pressed().next() else {
+                return;
+            };
+            (
+                format_gamepad(Some(button.button_type)),
+                control_settings.rebind_gamepad(capture.action, button.button_type),
+            )
+        }
+    };
+
+    let message = conflict.map_or_else(
+        || format!("{:?} bound too {bound}", capture.action),
+        |other| {
+            format!(
+                "{:?} bound too {bound}, {other:?} was using it and got the old binding",
+                capture.action
+            )
+        },
+    );
+    info!("{message}");
+    set_notice(&mut notice, &message);
+    cmds.remove_resource::<RebindCapture>();
+}
+
+/// resets every binding too its default
+pub fn reset_controls_interaction(
+    mut cmds: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ResetControlsTag>)>,
+    mut control_settings: ResMut<ControlSettings>,
+    mut notice: Query<&mut Text, With<RebindNotice>>,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            *control_settings = ControlSettings::default();
+            cmds.remove_resource::<RebindCapture>();
+            set_notice(&mut notice, "controls reset too defaults");
+        }
+    }
+}
+
+/// updates rebind button text from `ControlSettings` and highlights the captured button
+pub fn update_rebind_buttons(
+    control_settings: Res<ControlSettings>,
+    capture: Option<Res<RebindCapture>>,
+    buttons: Query<(&RebindButton, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (button, children) in &buttons {
+        let capturing = capture
+            .as_ref()
+            .is_some_and(|f| f.action == button.action && f.device == button.device);
+        let binding = control_settings.binding(button.action);
+        let value = if capturing {
+            "...".to_string()
+        } else {
+            match button.device {
+                BindingDevice::Kbm => format_kbm(binding.keyboard),
+                BindingDevice::Gamepad => format_gamepad(binding.gamepad),
+            }
+        };
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value.clone_from(&value);
+            }
+        }
+    }
+}
+
+/// replaces rebind notice text
+fn set_notice(notice: &mut Query<&mut Text, With<RebindNotice>>, message: &str) {
+    for mut text in notice.iter_mut() {
+        message.clone_into(&mut text.sections[0].value);
+    }
+}
@@ -8,15 +8,30 @@ use crate::{
     game::{
         interface::{
             random_color,
-            ui_widgets::{spawn_button, spawn_menu_title},
+            settings_menu::controls::{
+                capture_rebind, reset_controls_interaction, spawn_controls_list,
+                start_rebind_interaction, toggle_controls_interaction, update_rebind_buttons,
+                RebindCapture, ToggleControlsTag,
+            },
+            ui_widgets::{
+                spawn_button, spawn_dropdown, spawn_menu_title, spawn_slider, spawn_toggle,
+                UiDropdown, UiSlider, UiToggle,
+            },
             InterfaceRootTag,
         },
         AppState,
     },
-    loading::assets::AspenInitHandles,
+    loading::{
+        assets::AspenInitHandles,
+        config::{
+            ControlSettings, DifficultyScales, GameDifficulty, GeneralSettings, SaveSettingsEvent,
+            SoundSettings, WindowSettings,
+        },
+    },
 };
 
-// TODO: expand settings menu too include different settings
+/// rebindable controls list
+mod controls;
 
 /// game configuration ui
 pub struct SettingsMenuPlugin;
@@ -29,9 +44,28 @@ impl Plugin for SettingsMenuPlugin {
             (
                 close_settings_interaction,
                 apply_settings_interaction,
-                toggle_settings_interactions
-                    .run_if(in_state(AppState::PauseMenu).or_else(in_state(AppState::StartMenu))),
-            ),
+                toggle_settings_interactions,
+                (
+                    sync_settings_widgets.run_if(resource_added::<SettingsSnapshot>),
+                    preview_slider_settings,
+                    preview_toggle_settings,
+                    preview_dropdown_settings,
+                )
+                    .chain(),
+                (
+                    toggle_controls_interaction,
+                    start_rebind_interaction,
+                    capture_rebind,
+                    reset_controls_interaction,
+                    update_rebind_buttons.run_if(
+                        resource_changed::<ControlSettings>
+                            .or_else(resource_exists_and_changed::<RebindCapture>)
+                            .or_else(resource_removed::<RebindCapture>()),
+                    ),
+                )
+                    .chain(),
+            )
+                .run_if(in_state(AppState::PauseMenu).or_else(in_state(AppState::StartMenu))),
         );
     }
 }
@@ -48,12 +82,111 @@ pub struct ApplySettingsTag;
 #[derive(Debug, Component)]
 pub struct CloseSettingsTag;
 
-/// spawns start menu with buttons
+/// settings widget list marker component for querys
+#[derive(Debug, Component)]
+pub struct SettingsListTag;
+
+/// which setting a settings menu widget changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum SettingField {
+    /// `WindowSettings.resolution`
+    Resolution,
+    /// `WindowSettings.full_screen`
+    FullScreen,
+    /// `WindowSettings.v_sync`
+    VSync,
+    /// `WindowSettings.frame_rate_target`
+    FrameCap,
+    /// `SoundSettings.master_volume`
+    MasterVolume,
+    /// `SoundSettings.ambience_volume`
+    AmbienceVolume,
+    /// `SoundSettings.music_volume`
+    MusicVolume,
+    /// `SoundSettings.sound_volume`
+    SoundVolume,
+    /// `RenderSettings.msaa`
+    Msaa,
+    /// `GeneralSettings.camera_zoom`
+    CameraZoom,
+    /// `GeneralSettings.game_difficulty`
+    Difficulty,
+}
+
+/// settings when the menu was opened, restored by close
+#[derive(Debug, Resource)]
+pub struct SettingsSnapshot {
+    /// window settings
+    window: WindowSettings,
+    /// sound settings
+    sound: SoundSettings,
+    /// general settings
+    general: GeneralSettings,
+    /// anti aliasing
+    msaa: Msaa,
+    /// control bindings
+    controls: ControlSettings,
+}
+
+/// first resolution option keeps the current window size
+const KEEP_RESOLUTION: &str = "Window";
+/// resolutions in the resolution dropdown
+const RESOLUTIONS: &[(f32, f32)] = &[
+    (1280.0, 720.0),
+    (1366.0, 768.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+    (2560.0, 1440.0),
+    (3840.0, 2160.0),
+];
+/// difficulty dropdown options
+const DIFFICULTIES: &[&str] = &[
+    "Easy",
+    "Medium",
+    "Hard",
+    "Insane",
+    "MegaDeath",
+    "Debug",
+    "Custom",
+];
+
+/// resolution dropdown option for `resolution`, 0 if it isnt listed
+fn resolution_index(resolution: Vec2) -> usize {
+    RESOLUTIONS
+        .iter()
+        .position(|(x, y)| resolution == Vec2::new(*x, *y))
+        .map_or(0, |f| f + 1)
+}
+
+/// difficulty dropdown option for `difficulty`
+const fn difficulty_index(difficulty: GameDifficulty) -> usize {
+    match difficulty {
+        GameDifficulty::Easy => 0,
+        GameDifficulty::Medium => 1,
+        GameDifficulty::Hard => 2,
+        GameDifficulty::Insane => 3,
+        GameDifficulty::MegaDeath => 4,
+        GameDifficulty::Debug => 5,
+        GameDifficulty::Custom(_) => 6,
+    }
+}
+
+/// spawns settings menu with a widget for each setting
+#[allow(clippy::too_many_arguments)]
 fn spawn_settings_menu(
     mut cmds: Commands,
     assets: Res<AspenInitHandles>,
     interface_root: Query<Entity, With<InterfaceRootTag>>,
+    window_settings: Res<WindowSettings>,
+    sound_settings: Res<SoundSettings>,
+    general_settings: Res<GeneralSettings>,
+    control_settings: Res<ControlSettings>,
+    msaa: Res<Msaa>,
 ) {
+    let resolutions: Vec<String> = std::iter::once(KEEP_RESOLUTION.to_string())
+        .chain(RESOLUTIONS.iter().map(|(x, y)| format!("{x}x{y}")))
+        .collect();
+
     cmds.entity(interface_root.single())
         .with_children(|children| {
             children
@@ -123,42 +256,202 @@ fn spawn_settings_menu(
                                 "Close Settings",
                                 CloseSettingsTag,
                             );
+                            spawn_button(
+                                buttons,
+                                assets.font_regular.clone(),
+                                "Controls",
+                                ToggleControlsTag,
+                            );
                             spawn_menu_title(buttons, assets.font_title.clone(), "Settings Menu");
                         });
+                    start_menu_container_childs
+                        .spawn((
+                            Name::new("SettingsList"),
+                            SettingsListTag,
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    width: Val::Percent(60.0),
+                                    margin: UiRect::horizontal(Val::Auto),
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                        ))
+                        .with_children(|list| {
+                            let font = assets.font_regular.clone();
+                            spawn_dropdown(
+                                list,
+                                font.clone(),
+                                "Resolution",
+                                UiDropdown {
+                                    options: resolutions,
+                                    selected: resolution_index(window_settings.resolution),
+                                },
+                                SettingField::Resolution,
+                            );
+                            spawn_toggle(
+                                list,
+                                font.clone(),
+                                "Fullscreen",
+                                UiToggle(window_settings.full_screen),
+                                SettingField::FullScreen,
+                            );
+                            spawn_toggle(
+                                list,
+                                font.clone(),
+                                "VSync",
+                                UiToggle(window_settings.v_sync),
+                                SettingField::VSync,
+                            );
+                            spawn_slider(
+                                list,
+                                font.clone(),
+                                "Frame Cap",
+                                UiSlider::new(
+                                    30.0,
+                                    240.0,
+                                    1.0,
+                                    window_settings.frame_rate_target as f32,
+                                ),
+                                SettingField::FrameCap,
+                            );
+                            for (label, field, volume) in [
+                                (
+                                    "Master Volume",
+                                    SettingField::MasterVolume,
+                                    sound_settings.master_volume,
+                                ),
+                                (
+                                    "Ambience Volume",
+                                    SettingField::AmbienceVolume,
+                                    sound_settings.ambience_volume,
+                                ),
+                                (
+                                    "Music Volume",
+                                    SettingField::MusicVolume,
+                                    sound_settings.music_volume,
+                                ),
+                                (
+                                    "Sound Volume",
+                                    SettingField::SoundVolume,
+                                    sound_settings.sound_volume,
+                                ),
+                            ] {
+                                spawn_slider(
+                                    list,
+                                    font.clone(),
+                                    label,
+                                    UiSlider::new(0.0, 1.0, 0.05, volume as f32),
+                                    field,
+                                );
+                            }
+                            spawn_toggle(
+                                list,
+                                font.clone(),
+                                "MSAA",
+                                UiToggle(*msaa != Msaa::Off),
+                                SettingField::Msaa,
+                            );
+                            spawn_slider(
+                                list,
+                                font.clone(),
+                                "Camera Zoom",
+                                UiSlider::new(0.5, 10.0, 0.25, general_settings.camera_zoom),
+                                SettingField::CameraZoom,
+                            );
+                            spawn_dropdown(
+                                list,
+                                font,
+                                "Difficulty",
+                                UiDropdown {
+                                    options: DIFFICULTIES.iter().map(ToString::to_string).collect(),
+                                    selected: difficulty_index(general_settings.game_difficulty),
+                                },
+                                SettingField::Difficulty,
+                            );
+                        });
+                    spawn_controls_list(
+                        start_menu_container_childs,
+                        &assets.font_regular,
+                        &control_settings,
+                    );
                 });
         });
 }
 
-/// updates color of all buttons with text for interactions
+/// restores settings from when the menu was opened
+fn revert_settings(cmds: &mut Commands, snapshot: Option<&SettingsSnapshot>) {
+    if let Some(snapshot) = snapshot {
+        info!("reverting unapplied settings");
+        cmds.insert_resource(snapshot.window);
+        cmds.insert_resource(snapshot.sound);
+        cmds.insert_resource(snapshot.general);
+        cmds.insert_resource(snapshot.msaa);
+        cmds.insert_resource(snapshot.controls.clone());
+    }
+    cmds.remove_resource::<SettingsSnapshot>();
+    cmds.remove_resource::<RebindCapture>();
+}
+
+/// reverts previewed settings and hides settings menu
 fn close_settings_interaction(
+    mut cmds: Commands,
+    snapshot: Option<Res<SettingsSnapshot>>,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<CloseSettingsTag>)>,
     mut settings_menu_query: Query<&mut Style, (With<Node>, With<SettingsMenuTag>)>,
 ) {
     for interaction in &interaction_query {
         if matches!(interaction, Interaction::Pressed) {
+            revert_settings(&mut cmds, snapshot.as_deref());
             settings_menu_query.single_mut().display = Display::None;
         }
     }
 }
 
-/// updates color of all buttons with text for interactions
+/// saves current settings when apply is pressed
+#[allow(clippy::too_many_arguments)]
 fn apply_settings_interaction(
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<ApplySettingsTag>)>,
+    mut save_settings: EventWriter<SaveSettingsEvent>,
+    snapshot: Option<ResMut<SettingsSnapshot>>,
+    window_settings: Res<WindowSettings>,
+    sound_settings: Res<SoundSettings>,
+    general_settings: Res<GeneralSettings>,
+    control_settings: Res<ControlSettings>,
+    msaa: Res<Msaa>,
 ) {
+    let Some(mut snapshot) = snapshot else {
+        return;
+    };
     for interaction in &interaction_query {
         if matches!(interaction, Interaction::Pressed) {
-            //TODO: apply game settings?
-            // should probably be an event
             info!("applying game settings");
+            save_settings.send(SaveSettingsEvent);
+            // close no longer reverts applied settings
+            *snapshot = SettingsSnapshot {
+                window: *window_settings,
+                sound: *sound_settings,
+                general: *general_settings,
+                msaa: *msaa,
+                controls: control_settings.clone(),
+            };
         }
     }
 }
 
-/// toggles display of settings menu
+/// toggles display of settings menu, closing it reverts unapplied settings
+#[allow(clippy::too_many_arguments)]
 fn toggle_settings_interactions(
-    // mut cmds: Commands,
+    mut cmds: Commands,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<SettingsMenuToggleButton>)>,
     mut settings_menu_query: Query<&mut Style, (With<Node>, With<SettingsMenuTag>)>,
+    snapshot: Option<Res<SettingsSnapshot>>,
+    window_settings: Res<WindowSettings>,
+    sound_settings: Res<SoundSettings>,
+    general_settings: Res<GeneralSettings>,
+    control_settings: Res<ControlSettings>,
+    msaa: Res<Msaa>,
 ) {
     let mut settings_menu_style = settings_menu_query.single_mut();
 
@@ -166,9 +459,131 @@ fn toggle_settings_interactions(
         if matches!(interaction, Interaction::Pressed) {
             if settings_menu_style.display == Display::None {
                 settings_menu_style.display = Display::Flex;
+                cmds.insert_resource(SettingsSnapshot {
+                    window: *window_settings,
+                    sound: *sound_settings,
+                    general: *general_settings,
+                    msaa: *msaa,
+                    controls: control_settings.clone(),
+                });
             } else {
                 settings_menu_style.display = Display::None;
+                revert_settings(&mut cmds, snapshot.as_deref());
+            }
+        }
+    }
+}
+
+/// sets widgets too current settings when the menu is opened
+fn sync_settings_widgets(
+    snapshot: Res<SettingsSnapshot>,
+    mut sliders: Query<(&SettingField, &mut UiSlider)>,
+    mut toggles: Query<(&SettingField, &mut UiToggle)>,
+    mut dropdowns: Query<(&SettingField, &mut UiDropdown)>,
+) {
+    for (field, mut slider) in &mut sliders {
+        let value = match field {
+            SettingField::FrameCap => snapshot.window.frame_rate_target as f32,
+            SettingField::MasterVolume => snapshot.sound.master_volume as f32,
+            SettingField::AmbienceVolume => snapshot.sound.ambience_volume as f32,
+            SettingField::MusicVolume => snapshot.sound.music_volume as f32,
+            SettingField::SoundVolume => snapshot.sound.sound_volume as f32,
+            SettingField::CameraZoom => snapshot.general.camera_zoom,
+            _ => continue,
+        };
+        slider.set_if_neq(UiSlider { value, ..*slider });
+    }
+    for (field, mut toggle) in &mut toggles {
+        let value = match field {
+            SettingField::FullScreen => snapshot.window.full_screen,
+            SettingField::VSync => snapshot.window.v_sync,
+            SettingField::Msaa => snapshot.msaa != Msaa::Off,
+            _ => continue,
+        };
+        toggle.set_if_neq(UiToggle(value));
+    }
+    for (field, mut dropdown) in &mut dropdowns {
+        let selected = match field {
+            SettingField::Resolution => resolution_index(snapshot.window.resolution),
+            SettingField::Difficulty => difficulty_index(snapshot.general.game_difficulty),
+            _ => continue,
+        };
+        if dropdown.selected != selected {
+            dropdown.selected = selected;
+        }
+    }
+}
+
+/// applies slider changes too settings for preview
+fn preview_slider_settings(
+    sliders: Query<(&SettingField, &UiSlider), Changed<UiSlider>>,
+    mut window_settings: ResMut<WindowSettings>,
+    mut sound_settings: ResMut<SoundSettings>,
+    mut general_settings: ResMut<GeneralSettings>,
+) {
+    for (field, slider) in &sliders {
+        let value = slider.value;
+        match field {
+            SettingField::FrameCap => window_settings.frame_rate_target = f64::from(value),
+            SettingField::MasterVolume => sound_settings.master_volume = f64::from(value),
+            SettingField::AmbienceVolume => sound_settings.ambience_volume = f64::from(value),
+            SettingField::MusicVolume => sound_settings.music_volume = f64::from(value),
+            SettingField::SoundVolume => sound_settings.sound_volume = f64::from(value),
+            SettingField::CameraZoom => general_settings.camera_zoom = value,
+            _ => warn!("{field:?} is not a slider setting"),
+        }
+    }
+}
+
+/// applies toggle changes too settings for preview
+fn preview_toggle_settings(
+    mut cmds: Commands,
+    toggles: Query<(&SettingField, &UiToggle), Changed<UiToggle>>,
+    mut window_settings: ResMut<WindowSettings>,
+) {
+    for (field, toggle) in &toggles {
+        match field {
+            SettingField::FullScreen => window_settings.full_screen = toggle.0,
+            SettingField::VSync => window_settings.v_sync = toggle.0,
+            SettingField::Msaa => {
+                cmds.insert_resource(if toggle.0 { Msaa::Sample4 } else { Msaa::Off });
+            }
+            _ => warn!("{field:?} is not a toggle setting"),
+        }
+    }
+}
+
+/// applies dropdown changes too settings for preview
+fn preview_dropdown_settings(
+    dropdowns: Query<(&SettingField, &UiDropdown), Changed<UiDropdown>>,
+    mut window_settings: ResMut<WindowSettings>,
+    mut general_settings: ResMut<GeneralSettings>,
+    difficulty_scales: Res<DifficultyScales>,
+) {
+    for (field, dropdown) in &dropdowns {
+        match field {
+            SettingField::Resolution => {
+                // option 0 keeps the current window size
+                if let Some((x, y)) = dropdown
+                    .selected
+                    .checked_sub(1)
+                    .and_then(|f| RESOLUTIONS.get(f))
+                {
+                    window_settings.resolution = Vec2::new(*x, *y);
+                }
+            }
+            SettingField::Difficulty => {
+                general_settings.game_difficulty = match dropdown.selected {
+                    0 => GameDifficulty::Easy,
+                    1 => GameDifficulty::Medium,
+                    2 => GameDifficulty::Hard,
+                    3 => GameDifficulty::Insane,
+                    4 => GameDifficulty::MegaDeath,
+                    5 => GameDifficulty::Debug,
+                    _ => GameDifficulty::Custom(*difficulty_scales),
+                };
             }
+            _ => warn!("{field:?} is not a dropdown setting"),
         }
     }
 }
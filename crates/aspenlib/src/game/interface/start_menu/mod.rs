@@ -1,6 +1,8 @@
 use crate::{
     game::{
+        game_world::suspend::{read_suspended_run, run_storage, ContinueRunEvent},
         interface::{
+            profile_menu::OpenProfileMenuEvent,
             random_color,
             settings_menu::SettingsMenuToggleButton,
             ui_widgets::{spawn_button, spawn_menu_title},
@@ -8,7 +10,9 @@ use crate::{
         },
         AppState,
     },
-    loading::assets::AspenInitHandles,
+    loading::{
+        assets::AspenInitHandles, config::save_load::ConfigStorage, profile::ProfileStorage,
+    },
 };
 use bevy::app::AppExit;
 use bevy::prelude::*;
@@ -23,8 +27,12 @@ impl Plugin for StartMenuPlugin {
             Update,
             (
                 start_button_interaction,
+                continue_button_interaction,
                 exit_button_interaction,
                 show_start_menu,
+                show_continue_button.run_if(in_state(AppState::StartMenu).and_then(
+                    state_changed::<AppState>.or_else(resource_changed::<ProfileStorage>),
+                )),
             ),
         );
     }
@@ -38,6 +46,10 @@ pub struct StartMenuTag;
 #[derive(Debug, Component)]
 pub struct StartGameTag;
 
+/// marks continue button for query
+#[derive(Debug, Component)]
+pub struct ContinueRunTag;
+
 /// marks start button for query
 #[derive(Debug, Component)]
 pub struct ExitGameTag;
@@ -132,6 +144,12 @@ fn spawn_start_menu(
                                 "Start Game",
                                 StartGameTag,
                             );
+                            spawn_button(
+                                buttons,
+                                assets.font_regular.clone(),
+                                "Continue",
+                                ContinueRunTag,
+                            );
                             spawn_button(
                                 buttons,
                                 assets.font_regular.clone(),
@@ -149,16 +167,45 @@ fn spawn_start_menu(
         });
 }
 
-/// updates color of all buttons with text for interactions
+/// hides start menu and opens the profile picker
 fn start_button_interaction(
-    // mut cmds: Commands,
     interaction_query: Query<&Interaction, (Changed<Interaction>, With<StartGameTag>)>,
     mut start_menu_query: Query<&mut Style, (With<Node>, With<StartMenuTag>)>,
+    mut open_profile_menu: EventWriter<OpenProfileMenuEvent>,
 ) {
     for interaction in &interaction_query {
         if matches!(interaction, Interaction::Pressed) {
             start_menu_query.single_mut().display = Display::None;
-            // cmds.insert_resource(NextState(Some(AppState::PlayingGame)));
+            open_profile_menu.send(OpenProfileMenuEvent);
+        }
+    }
+}
+
+/// only shows continue button if the active save slot has a suspended run
+fn show_continue_button(
+    config_storage: Res<ConfigStorage>,
+    profile_storage: Res<ProfileStorage>,
+    mut continue_button: Query<&mut Style, With<ContinueRunTag>>,
+) {
+    let Ok(mut style) = continue_button.get_single_mut() else {
+        return;
+    };
+    let storage = run_storage(&config_storage, profile_storage.slot);
+    style.display = if matches!(read_suspended_run(&storage), Ok(Some(_))) {
+        Display::Flex
+    } else {
+        Display::None
+    };
+}
+
+/// continues the suspended run of the active save slot
+fn continue_button_interaction(
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<ContinueRunTag>)>,
+    mut continue_events: EventWriter<ContinueRunEvent>,
+) {
+    for interaction in &interaction_query {
+        if matches!(interaction, Interaction::Pressed) {
+            continue_events.send(ContinueRunEvent);
         }
     }
 }
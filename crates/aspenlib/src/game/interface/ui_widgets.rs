@@ -1,5 +1,5 @@
-use crate::game::interface::random_color;
-use bevy::prelude::*;
+use crate::{game::interface::random_color, AppState};
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 /// spawns styled menu button
 pub fn spawn_button<T: Component>(
@@ -73,3 +73,453 @@ pub fn spawn_menu_title(child_builder: &mut ChildBuilder, font: Handle<Font>, te
         }),
     ));
 }
+
+/// updates slider, toggle and dropdown widgets from interactions,
+/// widgets only exist in the settings menu so these only run in its menu states
+pub struct UiWidgetsPlugin;
+
+impl Plugin for UiWidgetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                drag_sliders,
+                update_slider_visuals,
+                press_toggles,
+                update_toggle_text,
+                toggle_dropdown_lists,
+                select_dropdown_options,
+                update_dropdown_text,
+            )
+                .run_if(in_state(AppState::PauseMenu).or_else(in_state(AppState::StartMenu))),
+        );
+    }
+}
+
+/// value picked by dragging on the slider track
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct UiSlider {
+    /// smallest value
+    pub min: f32,
+    /// largest value
+    pub max: f32,
+    /// value is rounded too a multiple of this, 0 for no rounding
+    pub step: f32,
+    /// current value
+    pub value: f32,
+}
+
+impl UiSlider {
+    /// slider between `min` and `max` starting at `value`
+    pub const fn new(min: f32, max: f32, step: f32, value: f32) -> Self {
+        Self {
+            min,
+            max,
+            step,
+            value,
+        }
+    }
+
+    /// how far along the track the value is, 0.0-1.0
+    fn fraction(&self) -> f32 {
+        if self.max <= self.min {
+            return 0.0;
+        }
+        ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+    }
+
+    /// value at `fraction` of the track, rounded too `step`
+    fn value_at(&self, fraction: f32) -> f32 {
+        let value = self.min + (self.max - self.min) * fraction.clamp(0.0, 1.0);
+        if self.step > 0.0 {
+            ((value / self.step).round() * self.step).clamp(self.min, self.max)
+        } else {
+            value
+        }
+    }
+}
+
+/// filled part of a slider track
+#[derive(Debug, Component)]
+pub struct UiSliderFill;
+
+/// text showing a widgets current value
+#[derive(Debug, Component)]
+pub struct UiWidgetText;
+
+/// on/off toggle button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct UiToggle(pub bool);
+
+/// button that opens a list of options too pick from
+#[derive(Debug, Clone, PartialEq, Eq, Component)]
+pub struct UiDropdown {
+    /// text for each option
+    pub options: Vec<String>,
+    /// index of selected option
+    pub selected: usize,
+}
+
+impl UiDropdown {
+    /// text of selected option
+    pub fn selected_text(&self) -> &str {
+        self.options
+            .get(self.selected)
+            .map_or("", std::string::String::as_str)
+    }
+}
+
+/// node holding a dropdowns option buttons
+#[derive(Debug, Component)]
+pub struct UiDropdownList;
+
+/// option button inside a dropdown list
+#[derive(Debug, Component)]
+pub struct UiDropdownOption {
+    /// dropdown this option belongs too
+    pub dropdown: Entity,
+    /// index into `UiDropdown.options`
+    pub index: usize,
+}
+
+/// text style used by widgets
+pub fn widget_text_style(font: Handle<Font>) -> TextStyle {
+    TextStyle {
+        font,
+        font_size: 14.0,
+        color: Color::WHITE,
+    }
+}
+
+/// spawns a labeled row and returns the row so a widget can be added
+pub fn spawn_widget_row<'a>(
+    builder: &'a mut ChildBuilder,
+    font: Handle<Font>,
+    label: &str,
+) -> EntityCommands<'a> {
+    let mut row = builder.spawn((
+        Name::new(format!("{label}Row")),
+        NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::SpaceBetween,
+                width: Val::Percent(100.0),
+                height: Val::Px(32.0),
+                margin: UiRect::vertical(Val::Px(4.0)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+    row.with_children(|row| {
+        row.spawn((
+            Name::new("Label"),
+            TextBundle::from_section(label, widget_text_style(font)),
+        ));
+    });
+    row
+}
+
+/// spawns a labeled slider, `component` is added too the slider track
+pub fn spawn_slider<T: Component>(
+    builder: &mut ChildBuilder,
+    font: Handle<Font>,
+    label: &str,
+    slider: UiSlider,
+    component: T,
+) {
+    spawn_widget_row(builder, font.clone(), label).with_children(|row| {
+        row.spawn((
+            Name::new("SliderTrack"),
+            component,
+            slider,
+            Interaction::default(),
+            bevy::ui::RelativeCursorPosition::default(),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(50.0),
+                    height: Val::Px(24.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+                ..default()
+            },
+        ))
+        .with_children(|track| {
+            track.spawn((
+                Name::new("SliderFill"),
+                UiSliderFill,
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        width: Val::Percent(slider.fraction() * 100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(crate::colors::PURPLE.into()),
+                    ..default()
+                },
+            ));
+            track.spawn((
+                Name::new("SliderValue"),
+                UiWidgetText,
+                TextBundle::from_section(format_slider(&slider), widget_text_style(font)),
+            ));
+        });
+    });
+}
+
+/// spawns a labeled on/off toggle, `component` is added too the toggle button
+pub fn spawn_toggle<T: Component>(
+    builder: &mut ChildBuilder,
+    font: Handle<Font>,
+    label: &str,
+    toggle: UiToggle,
+    component: T,
+) {
+    spawn_widget_row(builder, font.clone(), label).with_children(|row| {
+        row.spawn((
+            Name::new("Toggle"),
+            component,
+            toggle,
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(80.0),
+                    height: Val::Px(24.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|button| {
+            button.spawn((
+                Name::new("ToggleValue"),
+                UiWidgetText,
+                TextBundle::from_section(format_toggle(toggle), widget_text_style(font)),
+            ));
+        });
+    });
+}
+
+/// spawns a labeled dropdown, `component` is added too the dropdown button
+pub fn spawn_dropdown<T: Component>(
+    builder: &mut ChildBuilder,
+    font: Handle<Font>,
+    label: &str,
+    dropdown: UiDropdown,
+    component: T,
+) {
+    spawn_widget_row(builder, font.clone(), label).with_children(|row| {
+        let options = dropdown.options.clone();
+        let text = dropdown.selected_text().to_string();
+        let mut button = row.spawn((
+            Name::new("Dropdown"),
+            component,
+            dropdown,
+            ButtonBundle {
+                style: Style {
+                    width: Val::Percent(50.0),
+                    height: Val::Px(24.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ));
+        let dropdown_entity = button.id();
+        button.with_children(|button| {
+            button.spawn((
+                Name::new("DropdownValue"),
+                UiWidgetText,
+                TextBundle::from_section(text, widget_text_style(font.clone())),
+            ));
+            button
+                .spawn((
+                    Name::new("DropdownList"),
+                    UiDropdownList,
+                    NodeBundle {
+                        style: Style {
+                            display: Display::None,
+                            position_type: PositionType::Absolute,
+                            flex_direction: FlexDirection::Column,
+                            top: Val::Percent(100.0),
+                            width: Val::Percent(100.0),
+                            ..default()
+                        },
+                        z_index: ZIndex::Global(10),
+                        background_color: BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    for (index, option) in options.iter().enumerate() {
+                        list.spawn((
+                            Name::new("DropdownOption"),
+                            UiDropdownOption {
+                                dropdown: dropdown_entity,
+                                index,
+                            },
+                            ButtonBundle {
+                                style: Style {
+                                    width: Val::Percent(100.0),
+                                    height: Val::Px(24.0),
+                                    justify_content: JustifyContent::Center,
+                                    align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                        ))
+                        .with_children(|option_button| {
+                            option_button.spawn(TextBundle::from_section(
+                                option.clone(),
+                                widget_text_style(font.clone()),
+                            ));
+                        });
+                    }
+                });
+        });
+    });
+}
+
+/// formats slider value, whole numbers are shown without decimals
+fn format_slider(slider: &UiSlider) -> String {
+    if slider.step >= 1.0 {
+        format!("{:.0}", slider.value)
+    } else {
+        format!("{:.2}", slider.value)
+    }
+}
+
+/// formats toggle value
+const fn format_toggle(toggle: UiToggle) -> &'static str {
+    if toggle.0 {
+        "On"
+    } else {
+        "Off"
+    }
+}
+
+/// sets slider value from cursor position while the track is held
+fn drag_sliders(
+    mut sliders: Query<(
+        &Interaction,
+        &bevy::ui::RelativeCursorPosition,
+        &mut UiSlider,
+    )>,
+) {
+    for (interaction, cursor, mut slider) in &mut sliders {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let Some(normalized) = cursor.normalized else {
+            continue;
+        };
+        let value = slider.value_at(normalized.x);
+        slider.set_if_neq(UiSlider { value, ..*slider });
+    }
+}
+
+/// updates slider fill and value text
+fn update_slider_visuals(
+    sliders: Query<(&UiSlider, &Children), Changed<UiSlider>>,
+    mut fills: Query<&mut Style, With<UiSliderFill>>,
+    mut texts: Query<&mut Text, With<UiWidgetText>>,
+) {
+    for (slider, children) in &sliders {
+        for child in children {
+            if let Ok(mut fill) = fills.get_mut(*child) {
+                fill.width = Val::Percent(slider.fraction() * 100.0);
+            }
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = format_slider(slider);
+            }
+        }
+    }
+}
+
+/// flips toggles when pressed
+fn press_toggles(mut toggles: Query<(&Interaction, &mut UiToggle), Changed<Interaction>>) {
+    for (interaction, mut toggle) in &mut toggles {
+        if matches!(interaction, Interaction::Pressed) {
+            toggle.0 = !toggle.0;
+        }
+    }
+}
+
+/// updates toggle text
+fn update_toggle_text(
+    toggles: Query<(&UiToggle, &Children), Changed<UiToggle>>,
+    mut texts: Query<&mut Text, With<UiWidgetText>>,
+) {
+    for (toggle, children) in &toggles {
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.sections[0].value = format_toggle(*toggle).to_string();
+            }
+        }
+    }
+}
+
+/// opens or closes dropdown option lists when the dropdown is pressed
+fn toggle_dropdown_lists(
+    dropdowns: Query<(&Interaction, &Children), (Changed<Interaction>, With<UiDropdown>)>,
+    mut lists: Query<&mut Style, With<UiDropdownList>>,
+) {
+    for (interaction, children) in &dropdowns {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        for child in children {
+            if let Ok(mut list) = lists.get_mut(*child) {
+                list.display = if list.display == Display::None {
+                    Display::Flex
+                } else {
+                    Display::None
+                };
+            }
+        }
+    }
+}
+
+/// selects pressed dropdown option and closes its list
+fn select_dropdown_options(
+    options: Query<(&Interaction, &UiDropdownOption, &Parent), Changed<Interaction>>,
+    mut dropdowns: Query<&mut UiDropdown>,
+    mut lists: Query<&mut Style, With<UiDropdownList>>,
+) {
+    for (interaction, option, list) in &options {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        if let Ok(mut dropdown) = dropdowns.get_mut(option.dropdown) {
+            dropdown.selected = option.index;
+        }
+        if let Ok(mut list) = lists.get_mut(list.get()) {
+            list.display = Display::None;
+        }
+    }
+}
+
+/// updates dropdown text too selected option
+fn update_dropdown_text(
+    dropdowns: Query<(&UiDropdown, &Children), Changed<UiDropdown>>,
+    mut texts: Query<&mut Text, With<UiWidgetText>>,
+) {
+    for (dropdown, children) in &dropdowns {
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                dropdown
+                    .selected_text()
+                    .clone_into(&mut text.sections[0].value);
+            }
+        }
+    }
+}
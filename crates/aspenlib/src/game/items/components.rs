@@ -1,4 +1,9 @@
-use bevy::reflect::Reflect;
+use bevy::{
+    prelude::{Component, ReflectComponent},
+    reflect::Reflect,
+};
+
+use crate::game::attributes_stats::Effect;
 
 /// items function in the game
 #[derive(Debug, Reflect, Copy, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
@@ -12,3 +17,26 @@ pub enum ItemType {
     /// item is used too heal stats and give special effects for duration
     Food,
 }
+
+/// what a non weapon item does when picked up
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component)]
+pub enum ItemFunction {
+    /// items `EquipmentStats` are given too the carrier
+    Armor,
+    /// effect is applied too the carrier until the item is dropped
+    Trinket(Effect),
+    /// effect is applied once and the item is consumed
+    Food(Effect),
+}
+
+impl ItemFunction {
+    /// gets `ItemType` for this function
+    pub const fn item_type(&self) -> ItemType {
+        match self {
+            Self::Armor => ItemType::Armor,
+            Self::Trinket(_) => ItemType::Trinket,
+            Self::Food(_) => ItemType::Food,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::{RigidBodyDisabled, Velocity};
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::{
+    consts::TILE_SIZE,
+    game::{
+        attributes_stats::EffectQueue,
+        characters::{
+            components::{CharacterInventory, ItemSlot},
+            player::PlayerSelectedHero,
+        },
+        components::ActorColliderType,
+        input::action_maps,
+        items::{components::ItemFunction, utils::item_collider},
+    },
+    loading::registry::{ActorRegistry, RegistryIdentifier},
+};
+
+/// picks up or eats closest armor, trinket or food when interact is pressed
+///
+/// carried items are parented too the player, added too `CharacterInventory`
+/// and there physics are disabled until dropped
+#[allow(clippy::type_complexity)]
+pub fn pickup_closest_item(
+    mut cmds: Commands,
+    actions: Res<ActionState<action_maps::Gameplay>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut CharacterInventory,
+            &mut EffectQueue,
+        ),
+        With<PlayerSelectedHero>,
+    >,
+    item_query: Query<(Entity, &Transform, &RegistryIdentifier, &ItemFunction), Without<Parent>>,
+    item_colliders: Query<(Entity, &Parent), With<ActorColliderType>>,
+) {
+    if !actions.just_pressed(&action_maps::Gameplay::Interact) {
+        return;
+    }
+    let Ok((player, player_transform, mut inventory, mut effects)) = player_query.get_single_mut()
+    else {
+        return;
+    };
+
+    let Some((item, _, identifier, function)) = item_query
+        .iter()
+        .filter(|f| {
+            f.1.translation
+                .truncate()
+                .distance(player_transform.translation.truncate())
+                < TILE_SIZE * 2.0
+        })
+        .min_by(|lhs, rhs| {
+            let da = lhs
+                .1
+                .translation
+                .truncate()
+                .distance_squared(player_transform.translation.truncate());
+            let db = rhs
+                .1
+                .translation
+                .truncate()
+                .distance_squared(player_transform.translation.truncate());
+            da.total_cmp(&db)
+        })
+    else {
+        return;
+    };
+
+    match function {
+        ItemFunction::Food(effect) => {
+            if !effects.push_effect(*effect) {
+                warn!("effect queue full, cant eat {:?}", identifier);
+                return;
+            }
+            info!("ate {:?}", identifier);
+            cmds.entity(item).despawn_recursive();
+            return;
+        }
+        ItemFunction::Trinket(effect) => {
+            if !effects.push_effect(effect.from_source(item)) {
+                warn!("effect queue full, cant carry {:?}", identifier);
+                return;
+            }
+        }
+        ItemFunction::Armor => {}
+    }
+
+    let slot = (0..)
+        .map(ItemSlot)
+        .find(|slot| !inventory.items.values().any(|(_, used)| used == slot))
+        .unwrap_or_default();
+    info!("picked up {:?} into {:?}", identifier, slot);
+    inventory.items.insert(item, (identifier.clone(), slot));
+
+    for (collider, parent) in &item_colliders {
+        if parent.get() == item {
+            cmds.entity(collider).despawn_recursive();
+        }
+    }
+    stow_item(&mut cmds, item, player);
+}
+
+/// parents `item` too `carrier`, hides it and disables its physics until it is dropped
+///
+/// pickup colliders are not touched, despawn them before stowing
+pub fn stow_item(cmds: &mut Commands, item: Entity, carrier: Entity) {
+    cmds.entity(item).insert((
+        Transform::IDENTITY,
+        Visibility::Hidden,
+        RigidBodyDisabled,
+        Velocity::zero(),
+    ));
+    cmds.entity(carrier).push_children(&[item]);
+}
+
+/// drops the last picked up armor or trinket next too the player when `DropItem` is pressed
+///
+/// `sync_inventory_effects` then removes it from the inventory and ends its effect
+pub fn drop_last_item(
+    mut cmds: Commands,
+    actions: Res<ActionState<action_maps::Gameplay>>,
+    registry: Res<ActorRegistry>,
+    player_query: Query<(Entity, &Transform, &CharacterInventory), With<PlayerSelectedHero>>,
+    item_query: Query<&Name, With<ItemFunction>>,
+) {
+    if !actions.just_pressed(&action_maps::Gameplay::DropItem) {
+        return;
+    }
+    let Ok((player, player_transform, inventory)) = player_query.get_single() else {
+        return;
+    };
+    let Some((item, (identifier, _))) = inventory
+        .items
+        .iter()
+        .filter(|(item, _)| item_query.contains(**item))
+        .max_by_key(|(_, (_, slot))| slot.0)
+    else {
+        return;
+    };
+
+    info!("dropped {:?}", identifier);
+    let pixel_size = registry
+        .get_definition(identifier)
+        .map_or(Vec2::splat(TILE_SIZE), |f| f.actor.pixel_size);
+    let name = item_query.get(*item).cloned().unwrap_or_default();
+    cmds.entity(player).remove_children(&[*item]);
+    cmds.entity(*item)
+        .remove::<RigidBodyDisabled>()
+        .insert((
+            Transform::from_translation(
+                player_transform.translation + Vec3::new(TILE_SIZE, 0.0, 0.0),
+            ),
+            Visibility::Inherited,
+        ))
+        .with_children(|child| {
+            child.spawn(item_collider(&name, pixel_size));
+        });
+}
+
+/// removes dropped items from `CharacterInventory` and there trinket effects from `EffectQueue`
+pub fn sync_inventory_effects(
+    mut carriers: Query<(&mut CharacterInventory, &mut EffectQueue, Option<&Children>)>,
+) {
+    for (mut inventory, mut effects, children) in &mut carriers {
+        let dropped: Vec<Entity> = inventory
+            .items
+            .keys()
+            .filter(|item| !children.is_some_and(|f| f.contains(item)))
+            .copied()
+            .collect();
+
+        for item in dropped {
+            info!("item {:?} is no longer carried", item);
+            inventory.items.remove(&item);
+            effects.remove_source(item);
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 
 use crate::{
-    game::items::{components::ItemType, weapons, EventSpawnItem},
-    loading::{custom_assets::actor_definitions::ItemDefinition, registry::ActorRegistry},
+    game::items::{components::ItemType, utils::spawn_item, weapons, EventSpawnItem},
+    loading::registry::ActorRegistry,
 };
 
 // TODO: implement leveled list like system
@@ -12,7 +12,6 @@ pub fn spawn_item_on_event(
     mut item_spawn_requests: EventReader<EventSpawnItem>,
     global_transforms: Query<&GlobalTransform>,
     registry: Res<ActorRegistry>,
-    item_assets: Res<Assets<ItemDefinition>>,
 ) {
     for event in item_spawn_requests.read() {
         let Ok(requester_transform) = global_transforms.get(event.requester) else {
@@ -21,7 +20,7 @@ pub fn spawn_item_on_event(
         };
         let spawn_pos = &requester_transform.translation().truncate();
 
-        let Some(item_type) = registry.items.get_item_type(&event.spawn_data.0) else {
+        let Some(item_type) = registry.get_item_type(&event.spawn_data.0) else {
             error!(
                 "requested item did not exist in weapon registry: {:?}",
                 event.spawn_data.0
@@ -34,15 +33,14 @@ pub fn spawn_item_on_event(
                 info!("got weapon type");
                 weapons::utils::spawn_weapon(
                     &registry,
-                    &item_assets,
                     &event.spawn_data,
                     *spawn_pos,
                     &mut commands,
                 );
             }
-            a => {
-                warn!("item type not implemented: {:?}", a);
-                continue;
+            ItemType::Armor | ItemType::Trinket | ItemType::Food => {
+                info!("got {:?} type", item_type);
+                spawn_item(&registry, &event.spawn_data, *spawn_pos, &mut commands);
             }
         }
     }
@@ -1,10 +1,18 @@
-use crate::loading::registry::RegistryIdentifier;
+use crate::{
+    game::{characters::player::player_is_alive, items::components::ItemFunction, AppState},
+    loading::registry::RegistryIdentifier,
+    register_types,
+};
 use bevy::prelude::*;
 
 /// misc components
 pub mod components;
+/// armor, trinket and food pickup
+pub mod inventory;
 /// item spawner system
 pub mod item_spawner;
+/// armor, trinket and food spawning
+pub mod utils;
 /// weapon item plugin
 pub mod weapons;
 
@@ -13,14 +21,20 @@ pub struct ItemsPlugin;
 
 impl Plugin for ItemsPlugin {
     fn build(&self, app: &mut App) {
+        register_types!(app, [ItemFunction]);
         app.add_event::<EventSpawnItem>();
-        app.add_plugins((
-            // TODO: impl other items
-            weapons::WeaponItemPlugin,
-        ));
+        app.add_plugins((weapons::WeaponItemPlugin,));
         app.add_systems(
             Update,
-            item_spawner::spawn_item_on_event.run_if(on_event::<EventSpawnItem>()),
+            (
+                item_spawner::spawn_item_on_event.run_if(on_event::<EventSpawnItem>()),
+                (
+                    (inventory::pickup_closest_item, inventory::drop_last_item)
+                        .run_if(player_is_alive),
+                    inventory::sync_inventory_effects,
+                )
+                    .run_if(in_state(AppState::PlayingGame)),
+            ),
         );
     }
 }
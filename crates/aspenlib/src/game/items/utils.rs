@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_asepritesheet::prelude::{AnimEndAction, Spritesheet};
+use bevy_rapier2d::geometry::{Collider, CollisionGroups};
+use rand::{thread_rng, Rng};
+
+use crate::{
+    bundles::ActorColliderBundle,
+    consts::{AspenCollisionLayer, ACTOR_PHYSICS_Z_INDEX, ACTOR_Z_INDEX},
+    game::components::ActorColliderType,
+    loading::registry::{ActorRegistry, RegistryIdentifier},
+};
+
+/// finalizes spritesheet for armor, trinkets and food
+pub fn format_item_animations(sheet: &mut Spritesheet) {
+    let handle_idle = sheet.get_anim_handle("idle");
+
+    if let Ok(anim_idle) = sheet.get_anim_mut(&handle_idle) {
+        anim_idle.end_action = AnimEndAction::Loop;
+    }
+}
+
+/// spawns armor, trinket or food item
+pub fn spawn_item(
+    registry: &Res<ActorRegistry>,
+    spawn_data: &(RegistryIdentifier, i32),
+    spawn_position: Vec2,
+    commands: &mut Commands,
+) {
+    let Some(item_def) = registry.get_definition(&spawn_data.0) else {
+        error!(
+            "spawned items definition did not exist: {:?}",
+            &spawn_data.0
+        );
+        return;
+    };
+
+    let Some(item_bundle) = registry.get_item(&spawn_data.0) else {
+        error!(
+            "could not get ItemBundle from registry: {:?}",
+            &spawn_data.0
+        );
+        return;
+    };
+
+    let mut rng = thread_rng();
+    for _ in 0..spawn_data.1 {
+        let position = Vec2 {
+            x: spawn_position.x + rng.gen_range(-100.0..=100.0),
+            y: spawn_position.y + rng.gen_range(-100.0..=100.0),
+        };
+
+        let mut item = item_bundle.clone();
+        item.sprite.sprite_bundle.transform =
+            Transform::from_translation(position.extend(ACTOR_Z_INDEX));
+        info!("spawning item");
+        commands.spawn(item.clone()).with_children(|child| {
+            child.spawn(item_collider(&item.name, item_def.actor.pixel_size));
+        });
+    }
+}
+
+/// pickup collider for an item named `name` that is `pixel_size` big
+pub fn item_collider(name: &Name, pixel_size: Vec2) -> ActorColliderBundle {
+    ActorColliderBundle {
+        tag: ActorColliderType::Item,
+        name: Name::new(format!("{}Collider", name.as_str())),
+        collider: Collider::ball(pixel_size.max_element() / 2.0),
+        collision_groups: CollisionGroups::new(
+            AspenCollisionLayer::ACTOR,
+            AspenCollisionLayer::EVERYTHING,
+        ),
+        transform_bundle: TransformBundle::from_transform(Transform::from_xyz(
+            0.0,
+            0.0,
+            ACTOR_PHYSICS_Z_INDEX,
+        )),
+    }
+}
@@ -9,7 +9,18 @@ use bevy::{
 use crate::game::{attributes_stats::Damage, characters::components::WeaponSlot};
 
 /// current ammo count for weapons clip
-#[derive(Debug, Clone, Copy, Component, Default, Reflect, Deref, DerefMut)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Component,
+    Default,
+    Reflect,
+    Deref,
+    DerefMut,
+    serde::Deserialize,
+    serde::Serialize,
+)]
 #[reflect(Component)]
 pub struct CurrentAmmo {
     /// how much ammo this weapon currently has
@@ -51,13 +62,22 @@ pub struct WeaponCarrier {
     DerefMut,
     serde::Deserialize,
     serde::Serialize,
+    schemars::JsonSchema,
 )]
 #[reflect(Component)]
 pub struct AttackDamage(pub Damage);
 
 /// information describing how a weapon attacks and the paramaters for attack
 #[derive(
-    Debug, Clone, Copy, PartialEq, Component, Reflect, serde::Deserialize, serde::Serialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Component,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
 )]
 #[reflect(Component)]
 pub enum WeaponDescriptor {
@@ -96,13 +116,23 @@ pub struct BladeCfg {
 // others?
 
 /// encapsulated gun data for for enum variants
-#[derive(Debug, Clone, Copy, Reflect, serde::Deserialize, serde::Serialize, PartialEq)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Reflect,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+    PartialEq,
+)]
 pub struct GunCfg {
     /// projectile velocity
     pub projectile_speed: f32,
     /// projectile size
     pub projectile_size: f32,
     /// where projectile spawns
+    #[schemars(with = "[f32; 2]")]
     pub barrel_end: Vec2,
     /// how many shots before reload
     pub max_ammo: u32,
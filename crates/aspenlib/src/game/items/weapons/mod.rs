@@ -76,24 +76,26 @@ fn prepare_weapons(
             &WeaponDescriptor,
             &WeaponHolder,
             &RegistryIdentifier,
+            Option<&CurrentAmmo>,
         ),
         Added<WeaponDescriptor>,
     >,
 ) {
-    for (entity, weapon_descriptor, _, _) in &weapon_query {
+    for (entity, weapon_descriptor, _, _, ammo) in &weapon_query {
         match weapon_descriptor {
             WeaponDescriptor::Gun(cfg) => {
-                cmds.entity(entity).insert((
-                    WeaponTimers {
-                        attack: Timer::from_seconds(cfg.fire_rate, TimerMode::Once),
-                        refill: Timer::from_seconds(cfg.reload_time, TimerMode::Once),
-                        duration: Timer::from_seconds(cfg.fire_rate, TimerMode::Once),
-                    },
-                    CurrentAmmo {
+                cmds.entity(entity).insert(WeaponTimers {
+                    attack: Timer::from_seconds(cfg.fire_rate, TimerMode::Once),
+                    refill: Timer::from_seconds(cfg.reload_time, TimerMode::Once),
+                    duration: Timer::from_seconds(cfg.fire_rate, TimerMode::Once),
+                });
+                // restored weapons keep their ammo
+                if ammo.is_none() {
+                    cmds.entity(entity).insert(CurrentAmmo {
                         current: cfg.max_ammo,
                         max: cfg.max_ammo,
-                    },
-                ));
+                    });
+                }
                 warn!("gun weapons are unfinished");
             } // WeaponDescriptor::Flail { .. } => {
               //     warn!("flail weapons are unimplemented");
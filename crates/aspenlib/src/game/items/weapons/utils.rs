@@ -6,26 +6,25 @@ use crate::{
     bundles::ActorColliderBundle,
     consts::{AspenCollisionLayer, ACTOR_PHYSICS_Z_INDEX, ACTOR_Z_INDEX},
     game::components::ActorColliderType,
-    loading::{
-        custom_assets::actor_definitions::ItemDefinition,
-        registry::{ActorRegistry, RegistryIdentifier},
-    },
+    loading::registry::{ActorRegistry, RegistryIdentifier},
 };
 
 /// spawns weapon item
 pub fn spawn_weapon(
     registry: &Res<ActorRegistry>,
-    item_assets: &Res<Assets<ItemDefinition>>,
     spawn_data: &(RegistryIdentifier, i32),
     spawn_position: Vec2,
     commands: &mut Commands,
 ) {
-    let (_, item_def) = item_assets
-        .iter()
-        .find(|(_, asset)| asset.actor.identifier == spawn_data.0)
-        .expect("Spawned characters asset definition did not exist");
+    let Some(item_def) = registry.get_definition(&spawn_data.0) else {
+        error!(
+            "spawned items definition did not exist: {:?}",
+            &spawn_data.0
+        );
+        return;
+    };
 
-    let Some(weapon_bundle) = registry.items.weapons.get(&spawn_data.0) else {
+    let Some(weapon_bundle) = registry.get_weapon(&spawn_data.0) else {
         error!(
             "could not get WeaponBundle from registry: {:?}",
             &spawn_data.0
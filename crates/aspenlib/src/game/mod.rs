@@ -2,10 +2,7 @@ use bevy::prelude::*;
 
 use crate::{
     game::components::{ActorColliderType, TimeToLive},
-    loading::{
-        custom_assets::actor_definitions::{CharacterDefinition, ItemDefinition},
-        registry::RegistryIdentifier,
-    },
+    loading::registry::{ActorRegistry, RegistryIdentifier},
     register_types,
     utilities::{scale_to_fit, EntityCreator},
     AppState,
@@ -74,6 +71,7 @@ impl Plugin for AspenHallsPlugin {
                 game_world::GameWorldPlugin,
                 interface::InterfacePlugin,
                 animations::AnimationsPlugin,
+                attributes_stats::StatsPlugin,
             ))
             .add_systems(
                 Update,
@@ -99,8 +97,7 @@ fn time_to_live(
 fn update_actor_size(
     mut query: Query<(&mut Sprite, &TextureAtlas, &RegistryIdentifier)>,
     texture_atlasses: Res<Assets<TextureAtlasLayout>>,
-    obje_assets: Res<Assets<ItemDefinition>>,
-    char_assets: Res<Assets<CharacterDefinition>>,
+    registry: Res<ActorRegistry>,
 ) {
     for (mut sprite, texture_atlas, registry_identifier) in &mut query {
         if sprite.custom_size.is_some() {
@@ -113,23 +110,11 @@ fn update_actor_size(
         };
         let original_size = atlas.textures.first().expect("no textures in atlas").size();
 
-        let final_size: Vec2 = {
-            let maybe_characer = char_assets
-                .iter()
-                .find(|(_, asset)| asset.actor.identifier == *registry_identifier);
-            let maybe_item = obje_assets
-                .iter()
-                .find(|(_, asset)| asset.actor.identifier == *registry_identifier);
-
-            if let Some((_, def)) = maybe_characer {
-                def.actor.pixel_size
-            } else if let Some((_, def)) = maybe_item {
-                def.actor.pixel_size
-            } else {
-                warn!("character has no asset");
-                continue;
-            }
+        let Some(def) = registry.get_definition(registry_identifier) else {
+            warn!("actor has no definition: {:?}", registry_identifier);
+            continue;
         };
+        let final_size = def.actor.pixel_size;
 
         let new_custom_size = scale_to_fit(original_size.as_vec2(), final_size);
         sprite.custom_size = Some(new_custom_size);
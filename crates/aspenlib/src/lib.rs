@@ -22,6 +22,17 @@ mod loading;
 /// misc util functions that cant find a place
 mod utilities;
 
+/// dungeon layout generator without any bevy systems, used by the layout tests and fuzz target
+pub mod dungeon_generator {
+    pub use crate::game::game_world::dungeonator_v2::{
+        components::{
+            DungeonRoomDatabase, DungeonSettings, RoomDescriptor, RoomDistribution, RoomLevel,
+            RoomPreset, RoomShape, RoomType,
+        },
+        layout::{generate_layout, DungeonLayout, GeneratorError},
+    };
+}
+
 use crate::{
     game::{combat::SameUserDataFilter, DungeonFloor},
     loading::assets::AspenInitHandles,
@@ -31,6 +42,8 @@ use bevy::prelude::*;
 pub use bevy::color::palettes::css as colors;
 use bevy_rapier2d::prelude::{RapierConfiguration, RapierContext};
 pub use loading::config::*;
+pub use loading::schema::{write_definition_schemas, DEFAULT_SCHEMA_DIR};
+pub use loading::validate::{validate_pack, PackReport};
 
 /// application stages
 pub enum ApplicationStage {
@@ -74,6 +87,8 @@ pub enum AppState {
     PlayingGame, //(PlaySubStage),
     /// Game Paused in this state, rapier timestep set too 0.0, no physics, ai is also stopped
     PauseMenu,
+    /// run ended, summary of the finished run is shown
+    RunSummary,
     /// game failed to load an init asset. fatal error
     FailedLoadInit,
     /// game failed too load default pack
@@ -90,9 +105,11 @@ pub enum AppState {
 /// main app fn, configures app loop with logging, then
 /// then loads settings from config.toml and adds
 /// general game plugins
-pub fn start_app(cfg_file: ConfigFile) -> App {
+///
+/// `launch` holds launcher overrides for this session, use `LaunchOptions::default()` for none
+pub fn start_app(cfg_file: ConfigFile, launch: launch::LaunchOptions) -> App {
     println!("Hello World!!");
-    let mut vanillacoffee = loading::config::create_configured_app(cfg_file);
+    let mut vanillacoffee = loading::config::create_configured_app(cfg_file, launch);
 
     // add third party plugins
     vanillacoffee.add_plugins((
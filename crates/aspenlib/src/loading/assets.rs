@@ -9,7 +9,10 @@ use bevy::{
 use bevy_asset_loader::prelude::AssetCollection;
 use bevy_ecs_ldtk::prelude::LdtkProject;
 
-use crate::loading::custom_assets::actor_definitions::{CharacterDefinition, ItemDefinition};
+use crate::loading::custom_assets::{
+    actor_definitions::{ActorDefinition, DefinitionPatch},
+    floor_definitions::FloorDefinition,
+};
 
 /// ui textures
 #[derive(AssetCollection, Resource, Clone, Debug, Reflect)]
@@ -89,6 +92,24 @@ pub struct AspenTouchHandles {
     pub look_outline: Handle<Image>,
 }
 
+/// registry keys a pack must provide for the `AppState::Loading` collections,
+/// a test checks this list matches there `#[asset(key)]`s below
+pub const PACK_REQUIRED_KEYS: &[&str] = &[
+    // AspenAudioHandles
+    "ost",
+    "footsteps",
+    // AspenMapHandles
+    "default_levels",
+    // AspenTextureHandles
+    "debug",
+    "grass",
+    "dirt",
+    "grass_dirt_top",
+    // AspenDefinitionHandles
+    "character_definitions",
+    "item_definitions",
+];
+
 /// audio resource available
 #[derive(AssetCollection, Resource, Clone, Debug, Reflect)]
 pub struct AspenAudioHandles {
@@ -130,9 +151,85 @@ pub struct AspenTextureHandles {
 pub struct AspenDefinitionHandles {
     /// character definitons
     #[asset(key = "character_definitions", collection(typed))]
-    pub characters: Vec<Handle<CharacterDefinition>>,
+    pub characters: Vec<Handle<ActorDefinition>>,
 
     /// weapon item etc definitions
     #[asset(key = "item_definitions", collection(typed))]
-    pub items: Vec<Handle<ItemDefinition>>,
+    pub items: Vec<Handle<ActorDefinition>>,
+
+    /// `*.actor.toml/ron` definitions that arent listed as characters or items
+    #[asset(key = "actor_definitions", collection(typed), optional)]
+    pub actors: Option<Vec<Handle<ActorDefinition>>>,
+
+    /// partial definitions that patch definitions from lower priority packs
+    #[asset(key = "definition_patches", collection(typed), optional)]
+    pub patches: Option<Vec<Handle<DefinitionPatch>>>,
+
+    /// dungeon settings for each room level, hardcoded settings are used if missing
+    #[asset(key = "floor_definitions", optional)]
+    pub floors: Option<Handle<FloorDefinition>>,
+}
+
+/// checks `PACK_REQUIRED_KEYS` against the keys the pack collections load
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use bevy::prelude::*;
+    use bevy_asset_loader::prelude::{AssetCollection, DynamicAssets, StandardDynamicAsset};
+
+    use super::{
+        AspenAudioHandles, AspenDefinitionHandles, AspenMapHandles, AspenTextureHandles,
+        PACK_REQUIRED_KEYS,
+    };
+
+    /// app with an asset server and each of `keys` registered as a dynamic asset
+    fn app_with_keys(keys: &[&str]) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+
+        let mut dynamic_assets = DynamicAssets::default();
+        for key in keys {
+            dynamic_assets.register_asset(
+                *key,
+                Box::new(StandardDynamicAsset::File {
+                    path: format!("{key}.ron"),
+                }),
+            );
+        }
+        app.insert_resource(dynamic_assets);
+        app
+    }
+
+    /// starts loading each pack collection, panics if a required key is not registered
+    fn load_pack_collections(world: &mut World) {
+        AspenAudioHandles::load(world);
+        AspenMapHandles::load(world);
+        AspenTextureHandles::load(world);
+        AspenDefinitionHandles::load(world);
+    }
+
+    /// the pack collections load with only `PACK_REQUIRED_KEYS`,
+    /// and leaving out any of them fails
+    #[test]
+    fn pack_required_keys_match_collections() {
+        let mut app = app_with_keys(PACK_REQUIRED_KEYS);
+        load_pack_collections(app.world_mut());
+
+        for missing in PACK_REQUIRED_KEYS {
+            let keys: Vec<&str> = PACK_REQUIRED_KEYS
+                .iter()
+                .copied()
+                .filter(|key| key != missing)
+                .collect();
+            let mut app = app_with_keys(&keys);
+            let loaded = catch_unwind(AssertUnwindSafe(|| {
+                load_pack_collections(app.world_mut());
+            }));
+            assert!(
+                loaded.is_err(),
+                "'{missing}' is not required by the pack collections"
+            );
+        }
+    }
 }
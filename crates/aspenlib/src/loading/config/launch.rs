@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use std::str::FromStr;
+
+use crate::loading::config::{save_load::ConfigStorage, ConfigFile, GameDifficulty};
+
+/// launcher overrides that only apply too this session.
+///
+/// overridden values are not written back too `ConfigStorage` unless
+/// they are changed and applied from the settings menu
+#[derive(Debug, Clone, Default, Resource)]
+pub struct LaunchOptions {
+    /// storage config was loaded from, platform default if `None`
+    pub config_storage: Option<ConfigStorage>,
+    /// packs too load instead of `ConfigFile.enabled_packs`
+    pub enabled_packs: Option<Vec<String>>,
+    /// log filter used instead of `ConfigFile.log_filter`
+    pub log_filter: Option<String>,
+    /// start fullscreen or windowed
+    pub full_screen: Option<bool>,
+    /// window size in logical pixels
+    pub resolution: Option<Vec2>,
+    /// difficulty used instead of `GeneralSettings.game_difficulty`
+    pub difficulty: Option<GameDifficulty>,
+    /// seed for dungeon generation
+    pub seed: Option<u64>,
+    /// hero registry id too play as, skips the start menu and hideout
+    pub skip_menu_hero: Option<String>,
+}
+
+impl LaunchOptions {
+    /// returns `cfg` with these overrides applied
+    pub fn apply(&self, mut cfg: ConfigFile) -> ConfigFile {
+        if let Some(packs) = &self.enabled_packs {
+            cfg.enabled_packs.clone_from(packs);
+        }
+        if let Some(filter) = &self.log_filter {
+            cfg.log_filter = Some(filter.clone());
+        }
+        if let Some(full_screen) = self.full_screen {
+            cfg.window_settings.full_screen = full_screen;
+        }
+        if let Some(resolution) = self.resolution {
+            cfg.window_settings.resolution = resolution;
+        }
+        if let Some(difficulty) = self.difficulty {
+            cfg.general_settings.game_difficulty = difficulty;
+        }
+        cfg
+    }
+
+    /// returns `cfg` with the `saved` value for each field that still holds its override,
+    /// so overrides are only saved once changed in the settings menu
+    pub fn unapply(&self, saved: &ConfigFile, mut cfg: ConfigFile) -> ConfigFile {
+        if self.enabled_packs.is_some() {
+            cfg.enabled_packs.clone_from(&saved.enabled_packs);
+        }
+        if self.log_filter.is_some() {
+            cfg.log_filter.clone_from(&saved.log_filter);
+        }
+        if self.full_screen == Some(cfg.window_settings.full_screen) {
+            cfg.window_settings.full_screen = saved.window_settings.full_screen;
+        }
+        if self.resolution == Some(cfg.window_settings.resolution) {
+            cfg.window_settings.resolution = saved.window_settings.resolution;
+        }
+        if self.difficulty == Some(cfg.general_settings.game_difficulty) {
+            cfg.general_settings.game_difficulty = saved.general_settings.game_difficulty;
+        }
+        cfg
+    }
+}
+
+/// parses a `WIDTHxHEIGHT` window size like `1280x720`
+///
+/// # Errors
+/// returns an error if either side is missing, not a number or not positive
+pub fn parse_resolution(text: &str) -> Result<Vec2, String> {
+    let (width, height) = text
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got {text}"))?;
+    let parse_side = |side: &str| {
+        side.trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|f| f.is_finite() && *f > 0.0)
+            .ok_or_else(|| format!("{side} is not a valid window size"))
+    };
+    Ok(Vec2::new(parse_side(width)?, parse_side(height)?))
+}
+
+impl FromStr for GameDifficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Self::Easy),
+            "medium" => Ok(Self::Medium),
+            "hard" => Ok(Self::Hard),
+            "insane" => Ok(Self::Insane),
+            "megadeath" => Ok(Self::MegaDeath),
+            "debug" => Ok(Self::Debug),
+            _ => Err(format!(
+                "unknown difficulty {s}, expected easy, medium, hard, insane, megadeath or debug"
+            )),
+        }
+    }
+}
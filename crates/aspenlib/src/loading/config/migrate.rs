@@ -0,0 +1,323 @@
+use crate::loading::{
+    config::ConfigFile,
+    registry::overrides::{is_enum_variant, merge_toml_tables},
+};
+
+/// current `ConfigFile` layout version.
+///
+/// bump this and add a migration too `MIGRATIONS` when config fields are renamed or change type
+pub const CONFIG_VERSION: u32 = 1;
+
+/// upgrades a config table from the version at its index too the next version
+const MIGRATIONS: &[fn(&mut toml::Table)] = &[migrate_v0_to_v1];
+
+/// a config file after migrations and default filling
+#[derive(Debug)]
+pub struct MigratedConfig {
+    /// usable config
+    pub config: ConfigFile,
+    /// `config_version` the file had before migrating
+    pub from_version: u32,
+    /// what was changed, empty if the file was already current
+    pub changes: Vec<String>,
+}
+
+impl MigratedConfig {
+    /// true if the config on disk should be backed up and rewritten
+    pub fn needs_rewrite(&self) -> bool {
+        !self.changes.is_empty() && self.from_version <= CONFIG_VERSION
+    }
+}
+
+/// parses a `config.toml`, runs migrations from its `config_version` and fills
+/// missing or invalid fields from `ConfigFile::default()`
+///
+/// # Errors
+/// returns an error only if `text` is not valid toml
+pub fn migrate_config(text: &str) -> Result<MigratedConfig, String> {
+    let mut user: toml::Table = text.parse().map_err(|e| format!("{e}"))?;
+    let defaults = toml::Table::try_from(ConfigFile::default()).map_err(|e| e.to_string())?;
+    let mut changes = Vec::new();
+
+    let from_version = user
+        .get("config_version")
+        .and_then(toml::Value::as_integer)
+        .and_then(|f| u32::try_from(f).ok())
+        .unwrap_or(0);
+
+    if from_version > CONFIG_VERSION {
+        changes.push(format!(
+            "config_version {from_version} is newer than this game ({CONFIG_VERSION}), unknown fields are ignored"
+        ));
+    } else {
+        for (version, migration) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+            migration(&mut user);
+            changes.push(format!(
+                "migrated config_version {version} too {}",
+                version + 1
+            ));
+        }
+        user.insert(
+            "config_version".to_string(),
+            toml::Value::Integer(i64::from(CONFIG_VERSION)),
+        );
+    }
+
+    let mut unknown = Vec::new();
+    let mut missing = Vec::new();
+    compare_fields(&defaults, &user, "", &mut unknown, &mut missing);
+    changes.extend(
+        unknown
+            .into_iter()
+            .map(|f| format!("dropped unknown field {f}")),
+    );
+    changes.extend(
+        missing
+            .into_iter()
+            .map(|f| format!("added missing field {f}")),
+    );
+
+    // fields are applied one at a time so an invalid field only resets itself
+    let mut merged = defaults.clone();
+    let mut fields = Vec::new();
+    collect_fields(&defaults, &user, &mut Vec::new(), &mut fields);
+    for (path, value) in fields {
+        let mut candidate = merged.clone();
+        merge_toml_tables(&mut candidate, &nested_table(&path, value));
+        if toml::Value::Table(candidate.clone())
+            .try_into::<ConfigFile>()
+            .is_ok()
+        {
+            merged = candidate;
+        } else {
+            changes.push(format!(
+                "reset invalid field {} too default",
+                path.join(".")
+            ));
+        }
+    }
+
+    let config = toml::Value::Table(merged)
+        .try_into::<ConfigFile>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(MigratedConfig {
+        config,
+        from_version,
+        changes,
+    })
+}
+
+/// lists fields in `user` that `defaults` doesnt have and fields in `defaults` missing from `user`
+fn compare_fields(
+    defaults: &toml::Table,
+    user: &toml::Table,
+    prefix: &str,
+    unknown: &mut Vec<String>,
+    missing: &mut Vec<String>,
+) {
+    for (key, value) in user {
+        let path = format!("{prefix}{key}");
+        match (defaults.get(key), value) {
+            (None, _) => unknown.push(path),
+            (Some(toml::Value::Table(default_table)), toml::Value::Table(user_table))
+                if !is_enum_variant(default_table) && !is_enum_variant(user_table) =>
+            {
+                compare_fields(
+                    default_table,
+                    user_table,
+                    &format!("{path}."),
+                    unknown,
+                    missing,
+                );
+            }
+            _ => {}
+        }
+    }
+    for key in defaults.keys() {
+        if !user.contains_key(key) {
+            missing.push(format!("{prefix}{key}"));
+        }
+    }
+}
+
+/// lists every known field in `user` with its path.
+///
+/// struct tables are split into there fields, enum variants and other values are kept whole
+fn collect_fields(
+    defaults: &toml::Table,
+    user: &toml::Table,
+    path: &mut Vec<String>,
+    fields: &mut Vec<(Vec<String>, toml::Value)>,
+) {
+    for (key, value) in user {
+        let Some(default) = defaults.get(key) else {
+            continue;
+        };
+        path.push(key.clone());
+        match (default, value) {
+            (toml::Value::Table(default_table), toml::Value::Table(user_table))
+                if !is_enum_variant(default_table) && !is_enum_variant(user_table) =>
+            {
+                collect_fields(default_table, user_table, path, fields);
+            }
+            _ => fields.push((path.clone(), value.clone())),
+        }
+        path.pop();
+    }
+}
+
+/// wraps `value` in a table for each key in `path`
+fn nested_table(path: &[String], value: toml::Value) -> toml::Table {
+    let mut value = value;
+    for key in path.iter().skip(1).rev() {
+        value = toml::Value::Table(toml::Table::from_iter([(key.clone(), value)]));
+    }
+    toml::Table::from_iter([(path[0].clone(), value)])
+}
+
+/// version 0 files predate `config_version`.
+///
+/// no fields were renamed or changed type, the fields they lack
+/// (`enabled_packs`, `control_settings`, `active_save_slot`) are filled from defaults
+const fn migrate_v0_to_v1(_cfg: &mut toml::Table) {}
+
+/// `migrate_config` tests with real v0 config text
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loading::config::GameDifficulty;
+
+    /// `config.toml` as written by builds before `config_version` existed
+    const V0_CONFIG: &str = r#"
+log_filter = "info,symphonia=warn,blocking=warn,wgpu=error,naga=warn,gilrs=warn,bevy_ecs_tilemap=debug,big_brain=warn"
+
+[window_settings]
+v_sync = true
+frame_rate_target = 60.0
+full_screen = false
+window_scale_override = 1.0
+resolution = [1200.0, 720.0]
+
+[render_settings]
+msaa = true
+
+[sound_settings]
+master_volume = 0.5
+ambience_volume = 0.2
+music_volume = 0.2
+sound_volume = 0.2
+
+[general_settings]
+camera_zoom = 3.5
+
+[general_settings.game_difficulty.Custom]
+max_enemies_per_room = 12
+max_dungeon_amount = 5
+player_health_scale = 1.0
+player_damage_scale = 1.0
+player_speed_scale = 1.0
+enemy_health_scale = 2.0
+enemy_damage_scale = 1.0
+enemy_speed_scale = 1.0
+"#;
+
+    /// v0 files keep there values and are upgraded too `CONFIG_VERSION`
+    #[test]
+    fn v0_config_keeps_user_values() {
+        let migrated = migrate_config(V0_CONFIG).expect("v0 config is valid toml");
+        let config = migrated.config;
+
+        assert_eq!(migrated.from_version, 0);
+        assert!(migrated.needs_rewrite());
+        assert_eq!(config.config_version, CONFIG_VERSION);
+        assert!(config.render_settings.msaa);
+        assert!((config.sound_settings.master_volume - 0.5).abs() < f64::EPSILON);
+        let GameDifficulty::Custom(scales) = config.general_settings.game_difficulty else {
+            panic!("custom difficulty was not kept");
+        };
+        assert_eq!(scales.max_enemies_per_room, 12);
+        assert!((scales.enemy_health_scale - 2.0).abs() < f32::EPSILON);
+    }
+
+    /// fields added after v0 are reported and filled from defaults
+    #[test]
+    fn v0_config_gets_missing_fields() {
+        let migrated = migrate_config(V0_CONFIG).expect("v0 config is valid toml");
+
+        for field in ["enabled_packs", "control_settings", "active_save_slot"] {
+            assert!(
+                migrated
+                    .changes
+                    .contains(&format!("added missing field {field}")),
+                "{field} was not reported missing: {:?}",
+                migrated.changes
+            );
+        }
+        assert_eq!(
+            migrated.config.enabled_packs,
+            ConfigFile::default().enabled_packs
+        );
+    }
+
+    /// unit difficulty variants are stored as strings and kept whole
+    #[test]
+    fn unit_variant_difficulty_is_kept() {
+        let (general, _) = V0_CONFIG
+            .split_once("[general_settings.game_difficulty.Custom]")
+            .expect("v0 config has a custom difficulty");
+        let text = format!("{general}game_difficulty = \"Hard\"\n");
+        let migrated = migrate_config(&text).expect("config is valid toml");
+
+        assert_eq!(
+            migrated.config.general_settings.game_difficulty,
+            GameDifficulty::Hard
+        );
+    }
+
+    /// unknown fields at any depth are dropped without touching known fields
+    #[test]
+    fn unknown_fields_are_dropped() {
+        let mut text = toml::to_string(&ConfigFile::default()).expect("default config serializes");
+        text = text.replace("[render_settings]", "[render_settings]\nbloom = true");
+        text.push_str("\n[old_settings]\nzoom = 2\n");
+        let migrated = migrate_config(&text).expect("config is valid toml");
+
+        assert!(migrated
+            .changes
+            .contains(&"dropped unknown field render_settings.bloom".to_string()));
+        assert!(migrated
+            .changes
+            .contains(&"dropped unknown field old_settings".to_string()));
+        assert!(!migrated.config.render_settings.msaa);
+    }
+
+    /// a field with the wrong type resets too default, other fields are kept
+    #[test]
+    fn invalid_field_only_resets_itself() {
+        let text = V0_CONFIG.replace("msaa = true", "msaa = \"yes\"");
+        let migrated = migrate_config(&text).expect("config is valid toml");
+
+        assert!(migrated
+            .changes
+            .contains(&"reset invalid field render_settings.msaa too default".to_string()));
+        assert!(!migrated.config.render_settings.msaa);
+        assert!((migrated.config.sound_settings.master_volume - 0.5).abs() < f64::EPSILON);
+    }
+
+    /// a current default config needs no changes or rewrite
+    #[test]
+    fn current_config_is_unchanged() {
+        let text = toml::to_string(&ConfigFile::default()).expect("default config serializes");
+        let migrated = migrate_config(&text).expect("config is valid toml");
+
+        assert!(migrated.changes.is_empty(), "{:?}", migrated.changes);
+        assert!(!migrated.needs_rewrite());
+    }
+
+    /// text that is not toml is an error instead of a default config
+    #[test]
+    fn invalid_toml_is_an_error() {
+        assert!(migrate_config("render_settings = [").is_err());
+    }
+}
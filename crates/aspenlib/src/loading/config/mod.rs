@@ -5,19 +5,35 @@ use bevy::{
     window::{Cursor, PresentMode, WindowMode, WindowResized, WindowResolution},
 };
 use bevy_ecs_ldtk::assets::LdtkProject;
+use bevy_framepace::{FramepaceSettings, Limiter};
 use bevy_kira_audio::{AudioChannel, AudioControl};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    game::audio::{AmbienceSoundChannel, GameSoundChannel, MusicSoundChannel},
-    loading::splashscreen::MainCamera,
+    game::{
+        audio::{AmbienceSoundChannel, GameSoundChannel, MusicSoundChannel},
+        input::action_maps::{
+            default_bindings, default_gamepad_binding, default_kbm_binding, ActionBinding,
+            Gameplay, KbmBinding, REBINDABLE_ACTIONS,
+        },
+    },
+    loading::{
+        packs::{default_enabled_packs, EnabledPacks},
+        splashscreen::MainCamera,
+    },
     AppState,
 };
+use launch::LaunchOptions;
+use save_load::ConfigStorage;
 
 #[cfg(feature = "develop")]
 use bevy_inspector_egui::prelude::*;
 
+/// command line overrides for a single session
+pub mod launch;
+/// `config_version` upgrades for older config files
+pub mod migrate;
 /// functions too create default file and save file
 pub mod save_load;
 
@@ -25,8 +41,14 @@ pub mod save_load;
 #[derive(Reflect, Resource, Serialize, Deserialize, Clone, Debug)]
 #[reflect(Resource)]
 pub struct ConfigFile {
+    /// config layout version, older files are upgraded by `migrate::migrate_config`
+    #[serde(default)]
+    pub config_version: u32,
     /// preset log filter from cfg
     pub log_filter: Option<String>,
+    /// pack folder names too load from `assets/packs`, merged by manifest priority
+    #[serde(default = "default_enabled_packs")]
+    pub enabled_packs: Vec<String>,
     /// game window settings
     pub window_settings: WindowSettings,
     /// rendering settings
@@ -35,6 +57,12 @@ pub struct ConfigFile {
     pub sound_settings: SoundSettings,
     /// general settings like zoom and difficulty
     pub general_settings: GeneralSettings,
+    /// rebindable controls
+    #[serde(default)]
+    pub control_settings: ControlSettings,
+    /// save slot picked in the profile picker, loaded on launch
+    #[serde(default)]
+    pub active_save_slot: u32,
 }
 
 impl Default for ConfigFile {
@@ -42,10 +70,14 @@ impl Default for ConfigFile {
         Self {
             log_filter: Some("info,symphonia=warn,blocking=warn,wgpu=error,naga=warn,gilrs=warn,bevy_ecs_tilemap=debug,big_brain=warn".into()),
             // log_filter: Some("trace,log=warn,wgpu=error,naga=warn,gilrs=warn,bevy_ecs_tilemap=debug".into()),
+            enabled_packs: default_enabled_packs(),
+            config_version: migrate::CONFIG_VERSION,
             window_settings: WindowSettings::default(),
             render_settings: RenderSettings::default(),
             sound_settings: SoundSettings::default(),
             general_settings: GeneralSettings::default(),
+            control_settings: ControlSettings::default(),
+            active_save_slot: 0,
         }
     }
 }
@@ -74,6 +106,102 @@ pub struct RenderSettings {
     pub msaa: bool,
 }
 
+/// keyboard/mouse and gamepad bindings for `Gameplay` actions
+#[derive(Reflect, Resource, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[reflect(Resource)]
+pub struct ControlSettings {
+    /// bindings for each rebindable action, missing actions use defaults
+    pub bindings: Vec<ActionBinding>,
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        Self {
+            bindings: default_bindings(),
+        }
+    }
+}
+
+impl ControlSettings {
+    /// bindings for `action`, defaults if it isnt configured
+    pub fn binding(&self, action: Gameplay) -> ActionBinding {
+        self.bindings
+            .iter()
+            .find(|f| f.action == action)
+            .copied()
+            .unwrap_or(ActionBinding {
+                action,
+                keyboard: default_kbm_binding(action),
+                gamepad: default_gamepad_binding(action),
+            })
+    }
+
+    /// sets the keyboard/mouse button for `action`.
+    ///
+    /// if another action already uses `button` it gets the old button of
+    /// `action`, the swapped action is returned
+    ///
+    /// # Errors
+    /// returns the fixed axis action using `button`, W/A/S/D are kept for `Move`
+    pub fn rebind_kbm(
+        &mut self,
+        action: Gameplay,
+        button: KbmBinding,
+    ) -> Result<Option<Gameplay>, Gameplay> {
+        if let Some(fixed) = button.fixed_axis_conflict() {
+            return Err(fixed);
+        }
+        Ok(self.rebind_field(action, button, |f| &mut f.keyboard))
+    }
+
+    /// sets the gamepad button for `action`.
+    ///
+    /// if another action already uses `button` it gets the old button of
+    /// `action`, the swapped action is returned
+    pub fn rebind_gamepad(
+        &mut self,
+        action: Gameplay,
+        button: GamepadButtonType,
+    ) -> Option<Gameplay> {
+        self.rebind_field(action, button, |f| &mut f.gamepad)
+    }
+
+    /// swaps `button` into `field` of `action`, see `rebind_kbm`
+    fn rebind_field<T: Copy + PartialEq>(
+        &mut self,
+        action: Gameplay,
+        button: T,
+        field: fn(&mut ActionBinding) -> &mut Option<T>,
+    ) -> Option<Gameplay> {
+        let old = *field(self.binding_mut(action));
+        let conflict = REBINDABLE_ACTIONS
+            .iter()
+            .copied()
+            .filter(|f| *f != action)
+            .find(|other| *field(self.binding_mut(*other)) == Some(button));
+
+        if let Some(other) = conflict {
+            *field(self.binding_mut(other)) = old;
+        }
+        *field(self.binding_mut(action)) = Some(button);
+        conflict
+    }
+
+    /// mutable bindings for `action`, defaults are added if it isnt configured
+    fn binding_mut(&mut self, action: Gameplay) -> &mut ActionBinding {
+        let index = self
+            .bindings
+            .iter()
+            .position(|f| f.action == action)
+            .unwrap_or_else(|| {
+                let binding = self.binding(action);
+                self.bindings.push(binding);
+                self.bindings.len() - 1
+            });
+        &mut self.bindings[index]
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Reflect, PartialEq, PartialOrd, Default)]
 #[reflect(Default)]
 /// game difficulty enum
@@ -112,8 +240,7 @@ pub struct GeneralSettings {
     /// camera zoom
     #[cfg(not(feature = "develop"))]
     pub camera_zoom: f32,
-    /// game difficulty,
-    /// value ranging from 1-4, 1 being easiest, 4 being hardest
+    /// game difficulty preset or custom scales
     pub game_difficulty: GameDifficulty,
 }
 
@@ -159,12 +286,12 @@ pub struct SoundSettings {
     pub sound_volume: f64,
 }
 
-// TODO: refactor actors module to use this global difficulty resource
-// add a system that takes GeneralSettings.difficulty_settings and matches
-// that i32 and inserts this configured
 #[derive(Reflect, Debug, Serialize, Deserialize, Resource, Copy, Clone, PartialEq, PartialOrd)]
 #[reflect(Resource, Default)]
-/// difficulty resource used globally for configuring actors and dungeons
+/// difficulty resource used globally for configuring actors and dungeons.
+///
+/// resolved from `GeneralSettings.game_difficulty` when a dungeon is laid out
+#[serde(default)]
 pub struct DifficultyScales {
     /// not a scale, just an amount multiplied by total rooms
     pub max_enemies_per_room: i32,
@@ -184,6 +311,11 @@ pub struct DifficultyScales {
     pub enemy_damage_scale: f32,
     /// f32 used too scale
     pub enemy_speed_scale: f32,
+
+    /// part of carried coins kept when the player dies, 0.0 - 1.0
+    pub death_coin_keep: f32,
+    /// if false carried armor and trinkets are lost when the player dies
+    pub death_keeps_items: bool,
 }
 
 impl Default for DifficultyScales {
@@ -197,10 +329,19 @@ impl Default for DifficultyScales {
             enemy_damage_scale: 1.0,
             enemy_speed_scale: 1.0,
             player_speed_scale: 1.0,
+            death_coin_keep: 0.5,
+            death_keeps_items: false,
         }
     }
 }
 
+impl DifficultyScales {
+    /// multiplier for spawner enemy amounts, `max_enemies_per_room` relative too the default
+    pub fn enemy_amount_scale(&self) -> f32 {
+        self.max_enemies_per_room as f32 / Self::default().max_enemies_per_room as f32
+    }
+}
+
 impl Default for GeneralSettings {
     fn default() -> Self {
         Self {
@@ -238,8 +379,12 @@ impl Default for SoundSettings {
 }
 
 /// creates an `App` with logging and initialization assets
-pub fn create_configured_app(cfg_file: ConfigFile) -> App {
+///
+/// `launch` overrides are used for this session, `cfg_file` is kept as saved
+pub fn create_configured_app(cfg_file: ConfigFile, launch: LaunchOptions) -> App {
     let mut asha = App::new();
+    let saved_cfg = cfg_file.clone();
+    let cfg_file = launch.apply(cfg_file);
 
     asha.add_plugins((
         LogPlugin {
@@ -320,7 +465,13 @@ pub fn create_configured_app(cfg_file: ConfigFile) -> App {
     .insert_resource(cfg_file.window_settings)
     .insert_resource(cfg_file.sound_settings)
     .insert_resource(cfg_file.general_settings)
-    .insert_resource(difficulty_settings);
+    .insert_resource(cfg_file.control_settings)
+    .insert_resource(EnabledPacks(cfg_file.enabled_packs))
+    .insert_resource(difficulty_settings)
+    .insert_resource(saved_cfg)
+    .insert_resource(launch.config_storage.clone().unwrap_or_default())
+    .insert_resource(launch)
+    .add_event::<SaveSettingsEvent>();
 
     asha.init_state::<AppState>();
     asha.register_type::<ConfigFile>();
@@ -331,8 +482,8 @@ pub fn create_configured_app(cfg_file: ConfigFile) -> App {
             apply_window_settings.run_if(resource_changed::<WindowSettings>),
             apply_sound_settings.run_if(resource_changed::<SoundSettings>),
             apply_camera_zoom.run_if(resource_changed::<GeneralSettings>),
-            update_difficulty_settings.run_if(resource_changed::<GeneralSettings>),
             on_resize_system.run_if(on_event::<WindowResized>()),
+            save_settings_on_event.run_if(on_event::<SaveSettingsEvent>()),
         ),
     );
 
@@ -340,37 +491,66 @@ pub fn create_configured_app(cfg_file: ConfigFile) -> App {
     asha
 }
 
+/// send too write current settings too `ConfigStorage`
+#[derive(Debug, Event)]
+pub struct SaveSettingsEvent;
+
+/// copies current settings resources into `ConfigFile` and saves it too `ConfigStorage`,
+/// settings still holding a `LaunchOptions` override keep there saved value
+#[allow(clippy::too_many_arguments)]
+fn save_settings_on_event(
+    mut save_events: EventReader<SaveSettingsEvent>,
+    storage: Res<ConfigStorage>,
+    mut cfg_file: ResMut<ConfigFile>,
+    window_settings: Res<WindowSettings>,
+    sound_settings: Res<SoundSettings>,
+    general_settings: Res<GeneralSettings>,
+    control_settings: Res<ControlSettings>,
+    enabled_packs: Res<EnabledPacks>,
+    launch: Res<LaunchOptions>,
+    msaa: Res<Msaa>,
+) {
+    save_events.clear();
+    let mut current = cfg_file.clone();
+    current.window_settings = *window_settings;
+    current.sound_settings = *sound_settings;
+    current.general_settings = *general_settings;
+    current.control_settings.clone_from(&control_settings);
+    current.render_settings.msaa = *msaa != Msaa::Off;
+    current.enabled_packs.clone_from(&enabled_packs.0);
+    // `cfg_file` never holds command line overrides, keep it that way
+    *cfg_file = launch.unapply(&cfg_file, current);
+
+    info!("saving settings too {}", *storage);
+    save_load::save_config(&storage, &cfg_file);
+}
+
 //TODO: move this to loading plugin and only run it when the settings resource changes (clicking apply in the settings menu, or reacting to OS changes), or on game load.
 // (system ordering is important here) the camera needs to be spawned first or we get a panic
-// #[bevycheck::system]
 /// updates window settings if changed
 fn apply_window_settings(
-    // winit: NonSend<bevy::winit::WinitWindows>,
     window_settings: Res<WindowSettings>,
-    // mut frame_limiter_cfg: ResMut<FramepaceSettings>,
+    frame_limiter_cfg: Option<ResMut<FramepaceSettings>>,
     mut mut_window_entity: Query<(Entity, &mut Window)>,
     mut last_resolution: Local<Vec2>,
 ) {
-    let (_w_ent, mut b_window) = mut_window_entity.single_mut();
-
-    // TODO: fix this system too work better?
-    if window_settings.resolution == *last_resolution
-        && *last_resolution
-            == (Vec2 {
-                x: b_window.width(),
-                y: b_window.height(),
-            })
-    {
+    let Ok((_w_ent, mut b_window)) = mut_window_entity.get_single_mut() else {
         return;
-    }
-    *last_resolution = window_settings.resolution;
+    };
 
-    // let w_window = winit.get_window(w_ent).unwrap();
+    let present_mode = if window_settings.v_sync {
+        PresentMode::AutoVsync
+    } else {
+        PresentMode::AutoNoVsync
+    };
+    if b_window.present_mode != present_mode {
+        info!("setting present mode {:?}", present_mode);
+        b_window.present_mode = present_mode;
+    }
 
-    // let requested_limiter = Limiter::from_framerate(window_settings.frame_rate_target);
-    // if frame_limiter_cfg.limiter != requested_limiter {
-    //     frame_limiter_cfg.limiter = requested_limiter;
-    // }
+    if let Some(mut frame_limiter_cfg) = frame_limiter_cfg {
+        frame_limiter_cfg.limiter = Limiter::from_framerate(window_settings.frame_rate_target);
+    }
 
     if window_settings.full_screen && b_window.mode != WindowMode::BorderlessFullscreen {
         b_window.mode = WindowMode::BorderlessFullscreen;
@@ -380,6 +560,20 @@ fn apply_window_settings(
         b_window.resolution = window_settings.resolution.into();
     }
 
+    // resize events also update `WindowSettings.resolution`, only resize if it was changed elsewhere
+    let current_resolution = Vec2::new(b_window.width(), b_window.height());
+    if window_settings.resolution == *last_resolution
+        || window_settings.resolution == current_resolution
+    {
+        *last_resolution = window_settings.resolution;
+        return;
+    }
+    *last_resolution = window_settings.resolution;
+
+    if !window_settings.full_screen {
+        b_window.resolution = window_settings.resolution.into();
+    }
+
     info!(
         "Requested Window Resolution {}, Actual Resolution {:?}",
         window_settings.resolution, b_window.resolution
@@ -437,19 +631,18 @@ fn on_resize_system(
     }
 }
 
-/// updates `DifficultySettings` if player changes difficulty settings
-fn update_difficulty_settings(
+/// resolves `DifficultyScales` from `GeneralSettings`, runs before each dungeon is laid out
+/// so difficulty changes apply too the next dungeon
+pub fn update_difficulty_settings(
     levels: Query<(Entity, &Handle<LdtkProject>), With<Parent>>,
     general_settings: Res<GeneralSettings>,
-    mut cmds: Commands,
+    mut difficulty_settings: ResMut<DifficultyScales>,
 ) {
-    let level_amount = i32::try_from(levels.iter().len()).unwrap_or(1);
-    if let GameDifficulty::Custom(scales) = general_settings.game_difficulty {
-        cmds.insert_resource(scales);
-    } else {
-        let difficulty_settings: DifficultyScales =
-            create_difficulty_scales(*general_settings, Some(level_amount));
-        cmds.insert_resource(difficulty_settings);
+    let level_amount = i32::try_from(levels.iter().len()).unwrap_or(1).max(1);
+    let scales = create_difficulty_scales(*general_settings, Some(level_amount));
+    if *difficulty_settings != scales {
+        info!("applying difficulty {:?}", general_settings.game_difficulty);
+        *difficulty_settings = scales;
     }
 }
 
@@ -471,6 +664,8 @@ fn create_difficulty_scales(
             enemy_health_scale: 1.0,
             enemy_damage_scale: 1.0,
             enemy_speed_scale: 1.0,
+            death_coin_keep: 1.0,
+            death_keeps_items: true,
         },
         GameDifficulty::Easy => DifficultyScales {
             max_enemies_per_room: 10 * level_amount,
@@ -481,6 +676,8 @@ fn create_difficulty_scales(
             max_dungeon_amount: 5,
             enemy_speed_scale: 0.9,
             player_speed_scale: 1.2,
+            death_coin_keep: 1.0,
+            death_keeps_items: true,
         },
         GameDifficulty::Medium => DifficultyScales {
             max_enemies_per_room: 20 * level_amount,
@@ -491,6 +688,8 @@ fn create_difficulty_scales(
             max_dungeon_amount: 7,
             enemy_speed_scale: 1.0,
             player_speed_scale: 1.0,
+            death_coin_keep: 0.5,
+            death_keeps_items: false,
         },
         GameDifficulty::Hard => DifficultyScales {
             max_enemies_per_room: 30 * level_amount,
@@ -501,6 +700,8 @@ fn create_difficulty_scales(
             max_dungeon_amount: 9,
             enemy_speed_scale: 1.2,
             player_speed_scale: 1.0,
+            death_coin_keep: 0.5,
+            death_keeps_items: false,
         },
         GameDifficulty::Insane => DifficultyScales {
             max_enemies_per_room: 35 * level_amount,
@@ -511,6 +712,8 @@ fn create_difficulty_scales(
             max_dungeon_amount: 15,
             enemy_speed_scale: 1.5,
             player_speed_scale: 1.0,
+            death_coin_keep: 0.25,
+            death_keeps_items: false,
         },
         GameDifficulty::MegaDeath => DifficultyScales {
             max_enemies_per_room: 50 * level_amount,
@@ -521,6 +724,8 @@ fn create_difficulty_scales(
             max_dungeon_amount: 25,
             enemy_speed_scale: 1.7,
             player_speed_scale: 0.8,
+            death_coin_keep: 0.0,
+            death_keeps_items: false,
         },
     }
 }
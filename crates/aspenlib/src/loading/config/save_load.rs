@@ -1,35 +1,276 @@
-use crate::loading::config::ConfigFile;
-use std::path::Path;
+use bevy::prelude::*;
+use std::{fmt::Display, io::Write, path::PathBuf};
 
-/// saves a `ConfigFile` at `&Path`
-/// will panic if config file cant be deserialized into a string,
-/// or if it cant write a file
+use crate::loading::config::{migrate::migrate_config, ConfigFile};
+
+/// config file name used on desktop and android
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+/// prefix for `localStorage` keys used on web
+pub const STORAGE_KEY_PREFIX: &str = "aspen_halls";
+/// `localStorage` key used on web
+pub const CONFIG_STORAGE_KEY: &str = "aspen_halls.config";
+
+/// where settings are stored for this platform
+#[derive(Debug, Clone, Resource)]
+pub enum ConfigStorage {
+    /// config file on the filesystem
+    File(PathBuf),
+    /// browser `localStorage` key
+    LocalStorage(String),
+}
+
+impl Default for ConfigStorage {
+    fn default() -> Self {
+        Self::platform_default()
+    }
+}
+
+impl Display for ConfigStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::LocalStorage(key) => write!(f, "localStorage[{key}]"),
+        }
+    }
+}
+
+impl ConfigStorage {
+    /// desktop config is stored in `$XDG_CONFIG_HOME/AspenHalls` or `%APPDATA%\AspenHalls`
+    #[cfg(not(any(target_arch = "wasm32", target_os = "android")))]
+    pub fn platform_default() -> Self {
+        let dir = dirs::config_dir().map_or_else(|| PathBuf::from("."), |f| f.join("AspenHalls"));
+        Self::File(dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// android config is stored in the apps internal files dir
+    #[cfg(target_os = "android")]
+    pub fn platform_default() -> Self {
+        let dir = bevy::winit::ANDROID_APP
+            .get()
+            .and_then(|app| app.internal_data_path())
+            .unwrap_or_else(|| PathBuf::from("."));
+        Self::File(dir.join(CONFIG_FILE_NAME))
+    }
+
+    /// web config is stored in `localStorage`
+    #[cfg(target_arch = "wasm32")]
+    pub fn platform_default() -> Self {
+        Self::LocalStorage(CONFIG_STORAGE_KEY.to_string())
+    }
+
+    /// reads stored config text, `None` if nothing has been saved yet
+    ///
+    /// # Errors
+    /// returns an error if the storage exists but cant be read
+    pub fn read(&self) -> Result<Option<String>, String> {
+        match self {
+            Self::File(path) => match std::fs::read_to_string(path) {
+                Ok(text) => Ok(Some(text)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(format!("could not read {}: {e}", path.display())),
+            },
+            Self::LocalStorage(key) => local_storage_get(key),
+        }
+    }
+
+    /// replaces stored config text.
+    ///
+    /// files are written next too `path` then renamed over it,
+    /// so a crash while saving leaves the old file in place
+    ///
+    /// # Errors
+    /// returns an error if the storage cant be written
+    pub fn write(&self, text: &str) -> Result<(), String> {
+        match self {
+            Self::File(path) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)
+                        .map_err(|e| format!("could not create {}: {e}", parent.display()))?;
+                }
+                let tmp_path = path.with_file_name(format!("{}.tmp", self.file_name()));
+                let write_tmp = || -> std::io::Result<()> {
+                    let mut file = std::fs::File::create(&tmp_path)?;
+                    file.write_all(text.as_bytes())?;
+                    file.sync_all()?;
+                    std::fs::rename(&tmp_path, path)
+                };
+                write_tmp().map_err(|e| {
+                    let _ = std::fs::remove_file(&tmp_path);
+                    format!("could not write {}: {e}", path.display())
+                })
+            }
+            Self::LocalStorage(key) => local_storage_set(key, text),
+        }
+    }
+
+    /// deletes stored text, does nothing if nothing is stored
+    ///
+    /// # Errors
+    /// returns an error if the storage exists but cant be removed
+    pub fn remove(&self) -> Result<(), String> {
+        match self {
+            Self::File(path) => match std::fs::remove_file(path) {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(format!("could not remove {}: {e}", path.display())),
+            },
+            Self::LocalStorage(key) => local_storage_remove(key),
+        }
+    }
+
+    /// storage for another file in the same folder, or another key on web
+    pub fn sibling(&self, name: &str) -> Self {
+        match self {
+            Self::File(path) => Self::File(path.with_file_name(name)),
+            Self::LocalStorage(_) => Self::LocalStorage(format!("{STORAGE_KEY_PREFIX}.{name}")),
+        }
+    }
+
+    /// file name or key, used too name temp and backup files
+    fn file_name(&self) -> String {
+        match self {
+            Self::File(path) => path
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+                .unwrap_or_else(|| CONFIG_FILE_NAME.to_string()),
+            Self::LocalStorage(key) => key.clone(),
+        }
+    }
+
+    /// copies stored config too `$FILE.$TAG.bak` before it gets rewritten
+    ///
+    /// returns where the backup was written
+    ///
+    /// # Errors
+    /// returns an error if nothing is stored or the backup cant be written
+    pub fn backup(&self, tag: &str) -> Result<Self, String> {
+        let text = self.read()?.ok_or_else(|| format!("{self} is empty"))?;
+        let backup = match self {
+            Self::File(path) => {
+                Self::File(path.with_file_name(format!("{}.{tag}.bak", self.file_name())))
+            }
+            Self::LocalStorage(key) => Self::LocalStorage(format!("{key}.{tag}.bak")),
+        };
+        backup.write(&text)?;
+        Ok(backup)
+    }
+}
+
+/// loads the config from `storage`, migrating and backing up older configs.
 ///
-/// # Panics
-/// this function will panic if it cant serialize `app_settings`
-/// OR
-/// if `std::fs::write` encounters an error writing too `settings_path`
-pub fn save_settings(app_settings: &ConfigFile, settings_path: &Path) {
-    println!("Saving AppSettings, this overwrites current settings");
-    let serde_cfg = match toml::to_string(&app_settings) {
-        Ok(cfg) => {
-            println!("Successfully converted config file");
-            cfg
-        }
-        Err(error) => {
-            eprintln!("Couldn't convert game settings too `String`: {error}");
-            return;
+/// `defaults` is saved and used if nothing is stored yet, stored configs
+/// that arent valid toml are backed up and replaced with `defaults`
+pub fn load_config(storage: &ConfigStorage, defaults: ConfigFile) -> ConfigFile {
+    println!("loading config from {storage}");
+    let text = match storage.read() {
+        Ok(Some(text)) => text,
+        Ok(None) => {
+            println!("no config saved yet, creating default");
+            save_config(storage, &defaults);
+            return defaults;
+        }
+        Err(e) => {
+            eprintln!("There was an error accessing config: {e}");
+            return defaults;
         }
     };
-    match std::fs::write(settings_path, serde_cfg) {
-        Ok(()) => println!(
-            "Successfully saved settings too {}",
-            settings_path.display()
-        ),
-        Err(e) => eprintln!(
-            "There was an error saving config too {}: {}",
-            settings_path.display(),
-            e
-        ),
+
+    match migrate_config(&text) {
+        Err(e) => {
+            eprintln!("The app config is malformed: {e} \n config: {storage}");
+            backup_config(storage, "malformed");
+            save_config(storage, &defaults);
+            defaults
+        }
+        Ok(migrated) => {
+            for change in &migrated.changes {
+                println!("config: {change}");
+            }
+            if migrated.needs_rewrite() {
+                backup_config(storage, &format!("v{}", migrated.from_version));
+                save_config(storage, &migrated.config);
+            }
+            println!("Game Settings loaded successfully");
+            migrated.config
+        }
+    }
+}
+
+/// saves `config` too `storage`, errors are printed
+pub fn save_config(storage: &ConfigStorage, config: &ConfigFile) {
+    let text = match toml::to_string_pretty(config) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Couldn't convert game settings too `String`: {e}");
+            return;
+        }
     };
+    match storage.write(&text) {
+        Ok(()) => println!("Successfully saved settings too {storage}"),
+        Err(e) => eprintln!("There was an error saving config: {e}"),
+    }
+}
+
+/// backs up `storage` and prints the result
+fn backup_config(storage: &ConfigStorage, tag: &str) {
+    match storage.backup(tag) {
+        Ok(backup) => println!("Backed up {storage} too {backup}"),
+        Err(e) => eprintln!("Couldn't back up {storage}: {e}"),
+    }
+}
+
+/// browser `localStorage` for this page
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, String> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| "localStorage is not available".to_string())
+}
+
+/// reads `key` from `localStorage`
+#[cfg(target_arch = "wasm32")]
+fn local_storage_get(key: &str) -> Result<Option<String>, String> {
+    local_storage()?
+        .get_item(key)
+        .map_err(|e| format!("could not read localStorage[{key}]: {e:?}"))
+}
+
+/// writes `key` too `localStorage`
+#[cfg(target_arch = "wasm32")]
+fn local_storage_set(key: &str, text: &str) -> Result<(), String> {
+    local_storage()?
+        .set_item(key, text)
+        .map_err(|e| format!("could not write localStorage[{key}]: {e:?}"))
+}
+
+/// removes `key` from `localStorage`
+#[cfg(target_arch = "wasm32")]
+fn local_storage_remove(key: &str) -> Result<(), String> {
+    local_storage()?
+        .remove_item(key)
+        .map_err(|e| format!("could not remove localStorage[{key}]: {e:?}"))
+}
+
+/// `localStorage` only exists on web
+#[cfg(not(target_arch = "wasm32"))]
+fn local_storage_get(key: &str) -> Result<Option<String>, String> {
+    Err(format!(
+        "cant read localStorage[{key}], only available on web"
+    ))
+}
+
+/// `localStorage` only exists on web
+#[cfg(not(target_arch = "wasm32"))]
+fn local_storage_set(key: &str, _text: &str) -> Result<(), String> {
+    Err(format!(
+        "cant write localStorage[{key}], only available on web"
+    ))
+}
+
+/// `localStorage` only exists on web
+#[cfg(not(target_arch = "wasm32"))]
+fn local_storage_remove(key: &str) -> Result<(), String> {
+    Err(format!(
+        "cant remove localStorage[{key}], only available on web"
+    ))
 }
@@ -1,16 +1,29 @@
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
-use bevy::{asset::ReflectAsset, prelude::*};
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, ReflectAsset},
+    prelude::*,
+};
 use bevy_asepritesheet::core::AsepritesheetPlugin;
-use bevy_common_assets::{ron::RonAssetPlugin, toml::TomlAssetPlugin};
+use bevy_common_assets::toml::TomlAssetPlugin;
 
 use crate::{
     game::{
-        attributes_stats::{Attributes, Damage, ElementalEffect, PhysicalDamage},
+        attributes_stats::{
+            Attributes, Damage, Effect, EffectDuration, EffectTarget, EffectType, ElementalEffect,
+            PhysicalDamage,
+        },
         characters::{ai::components::AiType, components::CharacterType},
-        items::weapons::components::{AttackDamage, GunCfg, WeaponDescriptor},
+        items::{
+            components::ItemType,
+            weapons::components::{AttackDamage, GunCfg, WeaponDescriptor},
+        },
     },
-    loading::registry::RegistryIdentifier,
+    loading::registry::{overrides::merge_toml_tables, RegistryIdentifier},
 };
 
 /// plugin for actor asset definitions
@@ -18,31 +31,62 @@ pub struct ActorAssetPlugin;
 
 impl Plugin for ActorAssetPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.register_asset_reflect::<CharacterDefinition>()
-            .register_asset_reflect::<ItemDefinition>()
-            .add_systems(Startup, write_example_definitions)
+        app.init_asset::<ActorDefinition>()
+            .register_asset_reflect::<ActorDefinition>()
+            // actor definitions and older character/weapon definition files
+            .init_asset_loader::<ActorDefinitionLoader>()
             .add_plugins((
-                // plugin for character definition from files
-                TomlAssetPlugin::<CharacterDefinition>::new(&["character.toml"]),
-                RonAssetPlugin::<CharacterDefinition>::new(&["character.ron"]),
-                // plugin for item definition from files
-                TomlAssetPlugin::<ItemDefinition>::new(&["weapon.toml"]),
-                RonAssetPlugin::<ItemDefinition>::new(&["weapon.ron"]),
+                // partial definitions from higher priority packs
+                TomlAssetPlugin::<DefinitionPatch>::new(&["patch.toml"]),
                 // actor sprite sheet data
                 AsepritesheetPlugin::new(&["sprite.json"]),
             ));
     }
 }
 
-/// writes example actor asset definiitons too respective folders on game start
-fn write_example_definitions() {
-    write_character_def(None);
-    write_weapon_def(None);
+/// asset definition for every actor, characters and items share this
+#[derive(
+    Debug, Clone, Asset, Reflect, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+#[reflect(Asset)]
+pub struct ActorDefinition {
+    /// shared data for all actors
+    pub actor: ActorData,
+    /// data only used by this kind of actor
+    pub unique_data: ActorDefType,
 }
 
-/// character actor asset definition
-#[derive(Debug, Asset, Reflect, serde::Deserialize, serde::Serialize)]
-#[reflect(Asset)]
+impl ActorDefinition {
+    /// character asset type if this actor is a character
+    pub const fn character_type(&self) -> Option<CharacterAssetType> {
+        match self.unique_data {
+            ActorDefType::Character(character_type) => Some(character_type),
+            ActorDefType::Item(_) => None,
+        }
+    }
+
+    /// item asset type if this actor is an item
+    pub const fn item_type(&self) -> Option<ItemAssetType> {
+        match self.unique_data {
+            ActorDefType::Item(item_type) => Some(item_type),
+            ActorDefType::Character(_) => None,
+        }
+    }
+}
+
+/// what kind of actor a definition describes
+#[derive(
+    Debug, Copy, Clone, Reflect, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
+pub enum ActorDefType {
+    /// this actor is a character
+    Character(CharacterAssetType),
+    /// this actor is an item
+    Item(ItemAssetType),
+}
+
+/// older `*.character.toml/ron` file layout, read into an `ActorDefinition`
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
 pub struct CharacterDefinition {
     /// what type of character is this
     pub character_type: CharacterAssetType,
@@ -50,9 +94,17 @@ pub struct CharacterDefinition {
     pub actor: ActorData,
 }
 
-/// item actor asset definition
-#[derive(Debug, Asset, Reflect, serde::Deserialize, serde::Serialize)]
-#[reflect(Asset)]
+impl From<CharacterDefinition> for ActorDefinition {
+    fn from(def: CharacterDefinition) -> Self {
+        Self {
+            actor: def.actor,
+            unique_data: ActorDefType::Character(def.character_type),
+        }
+    }
+}
+
+/// older `*.weapon.toml/ron` file layout, read into an `ActorDefinition`
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
 pub struct ItemDefinition {
     /// info that describes this item
     pub item_type: ItemAssetType,
@@ -60,8 +112,17 @@ pub struct ItemDefinition {
     pub actor: ActorData,
 }
 
+impl From<ItemDefinition> for ActorDefinition {
+    fn from(def: ItemDefinition) -> Self {
+        Self {
+            actor: def.actor,
+            unique_data: ActorDefType::Item(def.item_type),
+        }
+    }
+}
+
 /// shared actor asset data
-#[derive(Debug, Reflect, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, Reflect, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
 pub struct ActorData {
     /// actors name
     pub name: String,
@@ -70,13 +131,153 @@ pub struct ActorData {
     /// path too aseprite containing animations and images
     pub aseprite_path: String,
     /// optional custom scale for weapon
+    #[schemars(with = "[f32; 2]")]
     pub pixel_size: Vec2,
     /// npc stats
     pub stats: Attributes,
 }
 
+/// file endings `ActorDefinitionLoader` can read
+pub const ACTOR_DEFINITION_EXTENSIONS: &[&str] = &[
+    "actor.toml",
+    "actor.ron",
+    "character.toml",
+    "character.ron",
+    "weapon.toml",
+    "weapon.ron",
+];
+
+/// parses an actor definition, the file ending decides which layout is used
+///
+/// # Errors
+/// returns an error if the file ending is unknown or the text does not parse
+pub fn parse_actor_definition(file_name: &str, text: &str) -> Result<ActorDefinition, String> {
+    let mut parts = file_name.rsplit('.');
+    let format = parts.next();
+    let layout = parts.next();
+
+    match (layout.unwrap_or_default(), format.unwrap_or_default()) {
+        ("actor", "toml") => toml::from_str(text).map_err(|e| e.to_string()),
+        ("actor", "ron") => ron::from_str(text).map_err(|e| e.to_string()),
+        ("character", "toml") => toml::from_str::<CharacterDefinition>(text)
+            .map(Into::into)
+            .map_err(|e| e.to_string()),
+        ("character", "ron") => ron::from_str::<CharacterDefinition>(text)
+            .map(Into::into)
+            .map_err(|e| e.to_string()),
+        ("weapon", "toml") => toml::from_str::<ItemDefinition>(text)
+            .map(Into::into)
+            .map_err(|e| e.to_string()),
+        ("weapon", "ron") => ron::from_str::<ItemDefinition>(text)
+            .map(Into::into)
+            .map_err(|e| e.to_string()),
+        _ => Err(format!("{file_name} is not an actor definition")),
+    }
+}
+
+/// loads `ActorDefinition`s from `*.actor.toml/ron` and the older character/weapon files
+#[derive(Debug, Default)]
+pub struct ActorDefinitionLoader;
+
+impl AssetLoader for ActorDefinitionLoader {
+    type Asset = ActorDefinition;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        let file_name = load_context
+            .path()
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(parse_actor_definition(&file_name, &text)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        ACTOR_DEFINITION_EXTENSIONS
+    }
+}
+
+/// partial actor definition from a `*.patch.toml` file
+///
+/// only the fields in the patch are changed, `actor.identifier` selects
+/// which definition gets patched. `character_type` and `item_type` from the
+/// older character/weapon layouts are moved into `unique_data` when loaded
+#[derive(Debug, Clone, Asset, TypePath, serde::Deserialize)]
+#[serde(from = "toml::Table")]
+pub struct DefinitionPatch(pub toml::Table);
+
+impl From<toml::Table> for DefinitionPatch {
+    fn from(mut table: toml::Table) -> Self {
+        for (old_key, variant) in [("character_type", "Character"), ("item_type", "Item")] {
+            if let Some(value) = table.remove(old_key) {
+                warn!("definition patch uses old key `{old_key}`, use `unique_data.{variant}`");
+                table.insert(
+                    "unique_data".to_string(),
+                    toml::Value::Table(toml::Table::from_iter([(variant.to_string(), value)])),
+                );
+            }
+        }
+        for key in table.keys() {
+            if !matches!(key.as_str(), "actor" | "unique_data") {
+                warn!("definition patch key `{key}` is unknown and will be ignored");
+            }
+        }
+        Self(table)
+    }
+}
+
+impl DefinitionPatch {
+    /// identifier of the definition this patch modifies
+    pub fn identifier(&self) -> Option<RegistryIdentifier> {
+        self.0
+            .get("actor")
+            .and_then(|actor| actor.get("identifier"))
+            .and_then(toml::Value::as_str)
+            .map(|id| RegistryIdentifier(id.to_string()))
+    }
+
+    /// true if this patch changes `actor.aseprite_path`
+    pub fn changes_sprite(&self) -> bool {
+        self.0
+            .get("actor")
+            .is_some_and(|actor| actor.get("aseprite_path").is_some())
+    }
+
+    /// merges this patch over `definition` and returns the patched copy
+    ///
+    /// # Errors
+    /// returns an error if `definition` cant be converted too toml
+    /// or the patched toml is not a valid `T`
+    pub fn apply<T>(&self, definition: &T) -> Result<T, Box<dyn std::error::Error>>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let mut base = toml::Table::try_from(definition)?;
+        merge_toml_tables(&mut base, &self.0);
+        Ok(toml::Value::Table(base).try_into::<T>()?)
+    }
+}
+
 /// information used too decide assets function
-#[derive(Debug, Reflect, Copy, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Debug,
+    Reflect,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    schemars::JsonSchema,
+)]
 pub enum CharacterAssetType {
     /// - final enemy of dungeon level
     /// - hostile too all npcs
@@ -139,7 +340,9 @@ impl CharacterAssetType {
 }
 
 /// different classes of items that can exist in the game
-#[derive(Debug, Copy, Clone, Reflect, serde::Deserialize, serde::Serialize)]
+#[derive(
+    Debug, Copy, Clone, Reflect, serde::Deserialize, serde::Serialize, schemars::JsonSchema,
+)]
 pub enum ItemAssetType {
     /// items that the holder can attack with
     Weapon {
@@ -149,169 +352,246 @@ pub enum ItemAssetType {
         form: WeaponDescriptor,
     },
     /// items that give the holder armor and attrs
-    Armor {},
+    Armor {
+        /// attributes given too the holder while carried
+        attributes: Attributes,
+    },
     /// items that give the holder small bonus / unique effects
-    Trinket {},
+    Trinket {
+        /// effect applied too the holder while carried
+        effect: Effect,
+    },
     /// items that give the user status effects
-    Food {},
+    Food {
+        /// effect applied when eaten
+        effect: Effect,
+    },
 }
 
-/// creates new weapon definition folder
-#[allow(unused)]
-pub fn write_weapon_def(def: Option<ItemDefinition>) {
-    let def = def.unwrap_or(ItemDefinition {
-        item_type: ItemAssetType::Weapon {
-            damage: AttackDamage(Damage {
-                physical: PhysicalDamage(30.0),
-                elemental: ElementalEffect::Fire(10.0),
-            }),
-            form: WeaponDescriptor::Gun(GunCfg {
-                projectile_speed: 50.0,
-                projectile_size: 15.0,
-                barrel_end: Vec2 { x: 20.0, y: 0.0 },
-                max_ammo: 50,
-                reload_time: 1.5,
-                fire_rate: 0.25,
-            }),
-        },
-        actor: ActorData {
-            name: "ExampleWeapon".to_owned(),
-            identifier: RegistryIdentifier("exampleweapon".to_owned()),
-            aseprite_path: "sprite_sheet.png".to_owned(),
-            pixel_size: Vec2 { x: 32.0, y: 32.0 },
-            stats: Attributes::WEAPON_DEFAULT,
-        },
-    });
+impl ItemAssetType {
+    /// gets assets corresponding `ItemType`
+    pub const fn as_itemtype(self) -> ItemType {
+        match self {
+            Self::Weapon { .. } => ItemType::Weapon,
+            Self::Armor { .. } => ItemType::Armor,
+            Self::Trinket { .. } => ItemType::Trinket,
+            Self::Food { .. } => ItemType::Food,
+        }
+    }
+}
 
-    let folder_path = format!("assets/packs/asha/items/w{}", def.actor.identifier.0);
-    let toml_path = format!("{}/{}.weapon.toml", folder_path, def.actor.identifier.0);
-    let ron_path = format!("{}/{}.weapon.ron", folder_path, def.actor.identifier.0);
-    write_definition(def, folder_path, ron_path, toml_path);
+/// kind of actor `scaffold_actor_definition` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScaffoldKind {
+    /// playable hero
+    Hero,
+    /// generic dungeon enemy
+    Creep,
+    /// dungeon boss
+    Boss,
+    /// runs away from creeps
+    Critter,
+    /// gun item
+    Weapon,
+    /// armor item
+    Armor,
+    /// trinket item
+    Trinket,
+    /// food item
+    Food,
 }
 
-/// creates new character definition folder
-#[allow(unused)]
-pub fn write_character_def(def: Option<CharacterDefinition>) {
-    let def = def.unwrap_or(CharacterDefinition {
-        character_type: CharacterAssetType::Creep { ai: AiType::Stupid },
-        actor: ActorData {
-            name: "ExampleNpc".to_owned(),
-            identifier: RegistryIdentifier("examplenpc".to_owned()),
-            aseprite_path: "sprite_sheet.png".to_owned(),
-            pixel_size: Vec2 { x: 32.0, y: 32.0 },
-            stats: Attributes::CREEP_DEFAULT,
-        },
-    });
-    let folder_path = format!("assets/packs/asha/characters/{}", def.actor.identifier.0);
-    let ron_path = format!("{}/{}.npc.ron", folder_path, def.actor.identifier.0);
-    let toml_path = format!("{}/{}.npc.toml", folder_path, def.actor.identifier.0);
-    write_definition(def, folder_path, ron_path, toml_path);
+impl std::str::FromStr for ScaffoldKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hero" => Ok(Self::Hero),
+            "creep" | "npc" => Ok(Self::Creep),
+            "boss" => Ok(Self::Boss),
+            "critter" => Ok(Self::Critter),
+            "weapon" | "gun" => Ok(Self::Weapon),
+            "armor" => Ok(Self::Armor),
+            "trinket" => Ok(Self::Trinket),
+            "food" => Ok(Self::Food),
+            _ => Err(format!(
+                "unknown actor kind '{s}', expected hero, creep, boss, critter, weapon, armor, trinket or food"
+            )),
+        }
+    }
 }
 
-//TODO: move folder formatting too write fn, only pass string and def too write_definition
-/// writes asset definiiton too file
-fn write_definition<T: Sized + serde::Serialize>(
-    def: T,
-    folder_path: String,
-    ron_path: String,
-    toml_path: String,
-) {
-    let Ok(toml) = toml::to_string(&def) else {
-        warn!("could not deserialize the asset");
-        return;
-    };
-    let Ok(ron) = ron::to_string(&def) else {
-        warn!("");
-        return;
-    };
-    match std::fs::create_dir(folder_path) {
-        Ok(()) => {
-            match std::fs::write(ron_path, ron) {
-                Ok(()) => {
-                    warn!("Wrote new definition");
-                }
-                Err(e) => {
-                    warn!("Couldnt write definiton: {}", e);
-                }
-            };
-            match std::fs::write(toml_path, toml) {
-                Ok(()) => {
-                    warn!("Wrote new definition");
-                }
-                Err(e) => {
-                    warn!("Couldnt write definiton: {}", e);
-                }
-            };
+impl ScaffoldKind {
+    /// pack sub folder and folder name prefix used for this kind, eg: `characters/cSlime`
+    const fn folder(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Hero => ("characters", "h"),
+            Self::Creep => ("characters", "c"),
+            Self::Boss => ("characters", "b"),
+            Self::Critter => ("characters", "cr"),
+            Self::Weapon => ("items", "w"),
+            Self::Armor => ("items", "a"),
+            Self::Trinket => ("items", "t"),
+            Self::Food => ("items", "f"),
+        }
+    }
+
+    /// example unique data and stats for this kind
+    const fn example_data(self) -> (ActorDefType, Attributes) {
+        let heal = Effect::new(
+            EffectDuration::OverTime(5.0),
+            EffectType::ValueModifier(50.0),
+            EffectTarget::Health,
+        );
+        match self {
+            Self::Hero => (
+                ActorDefType::Character(CharacterAssetType::Hero {
+                    ai: AiType::FollowerHero,
+                }),
+                Attributes::HERO_DEFAULT,
+            ),
+            Self::Creep => (
+                ActorDefType::Character(CharacterAssetType::Creep { ai: AiType::Stupid }),
+                Attributes::CREEP_DEFAULT,
+            ),
+            Self::Boss => (
+                ActorDefType::Character(CharacterAssetType::Boss { ai: AiType::Boss }),
+                Attributes::BOSS_DEFAULT,
+            ),
+            Self::Critter => (
+                ActorDefType::Character(CharacterAssetType::Critter {
+                    ai: AiType::Critter,
+                }),
+                Attributes::CREEP_DEFAULT,
+            ),
+            Self::Weapon => (
+                ActorDefType::Item(ItemAssetType::Weapon {
+                    damage: AttackDamage(Damage {
+                        physical: PhysicalDamage(30.0),
+                        elemental: ElementalEffect::Fire(10.0),
+                    }),
+                    form: WeaponDescriptor::Gun(GunCfg {
+                        projectile_speed: 50.0,
+                        projectile_size: 15.0,
+                        barrel_end: Vec2 { x: 20.0, y: 0.0 },
+                        max_ammo: 50,
+                        reload_time: 1.5,
+                        fire_rate: 0.25,
+                    }),
+                }),
+                Attributes::WEAPON_DEFAULT,
+            ),
+            Self::Armor => (
+                ActorDefType::Item(ItemAssetType::Armor {
+                    attributes: Attributes::ZERO,
+                }),
+                Attributes::ZERO,
+            ),
+            Self::Trinket => (
+                ActorDefType::Item(ItemAssetType::Trinket { effect: heal }),
+                Attributes::ZERO,
+            ),
+            Self::Food => (
+                ActorDefType::Item(ItemAssetType::Food { effect: heal }),
+                Attributes::ZERO,
+            ),
         }
-        Err(e) => {
-            warn!("Error making new definition folder: {}", e);
+    }
+}
+
+/// file format `scaffold_actor_definition` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DefinitionFormat {
+    /// `*.actor.toml`
+    #[default]
+    Toml,
+    /// `*.actor.ron`
+    Ron,
+}
+
+impl std::str::FromStr for DefinitionFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "toml" => Ok(Self::Toml),
+            "ron" => Ok(Self::Ron),
+            _ => Err(format!("unknown format '{s}', expected toml or ron")),
         }
-    };
+    }
 }
 
-// TODO: this actor asset definition structure might be better
-
-// #[derive(Debug, Default, Asset, Component, Reflect, serde::Deserialize, serde::Serialize)]
-// #[reflect(Asset, Component)]
-// pub struct ActorDefinition {
-//     /// actor display name
-//     pub name: String,
-//     /// actor registry identifier
-//     pub identifier: RegistryIdentifier,
-//     /// path too aseprite json containing animation and image data
-//     pub aseprite_path: String,
-//     /// optional custom scale for weapon
-//     pub pixel_size: Vec2,
-//     /// unique actor data
-//     pub unique_data: ActorDefType,
-// }
-
-// #[derive(Debug, Reflect, serde::Deserialize, serde::Serialize)]
-// pub enum ActorDefType {
-//     /// this actor is a character
-//     /// - let (character_type, controller, stats) = ActorDefType else {warn!("actor is not a character"); return}
-//     Character {
-//         character_type: CharacterType,
-//         controller: AiSetupConfig,
-//         stats: Attributes,
-//     },
-//     /// this actor is a weapon
-//     Weapon {
-//         /// how much weapon this damage does on attack
-//         damage: AttackDamage,
-//         /// what kind of weapon this is
-//         form: WeaponDescriptor,
-//         /// how much this weapon improves its holders stats
-//         stats: Attributes,
-//     },
-// }
-
-// impl ActorDefType {
-//     pub const DEFAULT_WEAPON: Self = Self::Weapon {
-//         damage: AttackDamage(Damage {
-//             physical: PhysicalDamage(10.0),
-//             elemental: ElementalEffect::None,
-//         }),
-//         form: WeaponDescriptor::Gun {
-//             projectile_speed: 10.0,
-//             projectile_size: 2.0,
-//             barrel_end: Vec2 { x: 0.0, y: 0.0 },
-//             ammo_amount: 7,
-//             reload_time: 1.0,
-//             fire_rate: 0.02,
-//         },
-//         stats: Attributes::WEAPON_DEFAULT,
-//     };
-
-//     pub const DEFAULT_CHARACTER: Self = Self::Character {
-//         character_type: CharacterType::Npc(NpcType::Creep),
-//         controller: AiSetupConfig::GameAI(AiType::Stupid),
-//         stats: Attributes::CREEP_DEFAULT,
-//     };
-// }
-
-// impl Default for ActorDefType {
-//     fn default() -> Self {
-//         Self::DEFAULT_WEAPON
-//     }
-// }
+/// writes an example definition for a new actor into `pack_dir`
+///
+/// the definition is written too `$PACK/characters/` or `$PACK/items/`
+/// in a folder named like the existing asha actors, eg: `cSlime/slime.actor.toml`
+///
+/// # Errors
+/// returns an error if `name` has no usable characters, the pack folder is missing,
+/// the definition file already exists or writing fails
+pub fn scaffold_actor_definition(
+    pack_dir: &Path,
+    kind: ScaffoldKind,
+    name: &str,
+    format: DefinitionFormat,
+) -> Result<PathBuf, String> {
+    let identifier: String = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if identifier.is_empty() {
+        return Err(format!("'{name}' cant be used as an actor name"));
+    }
+    if !pack_dir.is_dir() {
+        return Err(format!("pack folder {} does not exist", pack_dir.display()));
+    }
+
+    let (unique_data, stats) = kind.example_data();
+    let definition = ActorDefinition {
+        actor: ActorData {
+            name: name.to_owned(),
+            identifier: RegistryIdentifier(identifier.clone()),
+            aseprite_path: format!("{identifier}.sprite.json"),
+            pixel_size: Vec2 { x: 32.0, y: 32.0 },
+            stats,
+        },
+        unique_data,
+    };
+
+    let (text, extension) = match format {
+        DefinitionFormat::Toml => (
+            toml::to_string(&definition).map_err(|e| e.to_string())?,
+            "actor.toml",
+        ),
+        DefinitionFormat::Ron => (
+            ron::ser::to_string_pretty(&definition, ron::ser::PrettyConfig::default())
+                .map_err(|e| e.to_string())?,
+            "actor.ron",
+        ),
+    };
+
+    let (sub_folder, prefix) = kind.folder();
+    let folder_name: String = name.chars().filter(char::is_ascii_alphanumeric).collect();
+    let folder = pack_dir
+        .join(sub_folder)
+        .join(format!("{prefix}{folder_name}"));
+    let path = folder.join(format!("{identifier}.{extension}"));
+
+    std::fs::create_dir_all(&folder)
+        .map_err(|e| format!("could not create {}: {e}", folder.display()))?;
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => {
+                format!("{} already exists, not overwriting", path.display())
+            }
+            _ => format!("could not create {}: {e}", path.display()),
+        })?;
+    file.write_all(text.as_bytes())
+        .map_err(|e| format!("could not write {}: {e}", path.display()))?;
+
+    Ok(path)
+}
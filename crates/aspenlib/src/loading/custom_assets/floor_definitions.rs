@@ -0,0 +1,84 @@
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext, ReflectAsset},
+    prelude::*,
+};
+
+use crate::game::game_world::dungeonator_v2::components::{DungeonSettings, RoomLevel};
+
+/// plugin for dungeon floor asset definitions
+pub struct FloorAssetPlugin;
+
+impl Plugin for FloorAssetPlugin {
+    fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_asset::<FloorDefinition>()
+            .register_asset_reflect::<FloorDefinition>()
+            .init_asset_loader::<FloorDefinitionLoader>();
+    }
+}
+
+/// dungeon generator settings for each `RoomLevel`, loaded from a `*.floor.toml/ron` file
+///
+/// `seed` is ignored, dungeons get there seed when spawned
+#[derive(Debug, Clone, Default, Asset, Reflect, serde::Deserialize, serde::Serialize)]
+#[reflect(Asset)]
+pub struct FloorDefinition {
+    /// settings for each configured level
+    pub floors: Vec<DungeonSettings>,
+}
+
+impl FloorDefinition {
+    /// settings for `level`, levels without settings use the closest lower configured level
+    pub fn settings_for(&self, level: &RoomLevel) -> Option<&DungeonSettings> {
+        self.floors
+            .iter()
+            .filter(|f| f.level <= *level)
+            .max_by(|a, b| a.level.cmp(&b.level))
+    }
+}
+
+/// file endings `FloorDefinitionLoader` can read
+pub const FLOOR_DEFINITION_EXTENSIONS: &[&str] = &["floor.toml", "floor.ron"];
+
+/// parses a floor definition, the file ending decides the format
+///
+/// # Errors
+/// returns an error if the file ending is unknown or the text does not parse
+pub fn parse_floor_definition(file_name: &str, text: &str) -> Result<FloorDefinition, String> {
+    if file_name.ends_with(".floor.toml") {
+        toml::from_str(text).map_err(|e| e.to_string())
+    } else if file_name.ends_with(".floor.ron") {
+        ron::from_str(text).map_err(|e| e.to_string())
+    } else {
+        Err(format!("{file_name} is not a floor definition"))
+    }
+}
+
+/// loads `FloorDefinition`s from `*.floor.toml/ron` files
+#[derive(Debug, Default)]
+pub struct FloorDefinitionLoader;
+
+impl AssetLoader for FloorDefinitionLoader {
+    type Asset = FloorDefinition;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        let file_name = load_context
+            .path()
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Ok(parse_floor_definition(&file_name, &text)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        FLOOR_DEFINITION_EXTENSIONS
+    }
+}
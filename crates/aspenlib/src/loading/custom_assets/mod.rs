@@ -1,14 +1,18 @@
-use crate::loading::custom_assets::actor_definitions::ActorAssetPlugin;
+use crate::loading::custom_assets::{
+    actor_definitions::ActorAssetPlugin, floor_definitions::FloorAssetPlugin,
+};
 use bevy::app::Plugin;
 
 /// holds aspen halls custom actor asset plugins
 pub mod actor_definitions;
+/// holds aspen halls dungeon floor asset plugin
+pub mod floor_definitions;
 
 /// handles initialization of all custom assets
 pub struct AspenCustomAssetsPlugin;
 
 impl Plugin for AspenCustomAssetsPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        app.add_plugins(ActorAssetPlugin);
+        app.add_plugins((ActorAssetPlugin, FloorAssetPlugin));
     }
 }
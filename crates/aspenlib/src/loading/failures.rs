@@ -0,0 +1,68 @@
+use bevy::{asset::UntypedAssetLoadFailedEvent, prelude::*};
+use bevy_asset_loader::prelude::StandardDynamicAssetCollection;
+
+use crate::loading::packs::{registry_asset_paths, RegistryKeySources};
+
+/// tracks assets that failed too load so the failed load screens can show them
+pub struct LoadFailuresPlugin;
+
+impl Plugin for LoadFailuresPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetLoadFailures>().add_systems(
+            PreUpdate,
+            record_asset_failures.run_if(on_event::<UntypedAssetLoadFailedEvent>()),
+        );
+    }
+}
+
+/// a single asset that failed too load
+#[derive(Debug, Clone)]
+pub struct AssetLoadFailure {
+    /// asset path that failed
+    pub path: String,
+    /// registry key and pack that listed this path, if any
+    pub key: Option<(String, String)>,
+    /// loader error
+    pub error: String,
+}
+
+/// every asset that failed too load since the last retry
+#[derive(Debug, Default, Resource, Deref, DerefMut)]
+pub struct AssetLoadFailures(pub Vec<AssetLoadFailure>);
+
+/// stores failed asset loads with the registry key that requested them
+fn record_asset_failures(
+    asset_server: Res<AssetServer>,
+    key_sources: Res<RegistryKeySources>,
+    registries: Res<Assets<StandardDynamicAssetCollection>>,
+    mut failed_events: EventReader<UntypedAssetLoadFailedEvent>,
+    mut failures: ResMut<AssetLoadFailures>,
+) {
+    for event in failed_events.read() {
+        let path = event.path.path().to_string_lossy().to_string();
+        error!("asset failed too load {path}: {}", event.error);
+
+        let key = key_sources.get(&path).cloned().or_else(|| {
+            // registry files still in `Assets` were not merged by `PackPlugin`, eg: init
+            registries.iter().find_map(|(id, registry)| {
+                let (key, _) = registry
+                    .0
+                    .iter()
+                    .find(|(_, asset)| registry_asset_paths(asset).contains(&&path))?;
+                let registry_path = asset_server.get_path(id)?;
+                let pack = registry_path
+                    .path()
+                    .parent()
+                    .map(|f| f.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                Some((key.clone(), pack))
+            })
+        });
+
+        failures.push(AssetLoadFailure {
+            path,
+            key,
+            error: event.error.to_string(),
+        });
+    }
+}
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
-use crate::{loading::assets::*, AppState};
+use crate::{
+    loading::{assets::*, packs::AspenPackHandles},
+    AppState,
+};
 
 /// holds asset definitions
 pub mod assets;
@@ -9,10 +12,20 @@ pub mod assets;
 pub mod config;
 /// extra custom asset useable in game
 pub mod custom_assets;
+/// failed asset tracking for the failed load screens
+pub mod failures;
+/// pack manifests and pack load order
+pub mod packs;
+/// persistent player save profile
+pub mod profile;
 /// registry data
 pub mod registry;
+/// json schemas for definition files
+pub mod schema;
 /// splashscreen functions
 pub mod splashscreen;
+/// offline pack checks used by `aspen-validate`
+pub mod validate;
 
 /// This plugin loads all assets using [`AssetLoader`] from a third party bevy plugin
 /// Alternatively you can write the logic to load assets yourself
@@ -20,23 +33,23 @@ pub mod splashscreen;
 pub struct AppLoadingPlugin;
 
 impl Plugin for AppLoadingPlugin {
-    // TODO: convert the asset load plugin too handle the selected packs
-    // this can probably reside in the config file, the native launcher should be used too select packs
-    // mobile and web platforms will not make use of packs at this moment
+    // enabled packs come from `ConfigFile.enabled_packs`, there manifests and registrys
+    // are loaded while booting and merged into `DynamicAssets` before `AppState::Loading`
     fn build(&self, app: &mut App) {
         app.add_plugins((
             splashscreen::SplashPlugin,
             custom_assets::AspenCustomAssetsPlugin,
             registry::RegistryPlugin,
+            packs::PackPlugin,
+            failures::LoadFailuresPlugin,
+            profile::ProfilePlugin,
         ));
 
-        // TODO:
-        // make the pack plugin, using bevy_asset_loader and bevy_common_assets
-
         app.add_loading_state(
             LoadingState::new(AppState::BootingApp)
                 .load_collection::<AspenInitHandles>()
                 .load_collection::<AspenTouchHandles>()
+                .load_collection::<AspenPackHandles>()
                 .set_standard_dynamic_asset_collection_file_endings(["registry"].to_vec())
                 .with_dynamic_assets_file::<StandardDynamicAssetCollection>("init/pack.registry")
                 .continue_to_state(AppState::Loading)
@@ -46,10 +59,6 @@ impl Plugin for AppLoadingPlugin {
             LoadingState::new(AppState::Loading)
                 .continue_to_state(AppState::StartMenu)
                 .on_failure_continue_to_state(AppState::FailedLoadMenu)
-                .set_standard_dynamic_asset_collection_file_endings(["registry"].to_vec())
-                .with_dynamic_assets_file::<StandardDynamicAssetCollection>(
-                    "packs/asha/pack.registry",
-                )
                 .load_collection::<AspenDefinitionHandles>()
                 .load_collection::<AspenAudioHandles>()
                 .load_collection::<AspenMapHandles>()
@@ -0,0 +1,331 @@
+use bevy::{prelude::*, utils::HashMap};
+use bevy_asset_loader::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::{register_types, AppState};
+
+/// pack manifests are loaded from `packs/$PACK/` with this filename
+pub const PACK_MANIFEST_FILE: &str = "pack.manifest.ron";
+/// pack asset keys are loaded from `packs/$PACK/` with this filename
+pub const PACK_REGISTRY_FILE: &str = "pack.registry";
+
+/// loads enabled pack manifests and merges there registry files
+/// into the `DynamicAssets` used by the `AppState::Loading` collections
+pub struct PackPlugin;
+
+impl Plugin for PackPlugin {
+    fn build(&self, app: &mut App) {
+        register_types!(app, [EnabledPacks, PackLoadOrder, RegistryKeySources]);
+        app.add_plugins(RonAssetPlugin::<PackManifest>::new(&["manifest.ron"]))
+            .init_resource::<EnabledPacks>()
+            .init_resource::<PackLoadOrder>()
+            .init_resource::<RegistryKeySources>()
+            .add_systems(
+                OnTransition {
+                    exited: AppState::BootingApp,
+                    entered: AppState::Loading,
+                },
+                register_enabled_packs,
+            );
+    }
+}
+
+/// describes a content pack, read from `packs/$PACK/pack.manifest.ron`
+#[derive(Debug, Clone, Asset, Reflect, serde::Deserialize, serde::Serialize)]
+pub struct PackManifest {
+    /// pack name, should match the packs folder name
+    pub name: String,
+    /// pack version, informational only
+    pub version: String,
+    /// packs that must be loaded before this pack
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    /// packs with higher priority are merged later and override lower priority keys
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// pack names from `ConfigFile.enabled_packs`
+#[derive(Debug, Clone, Resource, Reflect, Deref)]
+#[reflect(Resource)]
+pub struct EnabledPacks(pub Vec<String>);
+
+impl Default for EnabledPacks {
+    fn default() -> Self {
+        Self(default_enabled_packs())
+    }
+}
+
+/// packs enabled if the config file doesn't list any
+pub fn default_enabled_packs() -> Vec<String> {
+    vec!["asha".to_string()]
+}
+
+/// resolved pack order, lowest priority first
+#[derive(Debug, Clone, Default, Resource, Reflect, Deref)]
+#[reflect(Resource)]
+pub struct PackLoadOrder(pub Vec<String>);
+
+/// registry key and pack for each asset path listed in a pack registry
+#[derive(Debug, Clone, Default, Resource, Reflect, Deref)]
+#[reflect(Resource)]
+pub struct RegistryKeySources(pub HashMap<String, (String, String)>);
+
+/// asset paths a registry entry points too
+pub fn registry_asset_paths(asset: &StandardDynamicAsset) -> Vec<&String> {
+    match asset {
+        StandardDynamicAsset::File { path } | StandardDynamicAsset::Folder { path } => {
+            vec![path]
+        }
+        StandardDynamicAsset::Files { paths } => paths.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// manifest and registry handles for a single enabled pack
+#[derive(Debug, Clone)]
+pub struct PackHandles {
+    /// pack folder name
+    pub name: String,
+    /// `pack.manifest.ron` handle
+    pub manifest: Handle<PackManifest>,
+    /// `pack.registry` handle
+    pub registry: Handle<StandardDynamicAssetCollection>,
+}
+
+/// handles for every enabled pack's manifest and registry
+///
+/// paths depend on `EnabledPacks` so this collection is implemented by hand
+#[derive(Debug, Clone, Resource)]
+pub struct AspenPackHandles {
+    /// enabled packs in config order
+    pub packs: Vec<PackHandles>,
+}
+
+impl AspenPackHandles {
+    /// loads manifest and registry for each pack in `EnabledPacks`
+    fn load_enabled(world: &World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let packs = world
+            .resource::<EnabledPacks>()
+            .iter()
+            .map(|name| PackHandles {
+                name: name.clone(),
+                manifest: asset_server.load(format!("packs/{name}/{PACK_MANIFEST_FILE}")),
+                registry: asset_server.load(format!("packs/{name}/{PACK_REGISTRY_FILE}")),
+            })
+            .collect();
+        Self { packs }
+    }
+}
+
+impl AssetCollection for AspenPackHandles {
+    fn create(world: &mut World) -> Self {
+        Self::load_enabled(world)
+    }
+
+    fn load(world: &mut World) -> Vec<UntypedHandle> {
+        Self::load_enabled(world)
+            .packs
+            .into_iter()
+            .flat_map(|pack| [pack.manifest.untyped(), pack.registry.untyped()])
+            .collect()
+    }
+}
+
+/// sorts manifests by priority then moves each pack after its dependencies.
+///
+/// packs with missing or circular dependencies are skipped,
+/// only the first manifest with a given name is used
+pub fn resolve_pack_order(manifests: &[PackManifest]) -> Vec<String> {
+    let mut remaining: Vec<&PackManifest> = Vec::with_capacity(manifests.len());
+    for manifest in manifests {
+        if remaining.iter().any(|kept| kept.name == manifest.name) {
+            error!("pack '{}' is enabled more than once", manifest.name);
+            continue;
+        }
+        remaining.push(manifest);
+    }
+    remaining.sort_by_key(|manifest| manifest.priority);
+
+    let mut ordered: Vec<String> = Vec::with_capacity(remaining.len());
+    while let Some(idx) = remaining.iter().position(|manifest| {
+        manifest
+            .dependencies
+            .iter()
+            .all(|dependency| ordered.contains(dependency))
+    }) {
+        ordered.push(remaining.remove(idx).name.clone());
+    }
+
+    for skipped in remaining {
+        let missing: Vec<&String> = skipped
+            .dependencies
+            .iter()
+            .filter(|dependency| !ordered.contains(dependency))
+            .collect();
+        error!(
+            "pack '{}' was skipped, dependencies not loaded: {:?}",
+            skipped.name, missing
+        );
+    }
+
+    ordered
+}
+
+/// merges enabled pack registries in load order and registers the merged keys.
+///
+/// `Files` keys are appended so packs can add definitions,
+/// any other key from a later pack replaces the earlier one
+fn register_enabled_packs(
+    mut cmds: Commands,
+    pack_handles: Option<Res<AspenPackHandles>>,
+    manifests: Res<Assets<PackManifest>>,
+    registries: Res<Assets<StandardDynamicAssetCollection>>,
+    mut dynamic_assets: ResMut<DynamicAssets>,
+) {
+    let Some(pack_handles) = pack_handles else {
+        error!("pack handles were not loaded, no packs will be registered");
+        return;
+    };
+
+    let loaded_packs: Vec<(&PackHandles, PackManifest)> = pack_handles
+        .packs
+        .iter()
+        .filter_map(|pack| {
+            let Some(manifest) = manifests.get(&pack.manifest) else {
+                error!("pack manifest for '{}' did not load", pack.name);
+                return None;
+            };
+            if pack.name != manifest.name {
+                warn!(
+                    "pack folder '{}' has manifest named '{}'",
+                    pack.name, manifest.name
+                );
+            }
+            Some((pack, manifest.clone()))
+        })
+        .collect();
+
+    let load_order = resolve_pack_order(
+        &loaded_packs
+            .iter()
+            .map(|(_, manifest)| manifest.clone())
+            .collect::<Vec<PackManifest>>(),
+    );
+    info!("pack load order: {:?}", load_order);
+
+    let mut merged: HashMap<String, StandardDynamicAsset> = HashMap::new();
+    let mut key_sources = RegistryKeySources::default();
+    for pack_name in &load_order {
+        let Some((pack, _)) = loaded_packs
+            .iter()
+            .find(|(_, manifest)| &manifest.name == pack_name)
+        else {
+            continue;
+        };
+        // registries stay loaded so retrying from `FailedLoadInit` can merge them again
+        let Some(registry) = registries.get(&pack.registry) else {
+            error!("pack registry for '{}' did not load", pack.name);
+            continue;
+        };
+
+        for (key, asset) in &registry.0 {
+            for path in registry_asset_paths(asset) {
+                key_sources
+                    .0
+                    .insert(path.clone(), (key.clone(), pack_name.clone()));
+            }
+            merge_registry_key(&mut merged, pack_name, key.clone(), asset.clone());
+        }
+    }
+
+    for (key, asset) in merged {
+        dynamic_assets.register_asset(key, Box::new(asset));
+    }
+    cmds.insert_resource(PackLoadOrder(load_order));
+    cmds.insert_resource(key_sources);
+}
+
+/// merges a single registry key into `merged`
+fn merge_registry_key(
+    merged: &mut HashMap<String, StandardDynamicAsset>,
+    pack_name: &str,
+    key: String,
+    asset: StandardDynamicAsset,
+) {
+    if let StandardDynamicAsset::Files { paths } = &asset
+        && let Some(StandardDynamicAsset::Files { paths: existing }) = merged.get_mut(&key)
+    {
+        debug!("pack '{pack_name}' added {} files too '{key}'", paths.len());
+        existing.extend(paths.iter().cloned());
+        return;
+    }
+
+    if merged.insert(key.clone(), asset).is_some() {
+        info!("pack '{pack_name}' overrides registry key '{key}'");
+    }
+}
+
+/// load order tests for `resolve_pack_order`
+#[cfg(test)]
+mod tests {
+    use super::{resolve_pack_order, PackManifest};
+
+    /// manifest named `name` with `priority` that depends on `dependencies`
+    fn manifest(name: &str, priority: i32, dependencies: &[&str]) -> PackManifest {
+        PackManifest {
+            name: name.to_string(),
+            version: "0.1.0".to_string(),
+            dependencies: dependencies.iter().map(ToString::to_string).collect(),
+            priority,
+        }
+    }
+
+    /// packs are sorted by priority, dependencies load first whatever there priority
+    #[test]
+    fn dependencies_load_first() {
+        let order = resolve_pack_order(&[
+            manifest("addon", 0, &["base"]),
+            manifest("late", 5, &[]),
+            manifest("base", 10, &[]),
+        ]);
+
+        assert_eq!(order, ["late", "base", "addon"]);
+    }
+
+    /// packs depending on a pack that isnt enabled are skipped
+    #[test]
+    fn missing_dependency_is_skipped() {
+        let order = resolve_pack_order(&[
+            manifest("base", 0, &[]),
+            manifest("addon", 1, &["not_enabled"]),
+        ]);
+
+        assert_eq!(order, ["base"]);
+    }
+
+    /// packs depending on each other are skipped
+    #[test]
+    fn dependency_cycle_is_skipped() {
+        let order = resolve_pack_order(&[
+            manifest("base", 0, &[]),
+            manifest("first", 1, &["second"]),
+            manifest("second", 1, &["first"]),
+        ]);
+
+        assert_eq!(order, ["base"]);
+    }
+
+    /// a pack enabled twice is only loaded once, using its first manifest
+    #[test]
+    fn duplicate_pack_loads_once() {
+        let order = resolve_pack_order(&[
+            manifest("base", 0, &[]),
+            manifest("addon", 1, &["base"]),
+            manifest("base", 2, &[]),
+        ]);
+
+        assert_eq!(order, ["base", "addon"]);
+    }
+}
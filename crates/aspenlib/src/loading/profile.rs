@@ -0,0 +1,378 @@
+use bevy::{app::AppExit, prelude::*};
+use std::fmt::Display;
+
+use crate::{
+    game::combat::PlayerSaveInformation,
+    loading::config::{save_load::ConfigStorage, ConfigFile},
+};
+
+/// save profile name for slot 0, stored next too the config file
+pub const PROFILE_FILE_NAME: &str = "profile.save";
+/// amount of save slots shown in the profile picker
+pub const MAX_SAVE_SLOTS: u32 = 6;
+/// current save profile layout version.
+///
+/// bump this and upgrade older profiles in `decode_profile` when fields are renamed or change type
+pub const PROFILE_VERSION: u32 = 1;
+/// first word of every save profile
+const PROFILE_MAGIC: &str = "ASPENSAVE";
+
+/// loads the save profile on launch and writes it on `SaveProfileEvent` and exit
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveProfileEvent>()
+            .init_resource::<PlayerSaveInformation>()
+            .add_systems(Startup, load_profile)
+            .add_systems(
+                Last,
+                save_profile.run_if(on_event::<SaveProfileEvent>().or_else(on_event::<AppExit>())),
+            );
+    }
+}
+
+/// send too write `PlayerSaveInformation` too `ProfileStorage`
+#[derive(Debug, Event)]
+pub struct SaveProfileEvent;
+
+/// where the save profile is stored
+#[derive(Debug, Clone, Resource)]
+pub struct ProfileStorage {
+    /// save slot this profile was loaded from
+    pub slot: u32,
+    /// profile file or `localStorage` key
+    pub storage: ConfigStorage,
+    /// false if the stored profile couldnt be read or is from a newer game,
+    /// progress is not saved so it doesnt get overwritten
+    pub writable: bool,
+}
+
+/// why a stored profile couldnt be loaded
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProfileError {
+    /// header or body couldnt be parsed
+    Malformed(String),
+    /// body doesnt match the checksum in the header
+    ChecksumMismatch {
+        /// checksum from the header
+        expected: u64,
+        /// checksum of the body
+        found: u64,
+    },
+    /// profile version this game doesnt know
+    UnknownVersion(u32),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(e) => write!(f, "malformed save profile: {e}"),
+            Self::ChecksumMismatch { expected, found } => write!(
+                f,
+                "save profile checksum is {found:016x}, expected {expected:016x}"
+            ),
+            Self::UnknownVersion(version) => write!(
+                f,
+                "save profile version {version} is unknown, this game reads version {PROFILE_VERSION}"
+            ),
+        }
+    }
+}
+
+/// profile text with a `ASPENSAVE $VERSION $CHECKSUM` header line followed by a toml body
+///
+/// # Errors
+/// returns an error if `profile` cant be serialized
+pub fn encode_profile(profile: &PlayerSaveInformation) -> Result<String, String> {
+    let body = toml::to_string_pretty(profile)
+        .map_err(|e| format!("couldnt convert save profile too `String`: {e}"))?;
+    Ok(format!(
+        "{PROFILE_MAGIC} {PROFILE_VERSION} {:016x}\n{body}",
+        checksum(body.as_bytes())
+    ))
+}
+
+/// reads profile text written by `encode_profile`
+///
+/// # Errors
+/// returns an error if the header is missing, the version is unknown,
+/// the checksum doesnt match or the body isnt a valid profile
+pub fn decode_profile(text: &str) -> Result<PlayerSaveInformation, ProfileError> {
+    let (header, body) = text
+        .split_once('\n')
+        .ok_or_else(|| ProfileError::Malformed("missing header".to_string()))?;
+    let mut header = header.split_whitespace();
+
+    if header.next() != Some(PROFILE_MAGIC) {
+        return Err(ProfileError::Malformed("not a save profile".to_string()));
+    }
+    let version = header
+        .next()
+        .and_then(|f| f.parse::<u32>().ok())
+        .ok_or_else(|| ProfileError::Malformed("missing version".to_string()))?;
+    if !(1..=PROFILE_VERSION).contains(&version) {
+        return Err(ProfileError::UnknownVersion(version));
+    }
+    let expected = header
+        .next()
+        .and_then(|f| u64::from_str_radix(f, 16).ok())
+        .ok_or_else(|| ProfileError::Malformed("missing checksum".to_string()))?;
+
+    let found = checksum(body.as_bytes());
+    if found != expected {
+        return Err(ProfileError::ChecksumMismatch { expected, found });
+    }
+
+    toml::from_str(body).map_err(|e| ProfileError::Malformed(e.to_string()))
+}
+
+/// 64 bit FNV-1a, stable across platforms and rust versions
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// what a save slot holds
+#[derive(Debug, Clone)]
+pub enum SlotContents {
+    /// nothing saved in this slot
+    Empty,
+    /// a readable profile
+    Profile(PlayerSaveInformation),
+    /// a profile that is corrupt or from a newer game
+    Unreadable(String),
+}
+
+/// storage for save `slot`, slot 0 uses `PROFILE_FILE_NAME`
+pub fn slot_storage(config_storage: &ConfigStorage, slot: u32) -> ConfigStorage {
+    if slot == 0 {
+        config_storage.sibling(PROFILE_FILE_NAME)
+    } else {
+        config_storage.sibling(&format!("profile_{slot}.save"))
+    }
+}
+
+/// reads save `slot` without changing it
+pub fn read_slot(config_storage: &ConfigStorage, slot: u32) -> SlotContents {
+    match slot_storage(config_storage, slot).read() {
+        Ok(None) => SlotContents::Empty,
+        Ok(Some(text)) => match decode_profile(&text) {
+            Ok(profile) => SlotContents::Profile(profile),
+            Err(e) => SlotContents::Unreadable(e.to_string()),
+        },
+        Err(e) => SlotContents::Unreadable(e),
+    }
+}
+
+/// loads save `slot` into a profile and the storage it should be saved too.
+///
+/// corrupt profiles are backed up and replaced, profiles that cant be
+/// read or are from a newer game are left alone and progress isnt saved
+pub fn load_slot(
+    config_storage: &ConfigStorage,
+    slot: u32,
+) -> (PlayerSaveInformation, ProfileStorage) {
+    let storage = slot_storage(config_storage, slot);
+
+    let (profile, writable) = match storage.read() {
+        Ok(None) => {
+            info!("no save profile at {storage}, starting a new one");
+            (PlayerSaveInformation::default(), true)
+        }
+        Ok(Some(text)) => match decode_profile(&text) {
+            Ok(profile) => {
+                info!("loaded save profile from {storage}");
+                (profile, true)
+            }
+            Err(e @ ProfileError::UnknownVersion(_)) => {
+                error!("{e}, progress will not be saved too {storage}");
+                (PlayerSaveInformation::default(), false)
+            }
+            Err(e) => {
+                error!("{storage}: {e}");
+                match storage.backup("corrupt") {
+                    Ok(backup) => {
+                        warn!("backed up corrupt save profile too {backup}, starting a new one");
+                        (PlayerSaveInformation::default(), true)
+                    }
+                    Err(e) => {
+                        error!(
+                            "couldnt back up corrupt save profile: {e}, progress will not be saved"
+                        );
+                        (PlayerSaveInformation::default(), false)
+                    }
+                }
+            }
+        },
+        Err(e) => {
+            error!("{e}, progress will not be saved");
+            (PlayerSaveInformation::default(), false)
+        }
+    };
+
+    (
+        profile,
+        ProfileStorage {
+            slot,
+            storage,
+            writable,
+        },
+    )
+}
+
+/// stamps `last_played` and writes `profile` too `storage`
+///
+/// # Errors
+/// returns an error if the profile cant be serialized or written
+pub fn write_profile(
+    storage: &ConfigStorage,
+    profile: &mut PlayerSaveInformation,
+) -> Result<(), String> {
+    profile.last_played = unix_time_now();
+    storage.write(&encode_profile(profile)?)
+}
+
+/// seconds since the unix epoch
+pub fn unix_time_now() -> u64 {
+    web_time::SystemTime::now()
+        .duration_since(web_time::UNIX_EPOCH)
+        .map_or(0, |f| f.as_secs())
+}
+
+/// loads the save slot picked in `ConfigFile.active_save_slot`
+fn load_profile(mut cmds: Commands, config_storage: Res<ConfigStorage>, cfg_file: Res<ConfigFile>) {
+    let slot = if cfg_file.active_save_slot < MAX_SAVE_SLOTS {
+        cfg_file.active_save_slot
+    } else {
+        warn!(
+            "active save slot {} doesnt exist, using slot 0",
+            cfg_file.active_save_slot
+        );
+        0
+    };
+    let (profile, storage) = load_slot(&config_storage, slot);
+    cmds.insert_resource(profile);
+    cmds.insert_resource(storage);
+}
+
+/// writes `PlayerSaveInformation` too `ProfileStorage`
+fn save_profile(storage: Res<ProfileStorage>, mut profile: ResMut<PlayerSaveInformation>) {
+    if !storage.writable {
+        warn!("not saving profile, {} is read only", storage.storage);
+        return;
+    }
+
+    match write_profile(&storage.storage, &mut profile) {
+        Ok(()) => info!("saved profile too {}", storage.storage),
+        Err(e) => error!("couldnt save profile: {e}"),
+    }
+}
+
+/// `encode_profile` and `decode_profile` tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// profile with every field changed from its default
+    fn test_profile() -> PlayerSaveInformation {
+        PlayerSaveInformation {
+            profile_name: "tester".to_string(),
+            last_played: 1_700_000_000,
+            all_time_damage: 1234.5,
+            runs_completed: 3,
+            runs_started: 7,
+            player_money: 250,
+            player_xp: 900,
+            total_kills: 88,
+            total_deaths: 4,
+            items_got: 12,
+            deepest_floor: 3,
+            time_played: 3600.25,
+        }
+    }
+
+    /// splits encoded profile text into its header words and body
+    fn split_profile(text: &str) -> (Vec<&str>, &str) {
+        let (header, body) = text.split_once('\n').expect("profile has a header line");
+        (header.split_whitespace().collect(), body)
+    }
+
+    /// a decoded profile equals the profile that was encoded
+    #[test]
+    fn profile_round_trips() {
+        let profile = test_profile();
+        let text = encode_profile(&profile).expect("profile encodes");
+
+        assert_eq!(decode_profile(&text), Ok(profile));
+    }
+
+    /// the header holds the magic word, `PROFILE_VERSION` and the body checksum
+    #[test]
+    fn header_describes_body() {
+        let text = encode_profile(&test_profile()).expect("profile encodes");
+        let (header, body) = split_profile(&text);
+
+        assert_eq!(header[0], PROFILE_MAGIC);
+        assert_eq!(header[1], PROFILE_VERSION.to_string());
+        assert_eq!(header[2], format!("{:016x}", checksum(body.as_bytes())));
+    }
+
+    /// editing the body without updating the checksum is detected
+    #[test]
+    fn edited_body_is_checksum_mismatch() {
+        let text = encode_profile(&test_profile()).expect("profile encodes");
+        let edited = text.replace("player_money = 250", "player_money = 999999");
+        assert_ne!(
+            text, edited,
+            "test profile should contain player_money = 250"
+        );
+        let (header, body) = split_profile(&edited);
+
+        assert_eq!(
+            decode_profile(&edited),
+            Err(ProfileError::ChecksumMismatch {
+                expected: u64::from_str_radix(header[2], 16).expect("checksum is hex"),
+                found: checksum(body.as_bytes()),
+            })
+        );
+    }
+
+    /// profiles from a newer game and version 0 are refused before the checksum is read
+    #[test]
+    fn unknown_version_is_refused() {
+        let text = encode_profile(&test_profile()).expect("profile encodes");
+        let current = format!("{PROFILE_MAGIC} {PROFILE_VERSION} ");
+
+        for version in [0, PROFILE_VERSION + 1] {
+            let changed = text.replacen(&current, &format!("{PROFILE_MAGIC} {version} "), 1);
+            assert_eq!(
+                decode_profile(&changed),
+                Err(ProfileError::UnknownVersion(version))
+            );
+        }
+    }
+
+    /// text without the header is malformed
+    #[test]
+    fn missing_header_is_malformed() {
+        let body = toml::to_string_pretty(&test_profile()).expect("profile serializes");
+
+        assert!(matches!(
+            decode_profile(&body),
+            Err(ProfileError::Malformed(_))
+        ));
+        assert!(matches!(
+            decode_profile("ASPENSAVE 1"),
+            Err(ProfileError::Malformed(_))
+        ));
+    }
+
+    /// the checksum is stable so profiles stay readable across builds
+    #[test]
+    fn checksum_is_fnv1a() {
+        assert_eq!(checksum(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(checksum(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
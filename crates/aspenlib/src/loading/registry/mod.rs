@@ -5,8 +5,9 @@ use bevy::{
     ecs::{
         component::Component,
         reflect::{ReflectComponent, ReflectResource},
-        system::{Res, Resource},
+        system::{Res, ResMut, Resource},
     },
+    log::{info, warn},
     prelude::{AssetServer, Assets, Commands, OnExit},
     reflect::Reflect,
     utils::HashMap,
@@ -14,15 +15,23 @@ use bevy::{
 use rand::prelude::{IteratorRandom, Rng};
 
 use crate::{
-    bundles::{CharacterBundle, WeaponBundle},
+    bundles::{CharacterBundle, ItemBundle, WeaponBundle},
     game::{characters::components::CharacterType, items::components::ItemType},
     loading::{
-        custom_assets::actor_definitions::{CharacterDefinition, ItemDefinition},
-        registry::utils::{build_character_bundles, build_item_bundles},
+        custom_assets::actor_definitions::{
+            ActorDefinition, CharacterAssetType, DefinitionPatch, ItemAssetType,
+        },
+        packs::PackLoadOrder,
+        registry::{
+            overrides::{resolve_definitions, RegistrySource},
+            utils::build_actor_bundles,
+        },
     },
     register_types, AppState,
 };
 
+/// pack override and patch resolution for definitions
+pub mod overrides;
 /// impls for registry and supporting parts
 mod reg_impl;
 /// misc functions for registry building
@@ -33,7 +42,7 @@ pub struct RegistryPlugin;
 
 impl Plugin for RegistryPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
-        register_types!(app, [ActorRegistry, RegistryIdentifier]);
+        register_types!(app, [ActorRegistry, RegistryIdentifier, RegistrySource]);
         app.add_systems(OnExit(AppState::Loading), create_actor_registry);
     }
 }
@@ -48,138 +57,148 @@ impl Plugin for RegistryPlugin {
     Clone,
     serde::Deserialize,
     serde::Serialize,
+    schemars::JsonSchema,
     Component,
     Reflect,
 )]
 #[reflect(Component)]
 pub struct RegistryIdentifier(pub String);
 
-// create items before weapons and weapons before characters
-// this way we can use the created weapon bundles too put weapons on the characters
 /// Database of all actors that can spawn in the game
 #[derive(Resource, Default, Reflect)]
 #[reflect(Resource)]
 pub struct ActorRegistry {
-    /// database of all carryables
-    pub items: ItemRegistry,
-    /// database of all characters
-    pub characters: CharacterRegistry,
+    /// every spawnable actor keyed by its identifier
+    pub actors: HashMap<RegistryIdentifier, RegistryEntry>,
 }
 
-/// list of all NPCs for the game, one of the heroes is the player
-#[derive(Default, Reflect)]
-pub struct CharacterRegistry {
-    /// final bad guys
-    pub bosses: HashMap<RegistryIdentifier, CharacterBundle>,
-    /// generic bad guys
-    pub creeps: HashMap<RegistryIdentifier, CharacterBundle>,
-    /// flavor characters
-    pub critters: HashMap<RegistryIdentifier, CharacterBundle>,
-    /// pets for other characters
-    pub hero_pets: HashMap<RegistryIdentifier, CharacterBundle>,
-    /// characters player can select too play or for followers
-    pub heroes: HashMap<RegistryIdentifier, CharacterBundle>,
-    /// helpful npcs for player, merchants etc etc etc
-    pub freindlies: HashMap<RegistryIdentifier, CharacterBundle>,
+/// a single spawnable actor
+#[derive(Clone, Reflect)]
+pub struct RegistryEntry {
+    /// resolved definition this entry was built from
+    pub definition: ActorDefinition,
+    /// bundle cloned when spawning this actor
+    pub bundle: ActorBundle,
+    /// pack that provided this entry
+    pub source: RegistrySource,
 }
 
-impl CharacterRegistry {
+/// spawnable bundle for a registry entry
+#[derive(Clone, Reflect)]
+pub enum ActorBundle {
+    /// heroes, creeps, bosses etc
+    Character(CharacterBundle),
+    /// items that can attack
+    Weapon(WeaponBundle),
+    /// armor, trinkets and food
+    Item(ItemBundle),
+}
+
+impl ActorRegistry {
+    /// gets registry entry for identifier
+    pub fn get(&self, identifier: &RegistryIdentifier) -> Option<&RegistryEntry> {
+        self.actors.get(identifier)
+    }
+
+    /// gets resolved definition for identifier
+    pub fn get_definition(&self, identifier: &RegistryIdentifier) -> Option<&ActorDefinition> {
+        self.get(identifier).map(|entry| &entry.definition)
+    }
+
     /// gets type of character for identifier
     pub fn get_character_type(&self, identifier: &RegistryIdentifier) -> Option<CharacterType> {
-        if self.bosses.contains_key(identifier) {
-            return Some(CharacterType::Boss);
-        } else if self.creeps.contains_key(identifier) {
-            return Some(CharacterType::Creep);
-        } else if self.critters.contains_key(identifier) {
-            return Some(CharacterType::Critter);
-        } else if self.freindlies.contains_key(identifier) {
-            return Some(CharacterType::Shopkeep);
-        } else if self.heroes.contains_key(identifier) {
-            return Some(CharacterType::Hero);
-        } else if self.hero_pets.contains_key(identifier) {
-            return Some(CharacterType::HeroPet);
-        }
-        None
+        self.get_definition(identifier)?
+            .character_type()
+            .map(CharacterAssetType::as_charactertype)
+    }
+
+    /// returns item type for this identifier, if it exists, else None
+    pub fn get_item_type(&self, identifier: &RegistryIdentifier) -> Option<ItemType> {
+        self.get_definition(identifier)?
+            .item_type()
+            .map(ItemAssetType::as_itemtype)
     }
 
     /// gets character bundle for requested identifier
     pub fn get_character(&self, identifier: &RegistryIdentifier) -> Option<CharacterBundle> {
-        if self.bosses.contains_key(identifier) {
-            self.bosses.get(identifier).cloned()
-        } else if self.creeps.contains_key(identifier) {
-            self.creeps.get(identifier).cloned()
-        } else if self.critters.contains_key(identifier) {
-            self.critters.get(identifier).cloned()
-        } else if self.freindlies.contains_key(identifier) {
-            self.freindlies.get(identifier).cloned()
-        } else if self.heroes.contains_key(identifier) {
-            self.heroes.get(identifier).cloned()
-        } else if self.hero_pets.contains_key(identifier) {
-            self.hero_pets.get(identifier).cloned()
-        } else {
-            None
+        match &self.get(identifier)?.bundle {
+            ActorBundle::Character(bundle) => Some(bundle.clone()),
+            _ => None,
+        }
+    }
+
+    /// gets weapon bundle for requested identifier
+    pub fn get_weapon(&self, identifier: &RegistryIdentifier) -> Option<&WeaponBundle> {
+        match &self.get(identifier)?.bundle {
+            ActorBundle::Weapon(bundle) => Some(bundle),
+            _ => None,
         }
     }
 
+    /// gets armor, trinket or food bundle for requested identifier
+    pub fn get_item(&self, identifier: &RegistryIdentifier) -> Option<&ItemBundle> {
+        match &self.get(identifier)?.bundle {
+            ActorBundle::Item(bundle) => Some(bundle),
+            _ => None,
+        }
+    }
+
+    /// iterates character bundles of `character_type`
+    pub fn characters_of_type(
+        &self,
+        character_type: CharacterType,
+    ) -> impl Iterator<Item = (&RegistryIdentifier, &CharacterBundle)> {
+        self.actors
+            .iter()
+            .filter_map(move |(identifier, entry)| match &entry.bundle {
+                ActorBundle::Character(bundle) if bundle.actor_type == character_type => {
+                    Some((identifier, bundle))
+                }
+                _ => None,
+            })
+    }
+
     /// returns random creep identifier
     pub fn random_creep_wave(&self) -> Vec<&RegistryIdentifier> {
         let mut rng = rand::thread_rng();
         let wave_length = rng.gen_range(0..=5);
-        self.creeps.keys().choose_multiple(&mut rng, wave_length)
-    }
-}
-
-/// list of all useable/equipabble/holdable actors for the game
-#[derive(Default, Reflect)]
-pub struct ItemRegistry {
-    /// availbe weapons for game
-    pub weapons: HashMap<RegistryIdentifier, WeaponBundle>,
-    /// availbe armor for game
-    pub armor: HashMap<RegistryIdentifier, WeaponBundle>,
-    /// availbe special items for game
-    pub trinkets: HashMap<RegistryIdentifier, WeaponBundle>,
-    /// availbe food style items for game
-    pub food: HashMap<RegistryIdentifier, WeaponBundle>,
-}
-
-impl ItemRegistry {
-    /// returns item type for this identifier, if it exists, else None
-    pub fn get_item_type(&self, identifier: &RegistryIdentifier) -> Option<ItemType> {
-        if self.weapons.contains_key(identifier) {
-            return Some(ItemType::Weapon);
-        } else if self.armor.contains_key(identifier) {
-            return Some(ItemType::Armor);
-        } else if self.trinkets.contains_key(identifier) {
-            return Some(ItemType::Trinket);
-        } else if self.food.contains_key(identifier) {
-            return Some(ItemType::Food);
-        }
-        None
+        self.characters_of_type(CharacterType::Creep)
+            .map(|(identifier, _)| identifier)
+            .choose_multiple(&mut rng, wave_length)
     }
 }
 
 /// creates an actor registry and populates it from actor asset definitons
+///
+/// definitions are resolved by pack priority first, see `overrides::resolve_definitions`
 pub fn create_actor_registry(
     mut cmds: Commands,
     asset_server: Res<AssetServer>,
-    character_definitions: Res<Assets<CharacterDefinition>>,
-    weapon_definition: Res<Assets<ItemDefinition>>,
+    load_order: Res<PackLoadOrder>,
+    patches: Res<Assets<DefinitionPatch>>,
+    mut actor_definitions: ResMut<Assets<ActorDefinition>>,
 ) {
     let mut registry = ActorRegistry::default();
 
-    build_item_bundles(
-        &mut cmds,
-        weapon_definition,
+    let actors = resolve_definitions(
+        "actor",
+        &mut actor_definitions,
+        &patches,
         &asset_server,
-        &mut registry.items,
+        &load_order,
+        |def| &def.actor.identifier,
     );
 
-    build_character_bundles(
-        &mut cmds,
-        character_definitions,
-        asset_server,
-        &mut registry.characters,
-    );
+    for (_, patch) in patches.iter() {
+        if let Some(id) = patch.identifier()
+            && !actors.iter().any(|f| f.definition.actor.identifier == id)
+        {
+            warn!("definition patch targets unknown identifier {:?}", id);
+        }
+    }
+
+    build_actor_bundles(&mut cmds, actors, &asset_server, &mut registry);
 
+    info!("actor registry created: {:?}", registry);
     cmds.insert_resource(registry);
 }
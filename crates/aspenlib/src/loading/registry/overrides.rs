@@ -0,0 +1,316 @@
+use std::path::{Path, PathBuf};
+
+use bevy::{
+    asset::{Asset, AssetId, AssetServer, Assets},
+    log::{error, info, warn},
+    reflect::Reflect,
+    utils::HashMap,
+};
+
+use crate::loading::{
+    custom_assets::actor_definitions::DefinitionPatch, packs::PackLoadOrder,
+    registry::RegistryIdentifier,
+};
+
+/// which pack a registry entry came from
+#[derive(Debug, Clone, Default, Reflect)]
+pub struct RegistrySource {
+    /// pack that provided the definition this entry was built from
+    pub pack: String,
+    /// packs that patched the definition, in the order they were applied
+    pub patched_by: Vec<String>,
+}
+
+/// definition left after pack overrides and patches are applied
+pub struct ResolvedDefinition<T> {
+    /// final definition data
+    pub definition: T,
+    /// folder the definition was loaded from, sprite paths are relative too this
+    pub folder: PathBuf,
+    /// where this definition came from
+    pub source: RegistrySource,
+}
+
+/// a loaded definition or patch with its pack and load order position
+struct PackedAsset<T> {
+    /// position of pack in `PackLoadOrder`, unknown packs load first
+    rank: Option<usize>,
+    /// asset path used too keep order stable inside a pack
+    path: PathBuf,
+    /// pack folder name
+    pack: String,
+    /// asset id in the `Assets` store
+    id: AssetId<T>,
+}
+
+/// gets the pack name from an asset path like `packs/$PACK/...`
+pub fn pack_of_path(path: &Path) -> String {
+    let mut components = path.components().map(|f| f.as_os_str().to_string_lossy());
+    match (components.next(), components.next()) {
+        (Some(root), Some(pack)) if root == "packs" => pack.to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+/// collects assets of `T` sorted by pack load order then path
+fn sort_by_pack<T: Asset>(
+    assets: &Assets<T>,
+    asset_server: &AssetServer,
+    load_order: &PackLoadOrder,
+) -> Vec<PackedAsset<T>> {
+    let mut packed: Vec<PackedAsset<T>> = assets
+        .ids()
+        .filter_map(|id| {
+            let Some(asset_path) = asset_server.get_path(id) else {
+                warn!("definition {id:?} has no asset path, skipping");
+                return None;
+            };
+            let path = asset_path.path().to_path_buf();
+            let pack = pack_of_path(&path);
+            Some(PackedAsset {
+                rank: load_order.iter().position(|f| f == &pack),
+                path,
+                pack,
+                id,
+            })
+        })
+        .collect();
+    packed.sort_by(|a, b| (a.rank, &a.path).cmp(&(b.rank, &b.path)));
+    packed
+}
+
+/// resolves definitions with the same `RegistryIdentifier` across packs.
+///
+/// a definition from a higher priority pack replaces the lower one, then
+/// `DefinitionPatch`es are merged over the winner field by field.
+/// replaced definitions are removed from `definitions` and patched ones are
+/// written back so lookups by identifier elsewhere get the final data
+pub fn resolve_definitions<T>(
+    kind: &str,
+    definitions: &mut Assets<T>,
+    patches: &Assets<DefinitionPatch>,
+    asset_server: &AssetServer,
+    load_order: &PackLoadOrder,
+    identifier: impl Fn(&T) -> &RegistryIdentifier,
+) -> Vec<ResolvedDefinition<T>>
+where
+    T: Asset + Clone + serde::Serialize + serde::de::DeserializeOwned,
+{
+    let mut winners: HashMap<RegistryIdentifier, PackedAsset<T>> = HashMap::new();
+    for candidate in sort_by_pack(definitions, asset_server, load_order) {
+        let Some(definition) = definitions.get(candidate.id) else {
+            continue;
+        };
+        let id = identifier(definition).clone();
+
+        if let Some(replaced) = winners.insert(id.clone(), candidate) {
+            let winner = &winners[&id];
+            if replaced.pack == winner.pack {
+                warn!(
+                    "{kind} {:?} is defined twice in pack '{}': {:?} replaces {:?}",
+                    id, winner.pack, winner.path, replaced.path
+                );
+            } else {
+                info!(
+                    "{kind} {:?} from pack '{}' overrides pack '{}'",
+                    id, winner.pack, replaced.pack
+                );
+            }
+            definitions.remove(replaced.id);
+        }
+    }
+
+    let sorted_patches = sort_by_pack(patches, asset_server, load_order);
+    let mut resolved = Vec::with_capacity(winners.len());
+    for (id, winner) in winners {
+        let Some(mut definition) = definitions.get(winner.id).cloned() else {
+            continue;
+        };
+        let mut folder = winner
+            .path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let mut source = RegistrySource {
+            pack: winner.pack.clone(),
+            patched_by: Vec::new(),
+        };
+
+        for patch_asset in &sorted_patches {
+            let Some(patch) = patches.get(patch_asset.id) else {
+                continue;
+            };
+            if patch.identifier().as_ref() != Some(&id) {
+                continue;
+            }
+            if patch_asset.rank < winner.rank {
+                warn!(
+                    "{kind} patch {:?} from pack '{}' is lower priority than definition from pack '{}', skipping",
+                    patch_asset.path, patch_asset.pack, winner.pack
+                );
+                continue;
+            }
+            match patch.apply(&definition) {
+                Ok(patched) => {
+                    info!(
+                        "{kind} {:?} patched by pack '{}' with {:?}",
+                        id, patch_asset.pack, patch_asset.path
+                    );
+                    if patch.changes_sprite()
+                        && let Some(patch_folder) = patch_asset.path.parent()
+                    {
+                        folder = patch_folder.to_path_buf();
+                    }
+                    definition = patched;
+                    source.patched_by.push(patch_asset.pack.clone());
+                }
+                Err(e) => error!(
+                    "{kind} patch {:?} could not be applied too {:?}: {e}",
+                    patch_asset.path, id
+                ),
+            }
+        }
+
+        if !source.patched_by.is_empty() {
+            definitions.insert(winner.id, definition.clone());
+        }
+        resolved.push(ResolvedDefinition {
+            definition,
+            folder,
+            source,
+        });
+    }
+
+    resolved
+}
+
+/// deep merges `patch` into `base`.
+///
+/// externally tagged enums that change variant are replaced instead of merged
+pub fn merge_toml_tables(base: &mut toml::Table, patch: &toml::Table) {
+    for (key, value) in patch {
+        if let toml::Value::Table(patch_table) = value
+            && let Some(toml::Value::Table(base_table)) = base.get_mut(key)
+            && !is_variant_change(base_table, patch_table)
+        {
+            merge_toml_tables(base_table, patch_table);
+            continue;
+        }
+        base.insert(key.clone(), value.clone());
+    }
+}
+
+/// true if both tables are enum variants and the variant differs
+fn is_variant_change(base: &toml::Table, patch: &toml::Table) -> bool {
+    is_enum_variant(base) && is_enum_variant(patch) && base.keys().ne(patch.keys())
+}
+
+/// true if `table` is an externally tagged enum variant like `game_difficulty = { Custom = .. }`.
+///
+/// serde writes variants in `PascalCase` and struct fields in `snake_case`,
+/// so single field structs like `render_settings` are not mistaken for variants
+pub fn is_enum_variant(table: &toml::Table) -> bool {
+    table.len() == 1 && table.keys().all(|f| f.starts_with(char::is_uppercase))
+}
+
+/// `merge_toml_tables` tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// parses a toml table for a test
+    fn table(text: &str) -> toml::Table {
+        text.parse().expect("test toml is valid")
+    }
+
+    /// patched fields are merged into nested tables, other fields are kept
+    #[test]
+    fn nested_tables_merge() {
+        let mut base = table(
+            r#"
+            [actor]
+            name = "skeleton"
+            [actor.stats]
+            max_hp = 100.0
+            speed = 2.0
+            "#,
+        );
+        merge_toml_tables(&mut base, &table("actor.stats.max_hp = 200.0"));
+
+        assert_eq!(
+            base,
+            table(
+                r#"
+                [actor]
+                name = "skeleton"
+                [actor.stats]
+                max_hp = 200.0
+                speed = 2.0
+                "#,
+            )
+        );
+    }
+
+    /// changing an enum variant replaces the whole variant table
+    #[test]
+    fn variant_swap_replaces() {
+        let mut base = table("unique_data.Item.Weapon = { damage = 1 }");
+        merge_toml_tables(
+            &mut base,
+            &table("unique_data.Character.Creep = { speed = 3 }"),
+        );
+
+        assert_eq!(base, table("unique_data.Character.Creep = { speed = 3 }"));
+    }
+
+    /// patching the same variant merges its fields
+    #[test]
+    fn same_variant_merges() {
+        let mut base = table("unique_data.Item = { damage = 1, range = 4 }");
+        merge_toml_tables(&mut base, &table("unique_data.Item = { damage = 2 }"));
+
+        assert_eq!(base, table("unique_data.Item = { damage = 2, range = 4 }"));
+    }
+
+    /// single field structs are merged, not mistaken for a variant change
+    #[test]
+    fn one_field_struct_merges() {
+        let mut base = table("stats = { speed = 1 }");
+        merge_toml_tables(&mut base, &table("stats = { health = 2 }"));
+
+        assert_eq!(base, table("stats = { speed = 1, health = 2 }"));
+    }
+
+    /// `DefinitionPatch::apply`, used by `resolve_definitions`, merges over a typed definition
+    #[test]
+    fn patch_applies_to_definition() {
+        /// one field struct like `render_settings`
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Speed {
+            /// movement speed
+            speed: f32,
+        }
+        /// definition with a nested one field struct
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Definition {
+            /// actor name
+            name: String,
+            /// nested stats
+            stats: Speed,
+        }
+
+        let definition = Definition {
+            name: "skeleton".to_string(),
+            stats: Speed { speed: 1.0 },
+        };
+        let patch = DefinitionPatch::from(table("stats.speed = 4.0"));
+
+        assert_eq!(
+            patch.apply(&definition).expect("patch applies"),
+            Definition {
+                name: "skeleton".to_string(),
+                stats: Speed { speed: 4.0 },
+            }
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::loading::registry::{ActorRegistry, RegistryIdentifier};
+use crate::loading::registry::{ActorBundle, ActorRegistry, RegistryIdentifier};
 use std::fmt::Debug;
 
 impl From<String> for RegistryIdentifier {
@@ -9,25 +9,23 @@ impl From<String> for RegistryIdentifier {
 
 impl Debug for ActorRegistry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let vec_creep = &self
-            .characters
-            .creeps
-            .keys()
-            .collect::<Vec<&RegistryIdentifier>>();
-        let vec_heroes = &self
-            .characters
-            .heroes
-            .keys()
-            .collect::<Vec<&RegistryIdentifier>>();
-        let vec_items = &self
-            .items
-            .weapons
-            .keys()
-            .collect::<Vec<&RegistryIdentifier>>();
+        let mut vec_characters: Vec<&RegistryIdentifier> = Vec::new();
+        let mut vec_items: Vec<&RegistryIdentifier> = Vec::new();
+        for (identifier, entry) in &self.actors {
+            match entry.bundle {
+                ActorBundle::Character(_) => vec_characters.push(identifier),
+                ActorBundle::Weapon(_) | ActorBundle::Item(_) => vec_items.push(identifier),
+            }
+        }
+        let sources = self
+            .actors
+            .iter()
+            .map(|(identifier, entry)| (identifier, &entry.source.pack))
+            .collect::<Vec<(&RegistryIdentifier, &String)>>();
         f.debug_struct("ActorRegistry")
-            .field("creeps", vec_creep)
-            .field("heroes", vec_heroes)
-            .field("weapons", vec_items)
+            .field("characters", &vec_characters)
+            .field("items", &vec_items)
+            .field("sources", &sources)
             .finish()
     }
 }
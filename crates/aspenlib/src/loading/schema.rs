@@ -0,0 +1,53 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use schemars::{schema::RootSchema, schema_for};
+
+use crate::{
+    game::{
+        attributes_stats::Attributes, characters::ai::components::AiType,
+        items::weapons::components::WeaponDescriptor,
+    },
+    loading::custom_assets::actor_definitions::{
+        ActorDefinition, CharacterDefinition, ItemDefinition,
+    },
+};
+
+/// folder schemas are written too if no folder is given
+pub const DEFAULT_SCHEMA_DIR: &str = "assets/schemas";
+
+/// json schemas for definition files and the types they use,
+/// paired with the file name each schema is written too
+pub fn definition_schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("actor.schema.json", schema_for!(ActorDefinition)),
+        ("character.schema.json", schema_for!(CharacterDefinition)),
+        ("weapon.schema.json", schema_for!(ItemDefinition)),
+        ("attributes.schema.json", schema_for!(Attributes)),
+        (
+            "weapon_descriptor.schema.json",
+            schema_for!(WeaponDescriptor),
+        ),
+        ("ai_type.schema.json", schema_for!(AiType)),
+    ]
+}
+
+/// writes every schema from `definition_schemas` into `dir`
+///
+/// # Errors
+/// returns an error if `dir` cant be created or a schema cant be written
+pub fn write_definition_schemas(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("could not create {}: {e}", dir.display()))?;
+
+    let mut written = Vec::new();
+    for (file_name, schema) in definition_schemas() {
+        let path = dir.join(file_name);
+        let json = serde_json::to_string_pretty(&schema)
+            .map_err(|e| format!("could not serialize {file_name}: {e}"))?;
+        fs::write(&path, json).map_err(|e| format!("could not write {}: {e}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
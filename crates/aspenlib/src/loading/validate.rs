@@ -0,0 +1,357 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::utils::HashMap;
+use bevy_asset_loader::prelude::{StandardDynamicAsset, StandardDynamicAssetCollection};
+use bevy_ecs_ldtk::ldtk::{LdtkJson, Level};
+
+use crate::{
+    game::game_world::dungeonator_v2::room_database::{
+        room_preset_from_level, validate_project_defs,
+    },
+    loading::{
+        assets::PACK_REQUIRED_KEYS,
+        custom_assets::{
+            actor_definitions::{parse_actor_definition, ACTOR_DEFINITION_EXTENSIONS},
+            floor_definitions::parse_floor_definition,
+        },
+        packs::{registry_asset_paths, PackManifest, PACK_MANIFEST_FILE, PACK_REGISTRY_FILE},
+        registry::RegistryIdentifier,
+    },
+};
+
+/// results of checking a single pack folder
+#[derive(Debug, Default)]
+pub struct PackReport {
+    /// pack folder that was checked
+    pub pack_dir: PathBuf,
+    /// problems that will break loading the pack
+    pub errors: Vec<String>,
+    /// problems that probably arent intended
+    pub warnings: Vec<String>,
+    /// how many files were checked
+    pub files_checked: usize,
+}
+
+impl PackReport {
+    /// true if no errors were found
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// adds an error for `path` too the report
+    fn error(&mut self, path: &Path, msg: impl Display) {
+        self.errors.push(format!("{}: {msg}", path.display()));
+    }
+
+    /// adds a warning for `path` too the report
+    fn warn(&mut self, path: &Path, msg: impl Display) {
+        self.warnings.push(format!("{}: {msg}", path.display()));
+    }
+}
+
+impl Display for PackReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "pack: {}", self.pack_dir.display())?;
+        writeln!(f, "files checked: {}", self.files_checked)?;
+        for warning in &self.warnings {
+            writeln!(f, "  warning: {warning}")?;
+        }
+        for error in &self.errors {
+            writeln!(f, "  error: {error}")?;
+        }
+        if self.is_ok() {
+            write!(f, "ok: {} warnings", self.warnings.len())
+        } else {
+            write!(
+                f,
+                "failed: {} errors, {} warnings",
+                self.errors.len(),
+                self.warnings.len()
+            )
+        }
+    }
+}
+
+/// checks a pack folder without starting the game.
+///
+/// `pack_dir` is expected too be `assets/packs/$PACK`, registry paths are
+/// resolved from the `assets` folder two levels up
+pub fn validate_pack(pack_dir: &Path) -> PackReport {
+    let mut report = PackReport {
+        pack_dir: pack_dir.to_path_buf(),
+        ..Default::default()
+    };
+    if !pack_dir.is_dir() {
+        report.error(pack_dir, "pack folder does not exist");
+        return report;
+    }
+    let assets_root = pack_dir
+        .parent()
+        .and_then(Path::parent)
+        .unwrap_or_else(|| Path::new("."));
+
+    let manifest = check_manifest(&mut report, pack_dir);
+    let registry = check_registry(&mut report, pack_dir, assets_root, manifest.as_ref());
+    check_definitions(&mut report, pack_dir, assets_root, registry.as_ref());
+
+    if let Some(StandardDynamicAsset::File { path }) =
+        registry.as_ref().and_then(|f| f.get("floor_definitions"))
+    {
+        check_floor_definition(&mut report, &assets_root.join(path));
+    }
+
+    if let Some(StandardDynamicAsset::File { path }) =
+        registry.as_ref().and_then(|f| f.get("default_levels"))
+    {
+        check_ldtk_project(&mut report, &assets_root.join(path));
+    }
+
+    report
+}
+
+/// reads and parses a file, adding an error too the report if either fails
+fn read_parsed<T>(
+    report: &mut PackReport,
+    path: &Path,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> Option<T> {
+    report.files_checked += 1;
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            report.error(path, format!("could not read file: {e}"));
+            return None;
+        }
+    };
+    match parse(&text) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            report.error(path, e);
+            None
+        }
+    }
+}
+
+/// parses the packs manifest
+fn check_manifest(report: &mut PackReport, pack_dir: &Path) -> Option<PackManifest> {
+    let path = pack_dir.join(PACK_MANIFEST_FILE);
+    let manifest: PackManifest = read_parsed(report, &path, |text| {
+        ron::from_str(text).map_err(|e| e.to_string())
+    })?;
+
+    let folder_name = pack_dir.file_name().map(|f| f.to_string_lossy());
+    if folder_name.as_deref() != Some(manifest.name.as_str()) {
+        report.warn(
+            &path,
+            format!(
+                "manifest name '{}' does not match pack folder",
+                manifest.name
+            ),
+        );
+    }
+    Some(manifest)
+}
+
+/// parses the packs registry, checks required keys and that every listed path exists
+fn check_registry(
+    report: &mut PackReport,
+    pack_dir: &Path,
+    assets_root: &Path,
+    manifest: Option<&PackManifest>,
+) -> Option<HashMap<String, StandardDynamicAsset>> {
+    let path = pack_dir.join(PACK_REGISTRY_FILE);
+    let registry: StandardDynamicAssetCollection = read_parsed(report, &path, |text| {
+        ron::from_str(text).map_err(|e| e.to_string())
+    })?;
+    let registry = registry.0;
+
+    let has_dependencies = manifest.is_some_and(|f| !f.dependencies.is_empty());
+    for key in PACK_REQUIRED_KEYS {
+        if registry.contains_key(*key) {
+            continue;
+        }
+        if has_dependencies {
+            report.warn(
+                &path,
+                format!("key '{key}' is missing, it must come from a dependency"),
+            );
+        } else {
+            report.error(&path, format!("required key '{key}' is missing"));
+        }
+    }
+
+    for (key, asset) in &registry {
+        for asset_path in registry_asset_paths(asset) {
+            if !assets_root.join(asset_path).exists() {
+                report.error(
+                    &path,
+                    format!("key '{key}' points too missing file {asset_path}"),
+                );
+            }
+        }
+    }
+
+    Some(registry)
+}
+
+/// parses every actor definition in the pack
+fn check_definitions(
+    report: &mut PackReport,
+    pack_dir: &Path,
+    assets_root: &Path,
+    registry: Option<&HashMap<String, StandardDynamicAsset>>,
+) {
+    let listed: Vec<PathBuf> = [
+        "character_definitions",
+        "item_definitions",
+        "actor_definitions",
+    ]
+    .iter()
+    .filter_map(|key| registry?.get(*key))
+    .filter_map(|asset| match asset {
+        StandardDynamicAsset::Files { paths } => Some(paths),
+        _ => None,
+    })
+    .flatten()
+    .map(|f| assets_root.join(f))
+    .collect();
+
+    let mut identifiers: HashMap<RegistryIdentifier, PathBuf> = HashMap::new();
+    for path in files_in(pack_dir) {
+        let file_name = path
+            .file_name()
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if !ACTOR_DEFINITION_EXTENSIONS
+            .iter()
+            .any(|extension| file_name.ends_with(&format!(".{extension}")))
+        {
+            continue;
+        }
+        let parsed = read_parsed(report, &path, |text| {
+            parse_actor_definition(&file_name, text)
+        })
+        .map(|def| (def.actor.identifier, def.actor.aseprite_path));
+
+        let Some((identifier, aseprite_path)) = parsed else {
+            continue;
+        };
+
+        let folder = path.parent().unwrap_or(pack_dir);
+        if !folder.join(&aseprite_path).exists() {
+            report.error(
+                &path,
+                format!("aseprite_path '{aseprite_path}' does not exist"),
+            );
+        }
+        if registry.is_some() && !listed.iter().any(|f| f == &path) {
+            report.warn(
+                &path,
+                "definition is not listed in pack.registry and wont load",
+            );
+        }
+        if let Some(first) = identifiers.get(&identifier) {
+            report.error(
+                &path,
+                format!(
+                    "identifier {:?} is already used by {}",
+                    identifier,
+                    first.display()
+                ),
+            );
+        } else {
+            identifiers.insert(identifier, path);
+        }
+    }
+}
+
+/// parses the packs floor definition and checks each level is configured once
+/// with a usable `hallway_loop_chance`
+fn check_floor_definition(report: &mut PackReport, path: &Path) {
+    let file_name = path
+        .file_name()
+        .map(|f| f.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Some(definition) = read_parsed(report, path, |text| {
+        parse_floor_definition(&file_name, text)
+    }) else {
+        return;
+    };
+
+    for (i, floor) in definition.floors.iter().enumerate() {
+        if definition.floors[..i]
+            .iter()
+            .any(|f| f.level == floor.level)
+        {
+            report.error(
+                path,
+                format!("level {:?} is defined more than once", floor.level),
+            );
+        }
+        if !(0.0..=1.0).contains(&floor.hallway_loop_chance) {
+            report.error(
+                path,
+                format!(
+                    "level {:?} hallway_loop_chance {} is not between 0 and 1",
+                    floor.level, floor.hallway_loop_chance
+                ),
+            );
+        }
+    }
+}
+
+/// runs the dungeon room checks from `build_room_presets` on an ldtk project
+fn check_ldtk_project(report: &mut PackReport, project_path: &Path) {
+    let Some(project) = read_parsed(report, project_path, |text| {
+        serde_json::from_str::<LdtkJson>(text).map_err(|e| e.to_string())
+    }) else {
+        return;
+    };
+
+    if let Err(e) = validate_project_defs(&project) {
+        report.error(project_path, e);
+    }
+
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    for level in &project.levels {
+        let level = match &level.external_rel_path {
+            Some(rel_path) => {
+                let level_path = project_dir.join(rel_path);
+                let Some(level) = read_parsed(report, &level_path, |text| {
+                    serde_json::from_str::<Level>(text).map_err(|e| e.to_string())
+                }) else {
+                    continue;
+                };
+                level
+            }
+            None => level.clone(),
+        };
+
+        if let Err(e) = room_preset_from_level(&level) {
+            report.error(project_path, e);
+        }
+    }
+}
+
+/// recursively lists files in `dir`, sorted so reports are stable
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(files_in(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files
+}
@@ -0,0 +1,167 @@
+//! property tests for `generate_layout`, run over many seeds, map sizes and room distributions
+
+use aspenlib::dungeon_generator::{
+    generate_layout, DungeonRoomDatabase, DungeonSettings, GeneratorError, RoomDescriptor,
+    RoomDistribution, RoomLevel, RoomPreset, RoomShape, RoomType,
+};
+use bevy::math::IVec2;
+use bevy_ecs_tilemap::prelude::TilemapSize;
+use proptest::prelude::*;
+
+/// px size of 1 tile
+const TILE: i32 = 32;
+
+/// room preset `tiles` big with an exit tile in the middle of each side in `sides`
+///
+/// sides are ordered bottom, top, left, right
+fn test_room(
+    name: &str,
+    rtype: RoomType,
+    shape: RoomShape,
+    tiles: IVec2,
+    sides: usize,
+) -> RoomPreset {
+    let size = tiles * TILE;
+    let middle = tiles / 2 * TILE;
+    let exits = [
+        IVec2::new(middle.x, 0),
+        IVec2::new(middle.x, size.y - TILE),
+        IVec2::new(0, middle.y),
+        IVec2::new(size.x - TILE, middle.y),
+    ]
+    .into_iter()
+    .take(sides)
+    .collect();
+
+    RoomPreset::headless(
+        name,
+        RoomDescriptor {
+            shape,
+            level: RoomLevel::Level1,
+            rtype,
+        },
+        size,
+        exits,
+    )
+}
+
+/// room database with scaled down rooms for each class the generator picks from
+fn test_database() -> DungeonRoomDatabase {
+    use RoomShape as S;
+    use RoomType as T;
+
+    let mut database = DungeonRoomDatabase::default();
+    let rooms = [
+        ("start", T::DungeonStart, S::NonStandard, (7, 7), 4),
+        ("end", T::DungeonEnd, S::NonStandard, (10, 10), 1),
+        ("treasure", T::Special, S::NonStandard, (6, 6), 1),
+        ("small_short", T::Normal, S::SmallShort, (8, 8), 4),
+        ("small_long", T::Normal, S::SmallLong, (8, 16), 3),
+        ("medium_short", T::Normal, S::MediumShort, (16, 16), 4),
+        ("medium_long", T::Normal, S::MediumLong, (16, 32), 2),
+        ("large_short", T::Normal, S::LargeShort, (24, 24), 4),
+        ("large_long", T::Normal, S::LargeLong, (24, 32), 4),
+    ];
+
+    for (name, rtype, shape, (x, y), sides) in rooms {
+        database.insert(test_room(name, rtype, shape, IVec2 { x, y }, sides));
+    }
+    database
+}
+
+/// a database without start or end rooms is an error instead of a panic
+#[test]
+fn missing_start_or_end_room_is_an_error() {
+    let settings = DungeonSettings::default();
+
+    let mut database = test_database();
+    database.start_rooms.clear();
+    assert_eq!(
+        generate_layout(&settings, &database, 0).map(|_| ()),
+        Err(GeneratorError::MissingStartRoom(RoomLevel::Level1))
+    );
+
+    let mut database = test_database();
+    database.end_rooms.clear();
+    assert_eq!(
+        generate_layout(&settings, &database, 0).map(|_| ()),
+        Err(GeneratorError::MissingEndRoom(RoomLevel::Level1))
+    );
+}
+
+/// a NaN `hallway_loop_chance` adds no loops instead of panicking
+#[test]
+fn nan_loop_chance_does_not_panic() {
+    let settings = DungeonSettings {
+        size: TilemapSize { x: 64, y: 64 },
+        hallway_loop_chance: f32::NAN,
+        ..Default::default()
+    };
+
+    let layout = generate_layout(&settings, &test_database(), 0)
+        .expect("test database has start and end rooms");
+    assert_eq!(layout.verify(), Ok(()));
+}
+
+prop_compose! {
+    /// room counts for each room class, huge rooms are left out of the test database
+    fn distribution()(counts in prop::array::uniform7(0..4i32)) -> RoomDistribution {
+        RoomDistribution {
+            small_short: counts[0],
+            small_long: counts[1],
+            medium_short: counts[2],
+            medium_long: counts[3],
+            large_short: counts[4],
+            large_long: counts[5],
+            huge_short: 0,
+            huge_long: 0,
+            special: counts[6],
+        }
+    }
+}
+
+prop_compose! {
+    /// generator settings with a random placement area, border, room distribution and loops
+    fn settings()(
+        size in (0..96u32, 0..96u32),
+        border in 0..6u32,
+        distribution in distribution(),
+        hallway_loop_chance in 0.0..=1.0f32,
+        max_loop_length in prop::option::of(0..64u32),
+    ) -> DungeonSettings {
+        DungeonSettings {
+            border,
+            size: TilemapSize { x: size.0, y: size.1 },
+            distribution,
+            hallway_loop_chance,
+            max_loop_length,
+            ..Default::default()
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /// rooms dont overlap, the start and end room exist, hallways path between 2 exits
+    /// without crossing rooms, loops dont cross hallways and every room is reachable
+    #[test]
+    fn layouts_keep_invariants(seed in any::<u64>(), settings in settings()) {
+        let layout = generate_layout(&settings, &test_database(), seed).expect("test database has start and end rooms");
+        prop_assert_eq!(layout.verify(), Ok(()));
+    }
+
+    /// the same settings and seed always give the same rooms and hallway paths
+    #[test]
+    fn layouts_are_deterministic(seed in any::<u64>(), settings in settings()) {
+        let database = test_database();
+        let first = generate_layout(&settings, &database, seed).expect("test database has start and end rooms");
+        let second = generate_layout(&settings, &database, seed).expect("test database has start and end rooms");
+
+        prop_assert_eq!(&first.rooms, &second.rooms);
+        prop_assert_eq!(
+            first.hallways.iter().map(|f| &f.node_path).collect::<Vec<_>>(),
+            second.hallways.iter().map(|f| &f.node_path).collect::<Vec<_>>()
+        );
+    }
+}
@@ -2,17 +2,81 @@
 #![doc = r"
     Aspen Halls native launcher, deals with loading the configuration.
     After valid configuration is found/created, starts bevy app
+
+    command line flags override config values for one session,
+    run with `--help` for the full list
 "]
 
 use aspenlib::{
-    save_load::save_settings, ConfigFile, GeneralSettings, RenderSettings, SoundSettings,
-    WindowSettings,
+    launch::{parse_resolution, LaunchOptions},
+    save_load::{load_config, ConfigStorage},
+    ConfigFile, GameDifficulty,
 };
 use bevy::{log::info, math::Vec2};
-use std::path::Path;
+use clap::Parser;
+use std::path::{Path, PathBuf};
 
+/// config location used before settings moved too the platform config dir,
 /// this translates too same folder as executable
-pub const APP_SETTINGS_PATH: &str = "./config.toml";
+pub const LEGACY_SETTINGS_PATH: &str = "./config.toml";
+
+/// Aspen Halls desktop launcher, flags only apply too this session
+#[derive(Debug, Parser)]
+#[command(name = "aspen_halls", version)]
+struct LaunchArgs {
+    /// config file too load and save instead of the platform config dir
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// pack folder too load from `assets/packs`, repeat for more packs
+    #[arg(long = "pack", value_name = "NAME")]
+    packs: Vec<String>,
+    /// log filter like `info,wgpu=error`
+    #[arg(long, value_name = "FILTER")]
+    log_filter: Option<String>,
+    /// start in a window
+    #[arg(long, conflicts_with = "fullscreen")]
+    windowed: bool,
+    /// start borderless fullscreen
+    #[arg(long)]
+    fullscreen: bool,
+    /// window size like `1280x720`
+    #[arg(long, value_name = "WxH", value_parser = parse_resolution)]
+    resolution: Option<Vec2>,
+    /// dungeon generation seed
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+    /// easy, medium, hard, insane, megadeath or debug
+    #[arg(long, value_name = "DIFFICULTY")]
+    difficulty: Option<GameDifficulty>,
+    /// skip the start menu and hideout, starts a dungeon as `--hero`
+    #[arg(long, requires = "hero")]
+    skip_menu: bool,
+    /// hero registry id too play as with `--skip-menu`
+    #[arg(long, value_name = "ID", requires = "skip_menu")]
+    hero: Option<String>,
+}
+
+impl LaunchArgs {
+    /// overrides passed too the game for this session
+    fn launch_options(self) -> LaunchOptions {
+        LaunchOptions {
+            config_storage: self.config.map(ConfigStorage::File),
+            enabled_packs: (!self.packs.is_empty()).then_some(self.packs),
+            log_filter: self.log_filter,
+            full_screen: if self.fullscreen {
+                Some(true)
+            } else if self.windowed {
+                Some(false)
+            } else {
+                None
+            },
+            resolution: self.resolution,
+            difficulty: self.difficulty,
+            seed: self.seed,
+            skip_menu_hero: self.hero.filter(|_| self.skip_menu),
+        }
+    }
+}
 
 fn main() {
     // check AppDir for config file, if no cfg, first run, guess settings based on hardware
@@ -24,75 +88,39 @@ fn main() {
             .support("- Open a support request on github")
     );
 
+    let launch = LaunchArgs::parse().launch_options();
+
     info!("Starting launcher: Native");
-    let cfg_file: ConfigFile = load_settings();
-    aspenlib::start_app(cfg_file).run();
+    let cfg_file: ConfigFile = launch
+        .config_storage
+        .as_ref()
+        .map_or_else(load_settings, |storage| {
+            load_config(storage, ConfigFile::default())
+        });
+    aspenlib::start_app(cfg_file, launch).run();
 }
 
-/// loads app settings from `consts::APP_SETTINGS_PATH` and returns a boxed config file
+/// loads app settings from `ConfigStorage::platform_default`
 ///
-/// # Panics
-/// This will panic if the config file
+/// config files from `LEGACY_SETTINGS_PATH` are copied too the platform config dir once
 pub fn load_settings() -> ConfigFile {
-    let settings_path = Path::new(APP_SETTINGS_PATH);
-    info!("loading config file from filesystem @ {:?}", settings_path);
-    let target_settings = match std::fs::read_to_string(settings_path) {
-        // if settings file cant be read cause it doesn't exist, no permissions, or other
-        Err(error) => {
-            eprintln!(
-                "There was an error: {} accessing settings file at: {}",
-                error,
-                settings_path.display()
-            );
-            let new_cfg = ConfigFile::default();
-            save_settings(&new_cfg, settings_path);
-            toml::to_string_pretty(&new_cfg).unwrap()
-        }
-        // if settings file can be read
-        Ok(target_settings) => target_settings,
-    };
+    let storage = ConfigStorage::platform_default();
 
-    match toml::from_str::<ConfigFile>(target_settings.as_str()) {
-        // if malformed settings file, create default
-        Err(error) => {
-            eprintln!(
-                "The app config file is malformed: {} \n this file: {}",
-                error,
-                settings_path.display()
+    if let ConfigStorage::File(settings_path) = &storage {
+        let legacy_path = Path::new(LEGACY_SETTINGS_PATH);
+        if !settings_path.exists() && legacy_path.exists() {
+            info!(
+                "moving config file from {:?} too {:?}",
+                legacy_path, settings_path
             );
-            ConfigFile::default()
-        }
-        // setting file is not malformed, can be loaded
-        Ok(cfg) => {
-            println!("Game Settings loaded from file successfully");
-            ConfigFile {
-                window_settings: WindowSettings {
-                    resolution: Vec2 {
-                        x: cfg.window_settings.resolution.x,
-                        y: cfg.window_settings.resolution.y,
-                    },
-                    v_sync: cfg.window_settings.v_sync,
-                    frame_rate_target: cfg.window_settings.frame_rate_target,
-                    full_screen: cfg.window_settings.full_screen,
-                    window_scale_override: cfg.window_settings.window_scale_override,
-                },
-
-                sound_settings: SoundSettings {
-                    master_volume: cfg.sound_settings.master_volume,
-                    ambience_volume: cfg.sound_settings.ambience_volume,
-                    music_volume: cfg.sound_settings.music_volume,
-                    sound_volume: cfg.sound_settings.sound_volume,
-                },
-
-                general_settings: GeneralSettings {
-                    camera_zoom: cfg.general_settings.camera_zoom,
-                    game_difficulty: cfg.general_settings.game_difficulty,
-                },
-                render_settings: RenderSettings {
-                    msaa: cfg.render_settings.msaa,
-                },
-                log_filter: cfg.log_filter,
+            if let Some(parent) = settings_path.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            if let Err(e) = std::fs::copy(legacy_path, settings_path) {
+                eprintln!("Couldn't copy old config file: {e}");
             }
         }
     }
+
+    load_config(&storage, ConfigFile::default())
 }
@@ -4,14 +4,19 @@
 "]
 
 use aspenlib::{
-    ConfigFile, GameDifficulty, GeneralSettings, RenderSettings, SoundSettings, WindowSettings,
+    launch::LaunchOptions,
+    save_load::{load_config, ConfigStorage},
+    ConfigFile, ControlSettings, GameDifficulty, GeneralSettings, RenderSettings, SoundSettings,
+    WindowSettings,
 };
 use bevy::{math::Vec2, prelude::bevy_main};
 
 #[bevy_main]
 fn main() {
-    let config = ConfigFile {
+    let defaults = ConfigFile {
         log_filter: Some("Info,wgpu=error,naga=error".to_string()),
+        enabled_packs: vec!["asha".to_string()],
+        config_version: aspenlib::migrate::CONFIG_VERSION,
         window_settings: WindowSettings {
             v_sync: true,
             frame_rate_target: 144.0,
@@ -33,8 +38,11 @@ fn main() {
             game_difficulty: GameDifficulty::Medium,
         },
         render_settings: RenderSettings { msaa: false },
+        control_settings: ControlSettings::default(),
+        active_save_slot: 0,
     };
 
     println!("Starting launcher: Mobile");
-    aspenlib::start_app(config).run();
+    let config = load_config(&ConfigStorage::platform_default(), defaults);
+    aspenlib::start_app(config, LaunchOptions::default()).run();
 }
@@ -34,8 +34,10 @@ fn main() {
     yew::Renderer::<Root>::new().render();
     // Start the Bevy App
     info!("Starting launcher: WASM");
-    let cfg_file = ConfigFile {
+    let defaults = ConfigFile {
         log_filter: Some("Info,wgpu=error,naga=error".to_string()),
+        enabled_packs: vec!["asha".to_string()],
+        config_version: aspenlib::migrate::CONFIG_VERSION,
         window_settings: WindowSettings {
             v_sync: true,
             frame_rate_target: 60.0,
@@ -54,6 +56,9 @@ fn main() {
             game_difficulty: GameDifficulty::Easy,
         },
         render_settings: RenderSettings { msaa: false },
+        control_settings: ControlSettings::default(),
+        active_save_slot: 0,
     };
-    aspenlib::start_app(cfg_file).run();
+    let cfg_file = save_load::load_config(&save_load::ConfigStorage::platform_default(), defaults);
+    aspenlib::start_app(cfg_file, launch::LaunchOptions::default()).run();
 }
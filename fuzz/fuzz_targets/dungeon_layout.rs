@@ -0,0 +1,158 @@
+#![no_main]
+#![doc = r"
+    feeds random `DungeonSettings` and synthetic room databases too `generate_layout`
+    and fails if the layout panics or breaks `DungeonLayout::verify`.
+
+    usage: `cargo fuzz run dungeon_layout`
+"]
+
+use arbitrary::Arbitrary;
+use aspenlib::dungeon_generator::{
+    generate_layout, DungeonRoomDatabase, DungeonSettings, RoomDescriptor, RoomDistribution,
+    RoomLevel, RoomPreset, RoomShape, RoomType,
+};
+use bevy::math::IVec2;
+use bevy_ecs_tilemap::prelude::TilemapSize;
+use libfuzzer_sys::fuzz_target;
+
+/// px size of 1 tile
+const TILE: i32 = 32;
+
+/// shapes a filler room can be sorted into
+const SHAPES: [RoomShape; 9] = [
+    RoomShape::NonStandard,
+    RoomShape::SmallShort,
+    RoomShape::SmallLong,
+    RoomShape::MediumShort,
+    RoomShape::MediumLong,
+    RoomShape::LargeShort,
+    RoomShape::LargeLong,
+    RoomShape::HugeShort,
+    RoomShape::HugeLong,
+];
+
+/// synthetic room, sizes are in tiles
+#[derive(Debug, Arbitrary)]
+struct FuzzRoom {
+    /// index into `SHAPES`
+    shape: u8,
+    /// tiles wide, clamped too 3..=34
+    width: u8,
+    /// tiles tall, clamped too 3..=34
+    height: u8,
+    /// exit tiles as a side and a tile offset along that side
+    exits: Vec<(u8, u8)>,
+}
+
+/// everything the generator is given
+#[derive(Debug, Arbitrary)]
+struct FuzzInput {
+    /// layout seed
+    seed: u64,
+    /// placement area in tiles
+    size: (u8, u8),
+    /// tiles between rooms and around the map
+    border: u8,
+    /// room count for each `RoomDistribution` field
+    counts: [u8; 9],
+    /// loop chance out of 255
+    loop_chance: u8,
+    /// longest loop hallway in tiles
+    max_loop_length: Option<u8>,
+    /// start room preset
+    start: FuzzRoom,
+    /// end room preset
+    end: FuzzRoom,
+    /// filler room presets
+    fillers: Vec<FuzzRoom>,
+}
+
+impl FuzzRoom {
+    /// turns this into a preset, rooms always get at least 1 exit
+    /// because the generator can not connect rooms without exits
+    fn preset(&self, name: &str, rtype: RoomType) -> RoomPreset {
+        let tiles = IVec2::new(
+            i32::from(self.width % 32) + 3,
+            i32::from(self.height % 32) + 3,
+        );
+        let size = tiles * TILE;
+
+        let mut exits: Vec<IVec2> = self
+            .exits
+            .iter()
+            .take(8)
+            .map(|(side, offset)| {
+                let along_x = i32::from(*offset) % tiles.x * TILE;
+                let along_y = i32::from(*offset) % tiles.y * TILE;
+                match side % 4 {
+                    0 => IVec2::new(along_x, 0),
+                    1 => IVec2::new(along_x, size.y - TILE),
+                    2 => IVec2::new(0, along_y),
+                    _ => IVec2::new(size.x - TILE, along_y),
+                }
+            })
+            .collect();
+        if exits.is_empty() {
+            exits.push(IVec2::new(tiles.x / 2 * TILE, 0));
+        }
+
+        RoomPreset::headless(
+            name,
+            RoomDescriptor {
+                shape: SHAPES[usize::from(self.shape) % SHAPES.len()].clone(),
+                level: RoomLevel::Level1,
+                rtype,
+            },
+            size,
+            exits,
+        )
+    }
+}
+
+impl FuzzInput {
+    /// generator settings, room counts are kept small so runs stay fast
+    fn settings(&self) -> DungeonSettings {
+        let counts = self.counts.map(|f| i32::from(f % 4));
+        DungeonSettings {
+            seed: self.seed,
+            border: u32::from(self.border % 8),
+            size: TilemapSize {
+                x: u32::from(self.size.0 % 128),
+                y: u32::from(self.size.1 % 128),
+            },
+            distribution: RoomDistribution {
+                small_short: counts[0],
+                small_long: counts[1],
+                medium_short: counts[2],
+                medium_long: counts[3],
+                large_short: counts[4],
+                large_long: counts[5],
+                huge_short: counts[6],
+                huge_long: counts[7],
+                special: counts[8],
+            },
+            hallway_loop_chance: f32::from(self.loop_chance) / 255.0,
+            max_loop_length: self.max_loop_length.map(u32::from),
+            ..Default::default()
+        }
+    }
+
+    /// room database with the start, end and filler presets
+    fn room_database(&self) -> DungeonRoomDatabase {
+        let mut database = DungeonRoomDatabase::default();
+        database.insert(self.start.preset("start", RoomType::DungeonStart));
+        database.insert(self.end.preset("end", RoomType::DungeonEnd));
+        for (i, filler) in self.fillers.iter().take(16).enumerate() {
+            database.insert(filler.preset(&format!("filler{i}"), RoomType::Normal));
+        }
+        database
+    }
+}
+
+fuzz_target!(|input: FuzzInput| {
+    let layout = generate_layout(&input.settings(), &input.room_database(), input.seed)
+        .expect("fuzz room databases always have start and end rooms");
+    if let Err(e) = layout.verify() {
+        panic!("{e}");
+    }
+});